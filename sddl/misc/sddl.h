@@ -76,6 +76,8 @@ extern "C" {
 #define SDDL_SCOPED_POLICY_ID               TEXT("SP")  // Scoped policy
 #define SDDL_CALLBACK_AUDIT                 TEXT("XU")  // Callback audit
 #define SDDL_CALLBACK_OBJECT_ACCESS_ALLOWED TEXT("ZA")  // Callback object access allowed
+#define SDDL_CALLBACK_OBJECT_ACCESS_DENIED  TEXT("ZD")  // Callback object access denied
+#define SDDL_CALLBACK_OBJECT_AUDIT          TEXT("ZU")  // Callback object audit
 #define SDDL_ACCESS_FILTER                  TEXT("FL")  // Access Filter
 
 //
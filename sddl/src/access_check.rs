@@ -0,0 +1,885 @@
+use getset::CopyGetters;
+use serde::Serialize;
+
+use crate::{
+    sddl_h::*, AccessMask, Ace, AceHeaderFlags, Acl, CentralAccessPolicy,
+    CentralAccessPolicyResolver, EvaluationContext, GenericMapping, ObjectTypeList, Privilege,
+    SecurityDescriptor, Sid, Token, Tristate,
+};
+
+/// Result of [`SecurityDescriptor::access_check`] for an object or an
+/// element of an [`ObjectTypeList`]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, CopyGetters, Serialize)]
+#[getset(get_copy = "pub")]
+pub struct AccessCheckResult {
+    /// the access rights which have been granted. If `MAXIMUM_ALLOWED` was
+    /// requested, this contains all rights the token is allowed to have.
+    granted_access: AccessMask,
+
+    /// `true` if all requested access rights have been granted. If
+    /// `MAXIMUM_ALLOWED` was requested, at least one right must be granted.
+    access_allowed: bool,
+
+    /// all access rights which would be granted if the staged DACLs of the
+    /// central access policy were effective, or `None` if no central access
+    /// policy has been applied
+    staged_granted_access: Option<AccessMask>,
+}
+
+impl AccessCheckResult {
+    fn allowed(granted_access: AccessMask) -> Self {
+        Self {
+            granted_access,
+            access_allowed: true,
+            staged_granted_access: None,
+        }
+    }
+
+    fn denied() -> Self {
+        Self {
+            granted_access: AccessMask::empty(),
+            access_allowed: false,
+            staged_granted_access: None,
+        }
+    }
+
+    fn with_staged(mut self, staged_granted_access: Option<AccessMask>) -> Self {
+        self.staged_granted_access = staged_granted_access;
+        self
+    }
+}
+
+/// the effect of an ACE in a DACL
+enum DaclEntry {
+    Allow,
+    Deny,
+}
+
+impl SecurityDescriptor {
+    /// checks if `token` is allowed to access an object which is protected by
+    /// this security descriptor, as specified in MS-DTYP 2.5.3.2. This
+    /// includes the mandatory integrity check against the label in the SACL.
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AI(A;;FR;;;BU)S:AI", None).unwrap();
+    /// let token = Token::new(Sid::try_from("S-1-5-21-1-2-3-1013").unwrap())
+    ///     .with_group(Sid::try_from("S-1-5-32-545").unwrap());
+    ///
+    /// let result = sd.access_check(&token, AccessMask::GENERIC_READ, &GenericMapping::FILE);
+    /// assert!(result.access_allowed());
+    ///
+    /// let result = sd.access_check(&token, AccessMask::GENERIC_WRITE, &GenericMapping::FILE);
+    /// assert!(!result.access_allowed());
+    /// ```
+    pub fn access_check(
+        &self,
+        token: &Token,
+        desired: AccessMask,
+        mapping: &GenericMapping,
+    ) -> AccessCheckResult {
+        self.check(token, desired, None, mapping, None)[0]
+    }
+
+    /// checks the access of `token` to every element of `object_types`, like
+    /// `AccessCheckByTypeResultList`, and returns one result per element.
+    ///
+    /// Object ACEs apply to the object type named by their GUID and to all
+    /// its descendants. Denied rights are also denied for all ancestors, and
+    /// rights which are granted to all children of an element are granted to
+    /// the element itself.
+    ///
+    /// Central access policies are not applied, see
+    /// [`SecurityDescriptor::access_check_by_type_with_policies`].
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let group = Guid::try_from("bf967a9c-0de6-11d0-a285-00aa003049e2").unwrap();
+    /// let membership = Guid::try_from("bc0ac240-79a9-11d0-9020-00c04fc2d4cf").unwrap();
+    /// let member = Guid::try_from("bf9679c0-0de6-11d0-a285-00aa003049e2").unwrap();
+    /// let general_information = Guid::try_from("59ba2f42-79a2-11d0-9020-00c04fc2d3cf").unwrap();
+    ///
+    /// let sd = SecurityDescriptor::from_sddl(
+    ///     "O:DAG:DAD:AI(OA;;WP;bf9679c0-0de6-11d0-a285-00aa003049e2;;AU)(A;;RP;;;AU)S:AI",
+    ///     Some(&[1, 2, 3])).unwrap();
+    /// let token = Token::new(Sid::try_from("S-1-5-21-1-2-3-1013").unwrap())
+    ///     .with_group(Sid::try_from("S-1-5-11").unwrap());
+    /// let list = ObjectTypeList::new(group)
+    ///     .with_property_set(membership)
+    ///     .with_property(member)
+    ///     .with_property_set(general_information);
+    ///
+    /// let results =
+    ///     sd.access_check_by_type_result_list(&token, AccessMask::WRITE_PROPERTY, &list, &GenericMapping::DS_OBJECT);
+    /// let allowed: Vec<_> = results.iter().map(|r| r.access_allowed()).collect();
+    /// assert_eq!(allowed, vec![false, true, true, false]);
+    /// ```
+    pub fn access_check_by_type_result_list(
+        &self,
+        token: &Token,
+        desired: AccessMask,
+        object_types: &ObjectTypeList,
+        mapping: &GenericMapping,
+    ) -> Vec<AccessCheckResult> {
+        self.check(token, desired, Some(object_types), mapping, None)
+    }
+
+    /// checks the access of `token` to every element of `object_types` like
+    /// [`SecurityDescriptor::access_check_by_type_result_list`], and limits
+    /// the access to each element by the central access policy of the object
+    /// like [`SecurityDescriptor::access_check_with_policies`]
+    pub fn access_check_by_type_with_policies(
+        &self,
+        token: &Token,
+        desired: AccessMask,
+        object_types: &ObjectTypeList,
+        mapping: &GenericMapping,
+        policies: &dyn CentralAccessPolicyResolver,
+    ) -> Vec<AccessCheckResult> {
+        self.check(token, desired, Some(object_types), mapping, Some(policies))
+    }
+
+    /// checks the access of `token` like [`SecurityDescriptor::access_check`],
+    /// and additionally applies the central access policy which is referenced
+    /// by a `SYSTEM_SCOPED_POLICY_ID_ACE` in the SACL. The access is limited
+    /// to the rights which are granted by the effective DACLs of all rules
+    /// which apply to the object, as specified in MS-DTYP 2.5.3.2. The result
+    /// also contains the access which the staged DACLs would grant.
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let policies = vec![CentralAccessPolicy::new("Finance", Sid::try_from("S-1-17-1").unwrap())
+    ///     .with_rule(CentralAccessRule::from_sddl(
+    ///         "Finance Documents",
+    ///         None,
+    ///         "D:(A;;FR;;;AU)",
+    ///         Some("D:(A;;FA;;;AU)"),
+    ///     ).unwrap())];
+    ///
+    /// let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:(A;;FA;;;AU)S:(SP;;;;;S-1-17-1)", None).unwrap();
+    /// let token = Token::new(Sid::try_from("S-1-5-21-1-2-3-1013").unwrap())
+    ///     .with_group(Sid::try_from("S-1-5-11").unwrap());
+    ///
+    /// let result = sd.access_check_with_policies(
+    ///     &token, AccessMask::GENERIC_WRITE, &GenericMapping::FILE, &policies);
+    /// assert!(!result.access_allowed());
+    /// assert!(result.staged_granted_access().unwrap().contains(GenericMapping::FILE.generic_write()));
+    /// ```
+    pub fn access_check_with_policies(
+        &self,
+        token: &Token,
+        desired: AccessMask,
+        mapping: &GenericMapping,
+        policies: &dyn CentralAccessPolicyResolver,
+    ) -> AccessCheckResult {
+        self.check(token, desired, None, mapping, Some(policies))[0]
+    }
+
+    /// checks the access to every element of `object_types`, or to the
+    /// object itself if no object types are given
+    fn check(
+        &self,
+        token: &Token,
+        desired: AccessMask,
+        object_types: Option<&ObjectTypeList>,
+        mapping: &GenericMapping,
+        policies: Option<&dyn CentralAccessPolicyResolver>,
+    ) -> Vec<AccessCheckResult> {
+        let nodes = object_types.map_or(1, |list| list.entries().len());
+        let max_allowed_mode = desired.contains(AccessMask::MAXIMUM_ALLOWED);
+        let desired = desired.map_generic(mapping) - AccessMask::MAXIMUM_ALLOWED;
+
+        // rights which are granted by privileges, regardless of the DACL
+        let mut privileged = AccessMask::empty();
+        if desired.contains(AccessMask::ACCESS_SYSTEM_SECURITY) {
+            if ![Privilege::Security, Privilege::Backup, Privilege::Restore]
+                .into_iter()
+                .any(|privilege| token.has_privilege(privilege))
+            {
+                return vec![AccessCheckResult::denied(); nodes];
+            }
+            privileged |= AccessMask::ACCESS_SYSTEM_SECURITY;
+        }
+        if token.has_privilege(Privilege::TakeOwnership)
+            && (max_allowed_mode || desired.contains(AccessMask::WRITE_OWNER))
+        {
+            privileged |= AccessMask::WRITE_OWNER;
+        }
+
+        // backup and restore operators may read and write every object, as
+        // if the caller had requested backup semantics
+        let requested = |rights: AccessMask| {
+            if max_allowed_mode {
+                rights
+            } else {
+                rights & desired
+            }
+        };
+        if token.has_privilege(Privilege::Backup) {
+            privileged |= requested(mapping.generic_read() | AccessMask::READ_CONTROL);
+        }
+        if token.has_privilege(Privilege::Restore) {
+            privileged |= requested(
+                mapping.generic_write()
+                    | AccessMask::WRITE_DACL
+                    | AccessMask::WRITE_OWNER
+                    | AccessMask::DELETE,
+            );
+        }
+
+        // mandatory integrity control limits the access, regardless of the
+        // DACL. Objects without a label are treated as medium integrity.
+        let mandatory = self
+            .integrity_label()
+            .unwrap_or_default()
+            .allowed_access(*token.integrity_level(), mapping);
+
+        let result = |granted: AccessMask, staged: Option<AccessMask>| {
+            let granted = (granted | privileged) & mandatory;
+            let staged = staged.map(|staged| (staged | privileged) & mandatory);
+            if !granted.contains(desired) || (max_allowed_mode && granted.is_empty()) {
+                AccessCheckResult::denied().with_staged(staged)
+            } else if max_allowed_mode {
+                AccessCheckResult::allowed(granted).with_staged(staged)
+            } else {
+                AccessCheckResult::allowed(desired).with_staged(staged)
+            }
+        };
+
+        // @Resource attributes in conditional ACEs refer to the resource
+        // attribute ACEs of the SACL
+        let ctx = self
+            .resource_attributes()
+            .into_iter()
+            .fold(token.evaluation_context(), |ctx, attribute| {
+                ctx.with_resource_attribute(attribute.name(), attribute.values().clone())
+            });
+
+        let evaluate = |dacl: &Acl| {
+            let mut granted = self.evaluate_dacl(
+                dacl,
+                object_types,
+                nodes,
+                mapping,
+                &ctx,
+                |sid, for_deny_ace| token.contains_sid(sid, for_deny_ace),
+            );
+
+            // a restricted token needs access for both its normal and its
+            // restricted SIDs
+            if token.is_restricted() {
+                let restricted = self.evaluate_dacl(
+                    dacl,
+                    object_types,
+                    nodes,
+                    mapping,
+                    &ctx,
+                    |sid, for_deny_ace| token.contains_restricted_sid(sid, for_deny_ace),
+                );
+                for (g, r) in granted.iter_mut().zip(restricted) {
+                    *g &= r;
+                }
+            }
+            granted
+        };
+
+        let granted = match self.dacl() {
+            Some(dacl) => evaluate(dacl),
+            // a NULL DACL grants full access to everyone
+            None => vec![mapping.generic_all() | desired; nodes],
+        };
+
+        let Some(capid) = policies.and(self.central_access_policy_id()) else {
+            return granted.into_iter().map(|g| result(g, None)).collect();
+        };
+
+        // the access is limited by every rule of the central access policy
+        // which applies to the object. Unknown policies are replaced by the
+        // recovery policy.
+        let recovery;
+        let policy = match policies.and_then(|policies| policies.resolve(capid)) {
+            Some(policy) => policy,
+            None => {
+                recovery = CentralAccessPolicy::recovery(capid.clone());
+                &recovery
+            }
+        };
+        let mut effective = granted.clone();
+        let mut staged = granted;
+        for rule in policy.rules() {
+            let applies = rule
+                .applies_to()
+                .as_ref()
+                .is_none_or(|condition| condition.evaluate(&ctx) == Tristate::True);
+            if !applies {
+                continue;
+            }
+
+            let rule_effective = evaluate(rule.effective_dacl());
+            let rule_staged = match rule.staged_dacl() {
+                Some(dacl) => evaluate(dacl),
+                None => rule_effective.clone(),
+            };
+            for (e, r) in effective.iter_mut().zip(rule_effective) {
+                *e &= r;
+            }
+            for (s, r) in staged.iter_mut().zip(rule_staged) {
+                *s &= r;
+            }
+        }
+
+        effective
+            .into_iter()
+            .zip(staged)
+            .map(|(e, s)| result(e, Some(s)))
+            .collect()
+    }
+
+    /// returns the ID of the central access policy which is referenced by the
+    /// first `SYSTEM_SCOPED_POLICY_ID_ACE` in the SACL, or `None` if the
+    /// object is not protected by a central access policy
+    pub fn central_access_policy_id(&self) -> Option<&Sid> {
+        self.sacl()
+            .as_ref()?
+            .ace_list()
+            .iter()
+            .filter(|ace| {
+                !ace.header()
+                    .ace_flags()
+                    .contains(AceHeaderFlags::INHERIT_ONLY_ACE)
+            })
+            .find_map(|ace| match ace {
+                Ace::SYSTEM_SCOPED_POLICY_ID_ACE { sid, .. } => Some(sid),
+                _ => None,
+            })
+    }
+
+    /// returns the access rights which the DACL grants to the SIDs matched by
+    /// `contains_sid`, for every node of the object type list
+    fn evaluate_dacl(
+        &self,
+        dacl: &Acl,
+        object_types: Option<&ObjectTypeList>,
+        nodes: usize,
+        mapping: &GenericMapping,
+        ctx: &EvaluationContext,
+        contains_sid: impl Fn(&Sid, bool) -> bool,
+    ) -> Vec<AccessMask> {
+        let is_owner = self
+            .owner()
+            .as_ref()
+            .is_some_and(|owner| contains_sid(owner, false));
+        let applicable_aces = || {
+            dacl.ace_list().iter().filter(|ace| {
+                !ace.header()
+                    .ace_flags()
+                    .contains(AceHeaderFlags::INHERIT_ONLY_ACE)
+            })
+        };
+
+        let mut allowed = vec![AccessMask::empty(); nodes];
+        let mut denied = vec![AccessMask::empty(); nodes];
+
+        // the owner is implicitly allowed to read and change the DACL,
+        // unless the DACL contains an ACE for OWNER RIGHTS
+        if is_owner && !applicable_aces().any(|ace| ace.trustee().is_some_and(is_owner_rights)) {
+            for a in allowed.iter_mut() {
+                *a |= AccessMask::READ_CONTROL | AccessMask::WRITE_DACL;
+            }
+        }
+
+        // once a right has been allowed, later deny ACEs cannot revoke it,
+        // and vice versa. This is equivalent to stopping at the first deny
+        // ACE which denies a requested right.
+        for ace in applicable_aces() {
+            let (Some(entry), Some(sid)) = (dacl_entry(ace), ace.trustee()) else {
+                continue;
+            };
+
+            let for_deny_ace = matches!(entry, DaclEntry::Deny);
+            let matches = if is_owner_rights(sid) {
+                is_owner
+            } else {
+                contains_sid(sid, for_deny_ace)
+            };
+            if !matches {
+                continue;
+            }
+
+            // conditional ACEs only apply if their condition is TRUE; deny
+            // ACEs also apply if their condition is UNKNOWN
+            let condition = match ace.condition() {
+                Ok(Some(expression)) => expression.evaluate(ctx),
+                Ok(None) => Tristate::True,
+                Err(_) => Tristate::Unknown,
+            };
+
+            let mask = ace.header().mask().map_generic(mapping);
+            match entry {
+                DaclEntry::Allow if condition == Tristate::True => {
+                    for i in affected_nodes(ace, &entry, object_types, nodes) {
+                        allowed[i] |= mask - denied[i];
+                    }
+                }
+                DaclEntry::Deny if condition != Tristate::False => {
+                    for i in affected_nodes(ace, &entry, object_types, nodes) {
+                        denied[i] |= mask - allowed[i];
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // rights which are granted to all children are granted to the parent
+        if let Some(list) = object_types {
+            for parent in (0..nodes).rev() {
+                let level = list.entries()[parent].level();
+                let inherited = list
+                    .subtree(parent)
+                    .skip(1)
+                    .filter(|child| list.entries()[*child].level() == level + 1)
+                    .map(|child| allowed[child])
+                    .reduce(|lhs, rhs| lhs & rhs);
+                if let Some(inherited) = inherited {
+                    allowed[parent] |= inherited - denied[parent];
+                }
+            }
+        }
+        allowed
+    }
+}
+
+fn is_owner_rights(sid: &Sid) -> bool {
+    *sid.alias() == Some(SDDL_OWNER_RIGHTS)
+}
+
+fn dacl_entry(ace: &Ace) -> Option<DaclEntry> {
+    match ace {
+        Ace::ACCESS_ALLOWED_ACE { .. }
+        | Ace::ACCESS_ALLOWED_CALLBACK_ACE { .. }
+        | Ace::ACCESS_ALLOWED_OBJECT_ACE { .. }
+        | Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE { .. } => Some(DaclEntry::Allow),
+        Ace::ACCESS_DENIED_ACE { .. }
+        | Ace::ACCESS_DENIED_CALLBACK_ACE { .. }
+        | Ace::ACCESS_DENIED_OBJECT_ACE { .. }
+        | Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE { .. } => Some(DaclEntry::Deny),
+        _ => None,
+    }
+}
+
+/// returns the indices of the nodes which are affected by an ACE. ACEs
+/// without object type apply to all nodes. Object ACEs only apply if their
+/// object type is part of the object type list.
+fn affected_nodes(
+    ace: &Ace,
+    entry: &DaclEntry,
+    object_types: Option<&ObjectTypeList>,
+    nodes: usize,
+) -> Vec<usize> {
+    let Some(object_type) = ace.object_type() else {
+        return (0..nodes).collect();
+    };
+    let Some(list) = object_types else {
+        return Vec::new();
+    };
+    let Some(index) = list.position(object_type) else {
+        return Vec::new();
+    };
+
+    let mut affected: Vec<_> = list.subtree(index).collect();
+    if matches!(entry, DaclEntry::Deny) {
+        affected.extend(list.ancestors(index));
+    }
+    affected
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AccessMask, CentralAccessPolicy, CentralAccessPolicyResolver, CentralAccessRule,
+        GenericMapping, GroupAttributes, Guid, IntegrityLevel, ObjectTypeList, Privilege,
+        SecurityDescriptor, Sid, Token,
+    };
+
+    fn user() -> Token {
+        Token::new(Sid::try_from("S-1-5-21-2623811015-3361044348-130300820-1013").unwrap())
+            .with_group(Sid::try_from("S-1-5-32-545").unwrap())
+            .with_group(Sid::try_from("S-1-5-11").unwrap())
+    }
+
+    fn check(sddl: &str, desired: AccessMask) -> (bool, AccessMask) {
+        let sd = SecurityDescriptor::from_sddl(sddl, None).unwrap();
+        let result = sd.access_check(&user(), desired, &GenericMapping::FILE);
+        (result.access_allowed(), result.granted_access())
+    }
+
+    #[test]
+    fn test_null_and_empty_dacl() {
+        let mut sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AIS:AI", None).unwrap();
+        assert!(!sd
+            .access_check(&user(), AccessMask::GENERIC_READ, &GenericMapping::FILE)
+            .access_allowed());
+        let result = sd.access_check(&user(), AccessMask::MAXIMUM_ALLOWED, &GenericMapping::FILE);
+        assert!(!result.access_allowed());
+        assert!(result.granted_access().is_empty());
+
+        sd = SecurityDescriptor::new(None, None, None, None);
+        let result = sd.access_check(&user(), AccessMask::MAXIMUM_ALLOWED, &GenericMapping::FILE);
+        assert!(result.access_allowed());
+        assert_eq!(result.granted_access(), AccessMask::from(0x001f_01ff));
+    }
+
+    #[test]
+    fn test_ace_order() {
+        // the deny ACE comes first and denies write access
+        let (allowed, _) = check(
+            "O:BAG:SYD:AI(D;;FW;;;BU)(A;;FA;;;AU)S:AI",
+            AccessMask::GENERIC_WRITE,
+        );
+        assert!(!allowed);
+
+        // all rights have been granted before the deny ACE is reached
+        let (allowed, _) = check(
+            "O:BAG:SYD:AI(A;;FA;;;AU)(D;;FW;;;BU)S:AI",
+            AccessMask::GENERIC_WRITE,
+        );
+        assert!(allowed);
+
+        // inherit-only ACEs do not apply to the object itself
+        let (allowed, _) = check("O:BAG:SYD:AI(A;IO;FA;;;AU)S:AI", AccessMask::GENERIC_READ);
+        assert!(!allowed);
+    }
+
+    #[test]
+    fn test_maximum_allowed() {
+        let (allowed, granted) = check(
+            "O:BAG:SYD:AI(D;;WD;;;BU)(A;;FA;;;AU)S:AI",
+            AccessMask::MAXIMUM_ALLOWED,
+        );
+        assert!(allowed);
+        assert_eq!(
+            granted,
+            AccessMask::from(0x001f_01ff) - AccessMask::WRITE_DACL
+        );
+
+        let (allowed, _) = check(
+            "O:BAG:SYD:AI(D;;WD;;;BU)(A;;FA;;;AU)S:AI",
+            AccessMask::MAXIMUM_ALLOWED | AccessMask::WRITE_DACL,
+        );
+        assert!(!allowed);
+    }
+
+    #[test]
+    fn test_owner_rights() {
+        let owner = "O:S-1-5-21-2623811015-3361044348-130300820-1013G:SY";
+
+        // implicit READ_CONTROL and WRITE_DAC for the owner
+        let (_, granted) = check(&format!("{owner}D:AIS:AI"), AccessMask::MAXIMUM_ALLOWED);
+        assert_eq!(granted, AccessMask::READ_CONTROL | AccessMask::WRITE_DACL);
+
+        // an OWNER RIGHTS ACE replaces the implicit rights
+        let (_, granted) = check(
+            &format!("{owner}D:AI(A;;RC;;;OW)S:AI"),
+            AccessMask::MAXIMUM_ALLOWED,
+        );
+        assert_eq!(granted, AccessMask::READ_CONTROL);
+    }
+
+    #[test]
+    fn test_conditional_aces() {
+        let (allowed, _) = check(
+            "O:BAG:SYD:AI(XA;;FR;;;AU;(Member_of {SID(BU)}))S:AI",
+            AccessMask::GENERIC_READ,
+        );
+        assert!(allowed);
+
+        // UNKNOWN does not grant access, but it denies access
+        let (allowed, _) = check(
+            "O:BAG:SYD:AI(XA;;FR;;;AU;(@User.clearance > 1))S:AI",
+            AccessMask::GENERIC_READ,
+        );
+        assert!(!allowed);
+        let (allowed, _) = check(
+            "O:BAG:SYD:AI(XD;;FR;;;AU;(@User.clearance > 1))(A;;FR;;;AU)S:AI",
+            AccessMask::GENERIC_READ,
+        );
+        assert!(!allowed);
+    }
+
+    #[test]
+    fn test_resource_attributes() {
+        let sddl = r#"O:BAG:SYD:AI(XA;;FR;;;AU;(@Resource.Secrecy < 3))S:AI(RA;;;;;WD;("Secrecy",TU,0x0,SECRECY))"#;
+        let (allowed, _) = check(&sddl.replace("SECRECY", "2"), AccessMask::GENERIC_READ);
+        assert!(allowed);
+        let (allowed, _) = check(&sddl.replace("SECRECY", "3"), AccessMask::GENERIC_READ);
+        assert!(!allowed);
+    }
+
+    #[test]
+    fn test_central_access_policy() {
+        let capid = Sid::try_from("S-1-17-1").unwrap();
+        let policies = vec![CentralAccessPolicy::new("p", capid.clone())
+            .with_rule(
+                CentralAccessRule::from_sddl(
+                    "finance",
+                    Some(r#"(@Resource.Department == "Finance")"#),
+                    "D:(A;;FR;;;AU)",
+                    Some("D:(A;;FA;;;AU)"),
+                )
+                .unwrap(),
+            )
+            .with_rule(
+                CentralAccessRule::from_sddl(
+                    "hr",
+                    Some(r#"(@Resource.Department == "HR")"#),
+                    "D:",
+                    None,
+                )
+                .unwrap(),
+            )];
+        let check = |sddl: &str, policies: &dyn CentralAccessPolicyResolver| {
+            let sd = SecurityDescriptor::from_sddl(sddl, None).unwrap();
+            sd.access_check_with_policies(
+                &user(),
+                AccessMask::MAXIMUM_ALLOWED,
+                &GenericMapping::FILE,
+                policies,
+            )
+        };
+        let full = GenericMapping::FILE.generic_all();
+        let read = GenericMapping::FILE.generic_read();
+
+        // the finance rule limits the access, the staged rule would not
+        let finance = r#"O:BAG:SYD:(A;;FA;;;AU)S:(SP;;;;;S-1-17-1)(RA;;;;;WD;("Department",TS,0x0,"Finance"))"#;
+        let result = check(finance, &policies);
+        assert_eq!(result.granted_access(), read);
+        assert_eq!(result.staged_granted_access(), Some(full));
+
+        // rules whose condition is not satisfied are ignored
+        let other =
+            r#"O:BAG:SYD:(A;;FA;;;AU)S:(SP;;;;;S-1-17-1)(RA;;;;;WD;("Department",TS,0x0,"Sales"))"#;
+        assert_eq!(check(other, &policies).granted_access(), full);
+
+        // an empty DACL in a rule denies all access
+        let hr =
+            r#"O:BAG:SYD:(A;;FA;;;AU)S:(SP;;;;;S-1-17-1)(RA;;;;;WD;("Department",TS,0x0,"HR"))"#;
+        let result = check(hr, &policies);
+        assert!(!result.access_allowed());
+        assert!(result.granted_access().is_empty());
+
+        // the recovery policy only grants access to administrators
+        let unknown = "O:BAG:SYD:(A;;FA;;;AU)S:(SP;;;;;S-1-17-2)";
+        let result = check(unknown, &policies);
+        assert!(!result.access_allowed());
+        assert!(result.granted_access().is_empty());
+
+        // without policies, SP ACEs are ignored
+        let sd = SecurityDescriptor::from_sddl(finance, None).unwrap();
+        let result = sd.access_check(&user(), AccessMask::MAXIMUM_ALLOWED, &GenericMapping::FILE);
+        assert_eq!(result.granted_access(), full);
+        assert_eq!(result.staged_granted_access(), None);
+    }
+
+    #[test]
+    fn test_central_access_policy_by_type() {
+        let capid = Sid::try_from("S-1-17-1").unwrap();
+        let policies = vec![CentralAccessPolicy::new("p", capid).with_rule(
+            CentralAccessRule::from_sddl("read only", None, "D:(A;;RP;;;AU)", None).unwrap(),
+        )];
+        let group = Guid::try_from("bf967a9c-0de6-11d0-a285-00aa003049e2").unwrap();
+        let member = Guid::try_from("bf9679c0-0de6-11d0-a285-00aa003049e2").unwrap();
+        let list = ObjectTypeList::new(group).with_property(member);
+        let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:(A;;RPWP;;;AU)S:(SP;;;;;S-1-17-1)", None)
+            .unwrap();
+
+        let results = sd.access_check_by_type_with_policies(
+            &user(),
+            AccessMask::MAXIMUM_ALLOWED,
+            &list,
+            &GenericMapping::DS_OBJECT,
+            &policies,
+        );
+        assert_eq!(results.len(), 2);
+        for result in results {
+            assert_eq!(result.granted_access(), AccessMask::READ_PROPERTY);
+            assert_eq!(
+                result.staged_granted_access(),
+                Some(AccessMask::READ_PROPERTY)
+            );
+        }
+
+        let results = sd.access_check_by_type_result_list(
+            &user(),
+            AccessMask::MAXIMUM_ALLOWED,
+            &list,
+            &GenericMapping::DS_OBJECT,
+        );
+        for result in results {
+            assert_eq!(
+                result.granted_access(),
+                AccessMask::READ_PROPERTY | AccessMask::WRITE_PROPERTY
+            );
+        }
+    }
+
+    #[test]
+    fn test_privileges() {
+        let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AI(A;;FR;;;AU)S:AI", None).unwrap();
+        let sacl_access = AccessMask::ACCESS_SYSTEM_SECURITY | AccessMask::WRITE_OWNER;
+        assert!(!sd
+            .access_check(&user(), sacl_access, &GenericMapping::FILE)
+            .access_allowed());
+
+        let token = user()
+            .with_privilege(Privilege::Security)
+            .with_privilege(Privilege::TakeOwnership);
+        assert!(sd
+            .access_check(&token, sacl_access, &GenericMapping::FILE)
+            .access_allowed());
+    }
+
+    #[test]
+    fn test_backup_privilege() {
+        let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AI(D;;FA;;;WD)S:AI", None).unwrap();
+        let token = user().with_privilege(Privilege::Backup);
+        let read = GenericMapping::FILE.generic_read();
+
+        let result = sd.access_check(&token, AccessMask::MAXIMUM_ALLOWED, &GenericMapping::FILE);
+        assert!(result.access_allowed());
+        assert_eq!(result.granted_access(), read);
+
+        let sacl_access = AccessMask::ACCESS_SYSTEM_SECURITY | AccessMask::READ_CONTROL;
+        assert!(sd
+            .access_check(&token, sacl_access, &GenericMapping::FILE)
+            .access_allowed());
+        assert!(!sd
+            .access_check(&token, AccessMask::from(0x0000_0002), &GenericMapping::FILE)
+            .access_allowed());
+    }
+
+    #[test]
+    fn test_restore_privilege() {
+        let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AI(D;;FA;;;WD)S:AI", None).unwrap();
+        let token = user().with_privilege(Privilege::Restore);
+        let write = GenericMapping::FILE.generic_write()
+            | AccessMask::WRITE_DACL
+            | AccessMask::WRITE_OWNER
+            | AccessMask::DELETE;
+
+        let result = sd.access_check(&token, AccessMask::MAXIMUM_ALLOWED, &GenericMapping::FILE);
+        assert!(result.access_allowed());
+        assert_eq!(result.granted_access(), write);
+
+        let sacl_access = AccessMask::ACCESS_SYSTEM_SECURITY | AccessMask::WRITE_DACL;
+        assert!(sd
+            .access_check(&token, sacl_access, &GenericMapping::FILE)
+            .access_allowed());
+        assert!(!sd
+            .access_check(&token, AccessMask::from(0x0000_0001), &GenericMapping::FILE)
+            .access_allowed());
+    }
+
+    #[test]
+    fn test_deny_only_and_restricted_sids() {
+        let sd = SecurityDescriptor::from_sddl(
+            "O:BAG:SYD:AI(D;;FW;;;BA)(A;;FA;;;BA)(A;;FR;;;AU)S:AI",
+            None,
+        )
+        .unwrap();
+        let administrators = Sid::try_from("S-1-5-32-544").unwrap();
+
+        // deny-only groups match deny ACEs, but never allow ACEs. FW shares
+        // SYNCHRONIZE and READ_CONTROL with FR, so these are denied as well
+        let token = user()
+            .with_group_attributes(administrators.clone(), GroupAttributes::USE_FOR_DENY_ONLY);
+        let result = sd.access_check(&token, AccessMask::MAXIMUM_ALLOWED, &GenericMapping::FILE);
+        assert_eq!(result.granted_access(), AccessMask::from(0x0000_0089));
+
+        // a restricted token only gets the rights which are granted to both
+        // its normal and its restricted SIDs
+        let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AI(A;;FA;;;BA)(A;;FR;;;AU)S:AI", None)
+            .unwrap();
+        let token = user()
+            .with_group(administrators)
+            .with_restricted_sid(Sid::try_from("S-1-5-11").unwrap());
+        assert!(!sd
+            .access_check(&token, AccessMask::DELETE, &GenericMapping::FILE)
+            .access_allowed());
+        assert!(sd
+            .access_check(&token, AccessMask::GENERIC_READ, &GenericMapping::FILE)
+            .access_allowed());
+    }
+
+    #[test]
+    fn test_object_type_list() {
+        let class = Guid::try_from("bf967a9c-0de6-11d0-a285-00aa003049e2").unwrap();
+        let property_set = Guid::try_from("bc0ac240-79a9-11d0-9020-00c04fc2d4cf").unwrap();
+        let property = Guid::try_from("bf9679c0-0de6-11d0-a285-00aa003049e2").unwrap();
+        let list = ObjectTypeList::new(class)
+            .with_property_set(property_set)
+            .with_property(property);
+        let allowed = |sddl: &str| -> Vec<bool> {
+            SecurityDescriptor::from_sddl(sddl, None)
+                .unwrap()
+                .access_check_by_type_result_list(
+                    &user(),
+                    AccessMask::WRITE_PROPERTY,
+                    &list,
+                    &GenericMapping::FILE,
+                )
+                .iter()
+                .map(|r| r.access_allowed())
+                .collect()
+        };
+
+        // granting a property set grants all its properties, and the class
+        // if this is its only property set
+        assert_eq!(
+            allowed("O:BAG:SYD:AI(OA;;WP;bc0ac240-79a9-11d0-9020-00c04fc2d4cf;;AU)S:AI"),
+            vec![true, true, true]
+        );
+
+        // denying a property denies all its ancestors
+        assert_eq!(
+            allowed(
+                "O:BAG:SYD:AI(OD;;WP;bf9679c0-0de6-11d0-a285-00aa003049e2;;AU)(A;;WP;;;AU)S:AI"
+            ),
+            vec![false, false, false]
+        );
+
+        // object ACEs for other object types are ignored
+        assert_eq!(
+            allowed("O:BAG:SYD:AI(OA;;WP;59ba2f42-79a2-11d0-9020-00c04fc2d3cf;;AU)S:AI"),
+            vec![false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_mandatory_integrity() {
+        let low = user().with_integrity_level(IntegrityLevel::Low);
+        let check = |sddl: &str, token: &Token, desired: AccessMask| {
+            SecurityDescriptor::from_sddl(sddl, None)
+                .unwrap()
+                .access_check(token, desired, &GenericMapping::FILE)
+                .access_allowed()
+        };
+
+        // objects without label have medium integrity and no-write-up
+        let sd = "O:BAG:SYD:AI(A;;FA;;;AU)S:AI";
+        assert!(check(sd, &low, AccessMask::GENERIC_READ));
+        assert!(!check(sd, &low, AccessMask::GENERIC_WRITE));
+        assert!(check(sd, &user(), AccessMask::GENERIC_WRITE));
+
+        let sd = "O:BAG:SYD:AI(A;;FA;;;AU)S:AI(ML;;NWNR;;;HI)";
+        assert!(!check(sd, &user(), AccessMask::GENERIC_READ));
+        assert!(check(sd, &user(), AccessMask::GENERIC_EXECUTE));
+        let high = user().with_integrity_level(IntegrityLevel::High);
+        assert!(check(sd, &high, AccessMask::GENERIC_ALL));
+
+        // the label also applies to a NULL DACL
+        let sd = SecurityDescriptor::new(None, None, None, None);
+        assert!(!sd
+            .access_check(&low, AccessMask::GENERIC_WRITE, &GenericMapping::FILE)
+            .access_allowed());
+    }
+}
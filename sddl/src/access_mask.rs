@@ -2,10 +2,12 @@ use binrw::{BinRead, BinReaderExt, BinWrite, BinWriterExt};
 use bitflags::bitflags;
 use constants::{
     FILE_ALL, FILE_EXECUTE, FILE_READ, FILE_WRITE, KEY_ALL, KEY_EXECUTE, KEY_READ, KEY_WRITE,
+    SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP, SYSTEM_MANDATORY_LABEL_NO_READ_UP,
+    SYSTEM_MANDATORY_LABEL_NO_WRITE_UP,
 };
 use serde::Serialize;
 
-use crate::sddl_h::*;
+use crate::{sddl_h::*, GenericMapping, NameContext, ObjectKind};
 
 pub mod constants {
     use super::AccessMask;
@@ -104,7 +106,7 @@ pub mod constants {
 }
 
 bitflags! {
-    #[derive(Eq, PartialEq, Debug, Copy, Clone, Serialize)]
+    #[derive(Eq, PartialEq, Debug, Copy, Clone)]
     pub struct AccessMask: u32 {
 
         /// **When used in an Access Request operation:** When read access to an
@@ -145,7 +147,7 @@ bitflags! {
         /// are set are implementation dependent. During this translation, the
         /// GW bit is cleared. The resulting ACCESS_MASK bits are the actual
         /// permissions that are granted by this ACE.
-        const GENERIC_WRITE = 0x4000000;
+        const GENERIC_WRITE = 0x40000000;
 
         /// **When used in an Access Request operation:** When execute access to
         /// an object is requested, this bit is translated to a combination of
@@ -243,47 +245,235 @@ bitflags! {
 }
 
 impl AccessMask {
+    /// replaces all generic access rights by the specific and standard
+    /// access rights they are mapped to for a type of object
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let mask = AccessMask::GENERIC_ALL;
+    /// assert_eq!(mask.map_generic(&GenericMapping::FILE).bits(), 0x001f_01ff);
+    /// assert_eq!(mask.map_generic(&GenericMapping::REGISTRY_KEY).bits(), 0x000f_003f);
+    /// ```
+    pub fn map_generic(&self, mapping: &GenericMapping) -> AccessMask {
+        let mut mapped = *self
+            - (Self::GENERIC_READ
+                | Self::GENERIC_WRITE
+                | Self::GENERIC_EXECUTE
+                | Self::GENERIC_ALL);
+        if self.contains(Self::GENERIC_READ) {
+            mapped |= mapping.generic_read();
+        }
+        if self.contains(Self::GENERIC_WRITE) {
+            mapped |= mapping.generic_write();
+        }
+        if self.contains(Self::GENERIC_EXECUTE) {
+            mapped |= mapping.generic_execute();
+        }
+        if self.contains(Self::GENERIC_ALL) {
+            mapped |= mapping.generic_all();
+        }
+        mapped
+    }
+
+    /// returns the names of the access rights in this mask, using the names
+    /// of the object-specific rights of `kind`. Bits which have no meaning
+    /// for this kind of object are omitted.
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let mask = AccessMask::from(0x0012_0001);
+    /// assert_eq!(
+    ///     mask.describe(ObjectKind::File),
+    ///     vec!["SYNCHRONIZE", "READ_CONTROL", "FILE_READ_DATA"]
+    /// );
+    /// assert_eq!(
+    ///     mask.describe(ObjectKind::RegistryKey),
+    ///     vec!["SYNCHRONIZE", "READ_CONTROL", "KEY_QUERY_VALUE"]
+    /// );
+    /// ```
+    pub fn describe(&self, kind: ObjectKind) -> Vec<&'static str> {
+        STANDARD_RIGHTS
+            .iter()
+            .chain(kind.specific_rights())
+            .filter(|(bits, _)| self.contains(Self::from_bits_retain(*bits)))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    /// returns the names of the access rights in this mask like
+    /// [`Self::describe`], but keeps the bits which have no name as hex
+    /// number. Without a kind, only the generic and standard rights are
+    /// named.
+    fn names(&self, kind: Option<ObjectKind>) -> Vec<String> {
+        let specific_rights = kind.map(|kind| kind.specific_rights()).unwrap_or_default();
+        let mut remaining = self.bits();
+        let mut names: Vec<String> = STANDARD_RIGHTS
+            .iter()
+            .chain(specific_rights)
+            .filter(|(bits, _)| self.bits() & bits == *bits)
+            .map(|(bits, name)| {
+                remaining &= !bits;
+                (*name).to_owned()
+            })
+            .collect();
+        if remaining != 0 {
+            names.push(format!("{remaining:#x}"));
+        }
+        names
+    }
+
+    /// returns the SDDL representation of this mask. If the mask equals one
+    /// of the file or registry shorthands (such as `FA` or `KR`), this
+    /// shorthand is used. Use [`Self::sddl_string_for`] if the kind of the
+    /// object is known.
     pub fn sddl_string(&self) -> String {
-        if *self == *FILE_ALL {
-            SDDL_FILE_ALL.into()
-        } else if *self == *FILE_READ {
-            SDDL_FILE_READ.into()
-        } else if *self == *FILE_WRITE {
-            SDDL_FILE_WRITE.into()
-        } else if *self == *FILE_EXECUTE {
-            SDDL_FILE_EXECUTE.into()
-        } else if *self == *KEY_ALL {
-            SDDL_KEY_ALL.into()
-        } else if *self == *KEY_READ {
-            SDDL_KEY_READ.into()
-        } else if *self == *KEY_WRITE {
-            SDDL_KEY_WRITE.into()
-        } else if *self == *KEY_EXECUTE {
-            SDDL_KEY_EXECUTE.into()
-        } else {
-            let mut sddl = String::with_capacity(32);
-            let mut flag = |mask: AccessMask, s: &str| {
-                if self.contains(mask) {
-                    sddl.push_str(s);
-                }
-            };
-
-            flag(Self::GENERIC_READ, SDDL_GENERIC_READ);
-            flag(Self::GENERIC_WRITE, SDDL_GENERIC_WRITE);
-            flag(Self::GENERIC_EXECUTE, SDDL_GENERIC_EXECUTE);
-            flag(Self::GENERIC_ALL, SDDL_GENERIC_ALL);
-            flag(Self::MAXIMUM_ALLOWED, "MA");
-            flag(Self::ACCESS_SYSTEM_SECURITY, "AS");
-            flag(Self::SYNCHRONIZE, "SY");
-            flag(Self::WRITE_OWNER, SDDL_WRITE_OWNER);
-            flag(Self::WRITE_DACL, SDDL_WRITE_DAC);
-            flag(Self::READ_CONTROL, SDDL_READ_CONTROL);
-            flag(Self::DELETE, SDDL_STANDARD_DELETE);
+        self.sddl_string_with(&[file_shorthands(), key_shorthands()].concat())
+    }
+
+    /// returns the SDDL representation of this mask for an object of type
+    /// `kind`, which uses the `F*` shorthands only for files and the `K*`
+    /// shorthands only for registry keys
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let mask = AccessMask::from(0x0002_0019);
+    /// assert_eq!(mask.sddl_string_for(ObjectKind::RegistryKey), "KR");
+    /// assert_eq!(mask.sddl_string_for(ObjectKind::DsObject), "CCSWRPRC");
+    /// ```
+    pub fn sddl_string_for(&self, kind: ObjectKind) -> String {
+        match kind {
+            ObjectKind::File | ObjectKind::Directory | ObjectKind::NamedPipe => {
+                self.sddl_string_with(&file_shorthands())
+            }
+            ObjectKind::RegistryKey => self.sddl_string_with(&key_shorthands()),
+            _ => self.sddl_string_with(&[]),
+        }
+    }
+
+    /// returns the SDDL representation of the policy of a mandatory label
+    pub(crate) fn mandatory_label_sddl_string(&self) -> String {
+        self.sddl_codes(&[
+            (SYSTEM_MANDATORY_LABEL_NO_WRITE_UP, SDDL_NO_WRITE_UP),
+            (SYSTEM_MANDATORY_LABEL_NO_READ_UP, SDDL_NO_READ_UP),
+            (SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP, SDDL_NO_EXECUTE_UP),
+        ])
+    }
+
+    fn sddl_string_with(&self, shorthands: &[(AccessMask, &'static str)]) -> String {
+        match shorthands.iter().find(|(mask, _)| mask == self) {
+            Some((_, shorthand)) => (*shorthand).into(),
+            None => self.sddl_codes(SDDL_RIGHTS),
+        }
+    }
+
+    /// concatenates the codes of all rights in this mask. If there are bits
+    /// which cannot be expressed by a code, the mask is written as number.
+    fn sddl_codes(&self, codes: &[(AccessMask, &'static str)]) -> String {
+        let mut sddl = String::with_capacity(32);
+        let mut remaining = *self;
+        for (mask, code) in codes {
+            if self.contains(*mask) {
+                sddl.push_str(code);
+                remaining -= *mask;
+            }
+        }
+        if remaining.is_empty() {
             sddl
+        } else {
+            format!("{:#x}", self.bits())
         }
     }
 }
 
+/// names of the generic and standard access rights, which have the same
+/// meaning for every kind of object
+const STANDARD_RIGHTS: &[(u32, &str)] = &[
+    (0x8000_0000, "GENERIC_READ"),
+    (0x4000_0000, "GENERIC_WRITE"),
+    (0x2000_0000, "GENERIC_EXECUTE"),
+    (0x1000_0000, "GENERIC_ALL"),
+    (0x0200_0000, "MAXIMUM_ALLOWED"),
+    (0x0100_0000, "ACCESS_SYSTEM_SECURITY"),
+    (0x0010_0000, "SYNCHRONIZE"),
+    (0x0008_0000, "WRITE_OWNER"),
+    (0x0004_0000, "WRITE_DAC"),
+    (0x0002_0000, "READ_CONTROL"),
+    (0x0001_0000, "DELETE"),
+];
+
+/// SDDL codes of single access rights, in the order used by Windows
+const SDDL_RIGHTS: &[(AccessMask, &str)] = &[
+    (AccessMask::GENERIC_ALL, SDDL_GENERIC_ALL),
+    (AccessMask::GENERIC_READ, SDDL_GENERIC_READ),
+    (AccessMask::GENERIC_WRITE, SDDL_GENERIC_WRITE),
+    (AccessMask::GENERIC_EXECUTE, SDDL_GENERIC_EXECUTE),
+    (AccessMask::MAXIMUM_ALLOWED, "MA"),
+    (AccessMask::ACCESS_SYSTEM_SECURITY, "AS"),
+    (AccessMask::CREATE_CHILD, SDDL_CREATE_CHILD),
+    (AccessMask::DELETE_CHILD, SDDL_DELETE_CHILD),
+    (AccessMask::LIST_CHILDREN, SDDL_LIST_CHILDREN),
+    (AccessMask::SELF_WRITE, SDDL_SELF_WRITE),
+    (AccessMask::READ_PROPERTY, SDDL_READ_PROPERTY),
+    (AccessMask::WRITE_PROPERTY, SDDL_WRITE_PROPERTY),
+    (AccessMask::DELETE_TREE, SDDL_DELETE_TREE),
+    (AccessMask::LIST_OBJECT, SDDL_LIST_OBJECT),
+    (AccessMask::CONTROL_ACCESS, SDDL_CONTROL_ACCESS),
+    (AccessMask::DELETE, SDDL_STANDARD_DELETE),
+    (AccessMask::READ_CONTROL, SDDL_READ_CONTROL),
+    (AccessMask::WRITE_DACL, SDDL_WRITE_DAC),
+    (AccessMask::WRITE_OWNER, SDDL_WRITE_OWNER),
+];
+
+fn file_shorthands() -> [(AccessMask, &'static str); 4] {
+    [
+        (*FILE_ALL, SDDL_FILE_ALL),
+        (*FILE_READ, SDDL_FILE_READ),
+        (*FILE_WRITE, SDDL_FILE_WRITE),
+        (*FILE_EXECUTE, SDDL_FILE_EXECUTE),
+    ]
+}
+
+fn key_shorthands() -> [(AccessMask, &'static str); 4] {
+    [
+        (*KEY_ALL, SDDL_KEY_ALL),
+        (*KEY_READ, SDDL_KEY_READ),
+        (*KEY_WRITE, SDDL_KEY_WRITE),
+        (*KEY_EXECUTE, SDDL_KEY_EXECUTE),
+    ]
+}
+
+/// Human-readable formats get the names of the access rights, separated by
+/// `|`. The object-specific rights are named for the object kind of the
+/// active [`NameContext`] (see [`NameContext::with_object_kind`]), and are
+/// written as hex number if there is no such kind.
+///
+/// ```
+/// use std::sync::Arc;
+/// use sddl::*;
+/// let mask = AccessMask::from(0x0012_0001);
+/// assert_eq!(
+///     serde_json::to_value(mask).unwrap(),
+///     "SYNCHRONIZE | READ_CONTROL | 0x1"
+/// );
+/// let context = Arc::new(NameContext::new().with_object_kind(ObjectKind::File));
+/// assert_eq!(
+///     context.scope(|| serde_json::to_value(mask).unwrap()),
+///     "SYNCHRONIZE | READ_CONTROL | FILE_READ_DATA"
+/// );
+/// ```
+impl Serialize for AccessMask {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u32(self.bits());
+        }
+        let kind = NameContext::with_active(|context| context.and_then(NameContext::object_kind));
+        serializer.serialize_str(&self.names(kind).join(" | "))
+    }
+}
+
 impl BinRead for AccessMask {
     type Args<'a> = ();
 
@@ -307,7 +497,9 @@ impl<'input> TryFrom<&'input str> for AccessMask {
     type Error = crate::Error;
 
     fn try_from(value: &'input str) -> Result<Self, Self::Error> {
-        Ok(crate::parser::AccessMaskParser::new().parse(None, value)?)
+        crate::parser::AccessMaskParser::new()
+            .parse(None, value)
+            .map_err(|e| crate::SddlParseError::from_lalrpop(value, e))
     }
 }
 
@@ -367,4 +559,103 @@ mod tests {
         assert_eq!(KEY_EXECUTE.bits(), 0x00020019);
         assert_eq!(KEY_WRITE.bits(), 0x00020006);
     }
+
+    #[test]
+    fn test_map_generic() {
+        use crate::GenericMapping;
+
+        let mask = AccessMask::GENERIC_READ | AccessMask::GENERIC_WRITE | AccessMask::DELETE;
+        assert_eq!(
+            mask.map_generic(&GenericMapping::FILE),
+            *FILE_READ | *FILE_WRITE | AccessMask::DELETE
+        );
+        assert_eq!(
+            mask.map_generic(&GenericMapping::REGISTRY_KEY),
+            *KEY_READ | *KEY_WRITE | AccessMask::DELETE
+        );
+        assert_eq!(
+            AccessMask::GENERIC_EXECUTE.map_generic(&GenericMapping::DS_OBJECT),
+            AccessMask::READ_CONTROL | AccessMask::LIST_CHILDREN
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        use crate::ObjectKind;
+
+        let mask = AccessMask::from(0x0001_0030);
+        assert_eq!(
+            mask.describe(ObjectKind::Service),
+            vec!["DELETE", "SERVICE_START", "SERVICE_STOP"]
+        );
+        assert_eq!(
+            mask.describe(ObjectKind::DsObject),
+            vec![
+                "DELETE",
+                "ADS_RIGHT_DS_READ_PROP",
+                "ADS_RIGHT_DS_WRITE_PROP"
+            ]
+        );
+        assert_eq!(
+            AccessMask::from(0x0000_0040).describe(ObjectKind::File),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn test_sddl_string_for() {
+        use crate::ObjectKind;
+
+        assert_eq!(FILE_READ.sddl_string_for(ObjectKind::File), "FR");
+        assert_eq!(FILE_READ.sddl_string_for(ObjectKind::Directory), "FR");
+        assert_eq!(
+            FILE_READ.sddl_string_for(ObjectKind::RegistryKey),
+            "0x120089"
+        );
+        assert_eq!(KEY_ALL.sddl_string_for(ObjectKind::RegistryKey), "KA");
+        assert_eq!(
+            KEY_ALL.sddl_string_for(ObjectKind::File),
+            "CCDCLCSWRPWPSDRCWDWO"
+        );
+        assert_eq!(KEY_ALL.sddl_string(), "KA");
+    }
+
+    #[test]
+    fn test_serialize_for_kind() {
+        use std::sync::Arc;
+
+        use crate::{NameContext, ObjectKind};
+
+        let mask = *FILE_READ | AccessMask::from(0x0200);
+        let json = |kind| {
+            Arc::new(NameContext::new().with_object_kind(kind))
+                .scope(|| serde_json::to_value(mask).unwrap())
+        };
+        assert_eq!(
+            json(ObjectKind::File),
+            "SYNCHRONIZE | READ_CONTROL | FILE_READ_DATA | FILE_READ_EA | \
+             FILE_READ_ATTRIBUTES | 0x200"
+        );
+        assert_eq!(
+            json(ObjectKind::RegistryKey),
+            "SYNCHRONIZE | READ_CONTROL | KEY_QUERY_VALUE | KEY_ENUMERATE_SUB_KEYS | \
+             KEY_WOW64_32KEY | 0x80"
+        );
+        assert_eq!(
+            serde_json::to_value(mask).unwrap(),
+            "SYNCHRONIZE | READ_CONTROL | 0x289"
+        );
+    }
+
+    #[test]
+    fn test_sddl_string_roundtrip() {
+        for s in ["CCDCLCSWRPWPDTLOCRSDRCWDWO", "GRGX", "RPCR", ""] {
+            assert_eq!(AccessMask::try_from(s).unwrap().sddl_string(), s);
+        }
+
+        // SYNCHRONIZE has no SDDL code
+        let mask = AccessMask::SYNCHRONIZE | AccessMask::READ_CONTROL;
+        assert_eq!(mask.sddl_string(), "0x120000");
+        assert_eq!(AccessMask::try_from("0x120000").unwrap(), mask);
+    }
 }
@@ -1,8 +1,36 @@
-use binrw::binrw;
+use binrw::{binrw, BinWriterExt};
+use lazy_static::lazy_static;
 use serde::Serialize;
 use std::fmt::Display;
+use std::io::Cursor;
+
+use crate::ace_header::remaining_ace_size;
+use crate::constants::SECURITY_NULL_SID_AUTHORITY;
+use crate::{
+    sddl_h::*, AccessMask, AceFlags, AceHeader, AceHeaderFlags, ClaimAttribute,
+    ConditionalExpression, Guid, ObjectKind, RawSize, Sid, ACE_HEADER_SIZE,
+    CONDITIONAL_ACE_SIGNATURE,
+};
+
+lazy_static! {
+    /// the placeholder trustee of ACEs of unknown types
+    static ref NULL_SID: Sid = SECURITY_NULL_SID_AUTHORITY.new_sid(&[0]);
+}
+
+/// size of the CompoundAceType and the Reserved field of an
+/// ACCESS_ALLOWED_COMPOUND_ACE
+const COMPOUND_ACE_FIELDS_SIZE: u16 = 2 + 2;
 
-use crate::{sddl_h::*, AccessMask, AceFlags, AceHeader, AceHeaderFlags, Guid, RawSize, Sid, ACE_HEADER_SIZE};
+/// The type of an ACCESS_ALLOWED_COMPOUND_ACE
+#[binrw]
+#[derive(Eq, PartialEq, Clone, Copy, Default, Debug, Serialize)]
+#[allow(non_camel_case_types)]
+#[brw(repr=u16)]
+pub enum CompoundAceType {
+    /// the ACE allows access to a server which impersonates a client
+    #[default]
+    COMPOUND_ACE_IMPERSONATION = 0x0001,
+}
 
 /// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/628ebb1d-c509-4ea0-a10f-77ef97ca4586>
 #[binrw]
@@ -18,7 +46,7 @@ pub enum Ace {
         header: AceHeader,
 
         /// The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
         #[br(count = usize::from(*header.expected_padding()))]
@@ -55,7 +83,7 @@ pub enum Ace {
         inherited_object_type: Option<Guid>,
 
         /// The SID of a trustee. The length of the SID MUST be a multiple of 4.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
         #[br(count = usize::from(*header.expected_padding()))]
@@ -69,7 +97,7 @@ pub enum Ace {
     ACCESS_DENIED_ACE {
         header: AceHeader,
         /// The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
         #[br(count = usize::from(*header.expected_padding()))]
@@ -105,7 +133,7 @@ pub enum Ace {
         inherited_object_type: Option<Guid>,
 
         ///  The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
         #[br(count = usize::from(*header.expected_padding()))]
@@ -119,12 +147,16 @@ pub enum Ace {
     ACCESS_ALLOWED_CALLBACK_ACE {
         header: AceHeader,
         ///  The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Optional application data. The size of the application data is
         /// determined by the AceSize field of the ACE_HEADER.
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         /// Conditional ACEs are a form of CALLBACK ACEs with a special format
@@ -146,12 +178,16 @@ pub enum Ace {
     ACCESS_DENIED_CALLBACK_ACE {
         header: AceHeader,
         ///  The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Optional application data. The size of the application data is
         /// determined by the AceSize field of the ACE_HEADER.
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         /// Conditional ACEs are a form of CALLBACK ACEs with a special format
@@ -196,15 +232,19 @@ pub enum Ace {
         inherited_object_type: Option<Guid>,
 
         ///  The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + flags.raw_size() as usize
+            + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Optional application data. The size of the application data is
         /// determined by the AceSize field of the ACE_HEADER.
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + flags.raw_size() as usize
-                                                + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         /// Conditional ACEs are a form of CALLBACK ACEs with a special format
@@ -249,15 +289,19 @@ pub enum Ace {
         inherited_object_type: Option<Guid>,
 
         ///  The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + flags.raw_size() as usize
+            + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Optional application data. The size of the application data is
         /// determined by the AceSize field of the ACE_HEADER.
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + flags.raw_size() as usize
-                                                + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         /// Conditional ACEs are a form of CALLBACK ACEs with a special format
@@ -286,7 +330,7 @@ pub enum Ace {
         ///  generate an audit message. If an application does not specify a SID
         ///  for this field, audit messages are generated for the specified
         ///  access rights for all trustees.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
         #[br(count = usize::from(*header.expected_padding()))]
@@ -324,15 +368,19 @@ pub enum Ace {
         inherited_object_type: Option<Guid>,
 
         ///  The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + flags.raw_size() as usize
+            + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Optional application data. The size of the application data is
         /// determined by the AceSize field of the ACE_HEADER.
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + flags.raw_size() as usize
-                                                + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         #[br(calc(if application_data.len() >= 4 {application_data[0..4] == [0x61, 0x72, 0x74, 0x78]} else {false}))]
@@ -357,12 +405,16 @@ pub enum Ace {
         ///  generate an audit message. If an application does not specify a SID
         ///  for this field, audit messages are generated for the specified
         ///  access rights for all trustees.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Optional application data. The size of the application data is
         /// determined by the AceSize field of the ACE_HEADER.
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         /// Conditional ACEs are a form of CALLBACK ACEs with a special format
@@ -390,7 +442,7 @@ pub enum Ace {
         ///  the mandatory integrity level of the object associated with the
         ///  SACL that contains this ACE. The RID must be one of the following
         ///  values.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
         #[br(count = usize::from(*header.expected_padding()))]
@@ -428,15 +480,19 @@ pub enum Ace {
         inherited_object_type: Option<Guid>,
 
         ///  The SID of a trustee.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + flags.raw_size() as usize
+            + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Optional application data. The size of the application data is
         /// determined by the AceSize field of the ACE_HEADER.
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + flags.raw_size() as usize
-                                                + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
-                                                + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         #[br(calc(if application_data.len() >= 4 {application_data[0..4] == [0x61, 0x72, 0x74, 0x78]} else {false}))]
@@ -456,13 +512,17 @@ pub enum Ace {
         header: AceHeader,
 
         ///  The SID corresponding to the Everyone SID (S-1-1-0) in binary form.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
         /// Data describing a resource attribute type, name, and value(s). This
         /// data MUST be encoded in CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1 format
         /// as described in section 2.4.10.1
-        #[br(count=*header.ace_size() as usize - (ACE_HEADER_SIZE as usize + sid.len()))]
+        #[br(count=application_data_size)]
         application_data: Vec<u8>,
 
         #[br(calc(if application_data.len() >= 4 {application_data[0..4] == [0x61, 0x72, 0x74, 0x78]} else {false}))]
@@ -484,37 +544,375 @@ pub enum Ace {
         ///  CentralAccessPoliciesList (as specified in [MS-GPCAP] section
         ///  3.2.1.1) of the machine on which the access evaluation will be
         ///  performed.
-        #[brw(assert(sid.len() % 4 == 0))]
+        #[brw(assert(sid.len().is_multiple_of(4)))]
         sid: Sid,
 
         #[br(count = usize::from(*header.expected_padding()))]
         _padding: Vec<u8>,
     },
+
+    /// The SYSTEM_PROCESS_TRUST_LABEL_ACE structure defines an ACE for the
+    /// SACL that restricts the access of processes which are not protected
+    /// at least at the protection level of `sid`.
+    #[brw(magic = 0x14u8)]
+    SYSTEM_PROCESS_TRUST_LABEL_ACE {
+        header: AceHeader,
+
+        ///  A SID in the `S-1-19` authority, which specifies the protection
+        ///  type and the signer of the process. See [`crate::ProtectionLevel`].
+        #[brw(assert(sid.len().is_multiple_of(4)))]
+        sid: Sid,
+
+        #[br(count = usize::from(*header.expected_padding()))]
+        _padding: Vec<u8>,
+    },
+
+    /// The SYSTEM_ACCESS_FILTER_ACE structure defines an ACE for the SACL
+    /// that limits the access to an object to the rights in the access mask
+    /// if its conditional expression is not satisfied.
+    #[brw(magic = 0x15u8)]
+    SYSTEM_ACCESS_FILTER_ACE {
+        header: AceHeader,
+
+        ///  The SID corresponding to the Everyone SID (S-1-1-0) in binary form.
+        #[brw(assert(sid.len().is_multiple_of(4)))]
+        sid: Sid,
+
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
+        /// The conditional expression of the filter
+        #[br(count=application_data_size)]
+        application_data: Vec<u8>,
+
+        #[br(calc(if application_data.len() >= 4 {application_data[0..4] == [0x61, 0x72, 0x74, 0x78]} else {false}))]
+        #[bw(ignore)]
+        is_conditional: bool,
+
+        #[br(count = usize::from(*header.expected_padding()))]
+        _padding: Vec<u8>,
+    },
+
+    /// The SYSTEM_ALARM_ACE structure is reserved for future use by Windows,
+    /// but it can be found in old descriptors. It has the same layout as the
+    /// SYSTEM_AUDIT_ACE.
+    #[brw(magic = 0x03u8)]
+    SYSTEM_ALARM_ACE {
+        header: AceHeader,
+
+        ///  The SID of a trustee.
+        #[brw(assert(sid.len().is_multiple_of(4)))]
+        sid: Sid,
+
+        #[br(count = usize::from(*header.expected_padding()))]
+        _padding: Vec<u8>,
+    },
+
+    /// The SYSTEM_ALARM_OBJECT_ACE structure is reserved for future use by
+    /// Windows. It has the same layout as the SYSTEM_AUDIT_OBJECT_ACE.
+    #[brw(magic = 0x08u8)]
+    SYSTEM_ALARM_OBJECT_ACE {
+        header: AceHeader,
+
+        flags: AceFlags,
+
+        /// A GUID that identifies a property set, a property, an extended
+        /// right, or a type of child object. This field is valid only if the
+        /// ACE_OBJECT_TYPE_PRESENT bit is set in the Flags field.
+        #[brw(if(flags.contains(AceFlags::ACE_OBJECT_TYPE_PRESENT)))]
+        object_type: Option<Guid>,
+
+        /// A GUID that identifies the type of child object that can inherit
+        /// the ACE. This field is valid only if the
+        /// ACE_INHERITED_OBJECT_TYPE_PRESENT bit is set in the Flags member.
+        #[brw(if(flags.contains(AceFlags::ACE_INHERITED_OBJECT_TYPE_PRESENT)))]
+        inherited_object_type: Option<Guid>,
+
+        ///  The SID of a trustee.
+        #[brw(assert(sid.len().is_multiple_of(4)))]
+        sid: Sid,
+
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + flags.raw_size() as usize
+            + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
+        /// Optional application data. The size of the application data is
+        /// determined by the AceSize field of the ACE_HEADER.
+        #[br(count=application_data_size)]
+        application_data: Vec<u8>,
+
+        #[br(calc(if application_data.len() >= 4 {application_data[0..4] == [0x61, 0x72, 0x74, 0x78]} else {false}))]
+        #[bw(ignore)]
+        is_conditional: bool,
+
+        #[br(count = usize::from(*header.expected_padding()))]
+        _padding: Vec<u8>,
+    },
+
+    /// The SYSTEM_ALARM_CALLBACK_ACE structure is reserved for future use by
+    /// Windows. It has the same layout as the SYSTEM_AUDIT_CALLBACK_ACE.
+    #[brw(magic = 0x0eu8)]
+    SYSTEM_ALARM_CALLBACK_ACE {
+        header: AceHeader,
+
+        ///  The SID of a trustee.
+        #[brw(assert(sid.len().is_multiple_of(4)))]
+        sid: Sid,
+
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
+        /// Optional application data. The size of the application data is
+        /// determined by the AceSize field of the ACE_HEADER.
+        #[br(count=application_data_size)]
+        application_data: Vec<u8>,
+
+        #[br(calc(if application_data.len() >= 4 {application_data[0..4] == [0x61, 0x72, 0x74, 0x78]} else {false}))]
+        #[bw(ignore)]
+        is_conditional: bool,
+
+        #[br(count = usize::from(*header.expected_padding()))]
+        _padding: Vec<u8>,
+    },
+
+    /// The SYSTEM_ALARM_CALLBACK_OBJECT_ACE structure is reserved for future
+    /// use by Windows. It has the same layout as the
+    /// SYSTEM_AUDIT_CALLBACK_OBJECT_ACE.
+    #[brw(magic = 0x10u8)]
+    SYSTEM_ALARM_CALLBACK_OBJECT_ACE {
+        header: AceHeader,
+
+        flags: AceFlags,
+
+        /// A GUID that identifies a property set, a property, an extended
+        /// right, or a type of child object. This field is valid only if the
+        /// ACE_OBJECT_TYPE_PRESENT bit is set in the Flags field.
+        #[brw(if(flags.contains(AceFlags::ACE_OBJECT_TYPE_PRESENT)))]
+        object_type: Option<Guid>,
+
+        /// A GUID that identifies the type of child object that can inherit
+        /// the ACE. This field is valid only if the
+        /// ACE_INHERITED_OBJECT_TYPE_PRESENT bit is set in the Flags member.
+        #[brw(if(flags.contains(AceFlags::ACE_INHERITED_OBJECT_TYPE_PRESENT)))]
+        inherited_object_type: Option<Guid>,
+
+        ///  The SID of a trustee.
+        #[brw(assert(sid.len().is_multiple_of(4)))]
+        sid: Sid,
+
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize + flags.raw_size() as usize
+            + object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + inherited_object_type.map(|g| g.raw_size() as usize).unwrap_or(0)
+            + sid.len()))]
+        #[bw(ignore)]
+        application_data_size: usize,
+
+        /// Optional application data. The size of the application data is
+        /// determined by the AceSize field of the ACE_HEADER.
+        #[br(count=application_data_size)]
+        application_data: Vec<u8>,
+
+        #[br(calc(if application_data.len() >= 4 {application_data[0..4] == [0x61, 0x72, 0x74, 0x78]} else {false}))]
+        #[bw(ignore)]
+        is_conditional: bool,
+
+        #[br(count = usize::from(*header.expected_padding()))]
+        _padding: Vec<u8>,
+    },
+
+    /// The ACCESS_ALLOWED_COMPOUND_ACE structure has been used by Windows NT
+    /// 4.0 to allow a server to access an object while it impersonates a
+    /// client. Access is allowed if the server is identified by `server_sid`
+    /// and the client is identified by `client_sid`.
+    ///
+    /// <https://learn.microsoft.com/en-us/windows/win32/secauthz/ace-header>
+    #[brw(magic = 0x04u8)]
+    ACCESS_ALLOWED_COMPOUND_ACE {
+        header: AceHeader,
+
+        compound_ace_type: CompoundAceType,
+
+        #[br(temp)]
+        #[bw(calc(0))]
+        _reserved: u16,
+
+        /// The SID of the server
+        #[brw(assert(server_sid.len().is_multiple_of(4)))]
+        server_sid: Sid,
+
+        /// The SID of the client, which is impersonated by the server
+        #[brw(assert(client_sid.len().is_multiple_of(4)))]
+        client_sid: Sid,
+
+        #[br(count = usize::from(*header.expected_padding()))]
+        _padding: Vec<u8>,
+    },
+
+    /// An ACE of a type which is not supported by this crate. Such ACEs are
+    /// never returned by [`crate::SecurityDescriptor::from_bytes`], but
+    /// [`crate::SecurityDescriptor::from_bytes_lenient`] keeps them, so that
+    /// they can be inspected and written back unchanged.
+    #[br(pre_assert(false))]
+    Unknown {
+        /// the value of the AceType field
+        ace_type: u8,
+
+        header: AceHeader,
+
+        #[br(temp, parse_with = remaining_ace_size, args(*header.ace_size(), ACE_HEADER_SIZE as usize))]
+        #[bw(ignore)]
+        raw_size: usize,
+
+        /// the bytes which follow the ACE header, including any padding
+        #[br(count=raw_size)]
+        raw: Vec<u8>,
+    },
 }
 
 impl Display for Ace {
+    /// writes the SDDL representation of this ACE. ACEs which have no SDDL
+    /// representation (see [`Ace::to_sddl`]) are written as octet string,
+    /// i.e. as `#`, followed by the hexadecimal digits of their binary form.
+    /// The octet string is empty if the ACE cannot be written in binary form
+    /// either. The SDDL parser rejects such ACEs with
+    /// [`crate::Error::NoSddlRepresentation`].
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let ace = Ace::access_allowed_compound(
+    ///     AceHeaderFlags::empty(),
+    ///     AccessMask::from(0x001f_01ff),
+    ///     Sid::try_from("S-1-5-18").unwrap(),
+    ///     Sid::try_from("S-1-5-32-544").unwrap(),
+    /// );
+    /// assert!(ace.to_string().starts_with("#0400"));
+    /// ```
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let type_string = self.type_string();
-        let flag_string = self.header().ace_flags().sddl_string();
-        let ace_rights = self.header().mask().sddl_string();
-        let object_guid = "";
-        let inherit_object_guid = "";
-        let sid_string = self
-            .sid()
-            .alias()
-            .map(|s| s.short_name().to_owned())
-            .unwrap_or(self.sid().to_string());
+        f.write_str(&self.display_string(None))
+    }
+}
+
+impl Ace {
+    /// returns the SDDL representation of this ACE, or
+    /// [`crate::Error::NoSddlRepresentation`] if the ACE cannot be written as
+    /// SDDL without losing information. This is the case for ACEs of unknown
+    /// types, for alarm callback and compound ACEs, which have no ACE string,
+    /// and for ACEs whose application data cannot be decoded.
+    pub fn to_sddl(&self) -> Result<String, crate::Error> {
+        self.sddl_string_with(None)
+    }
+
+    /// returns the SDDL representation of this ACE, using the access right
+    /// shorthands which are appropriate for an object of type `kind`
+    pub fn sddl_string_for(&self, kind: ObjectKind) -> Result<String, crate::Error> {
+        self.sddl_string_with(Some(kind))
+    }
+
+    /// returns the SDDL representation of this ACE, or its binary form as
+    /// octet string if there is no SDDL representation. ACEs which cannot be
+    /// written in binary form either are written as an empty octet string.
+    pub(crate) fn display_string(&self, kind: Option<ObjectKind>) -> String {
+        if let Ok(sddl) = self.sddl_string_with(kind) {
+            return sddl;
+        }
+        let mut cursor = Cursor::new(Vec::new());
+        let bytes = match cursor.write_le(self) {
+            Ok(()) => cursor.into_inner(),
+            Err(_) => Vec::new(),
+        };
+        bytes
+            .iter()
+            .fold(String::from("#"), |s, b| s + &format!("{b:02x}"))
+    }
+
+    pub(crate) fn sddl_string_with(
+        &self,
+        kind: Option<ObjectKind>,
+    ) -> Result<String, crate::Error> {
+        let type_string = self.type_string()?;
+        let flag_string = match self {
+            Ace::SYSTEM_ACCESS_FILTER_ACE { .. } => {
+                self.header().ace_flags().access_filter_sddl_string()
+            }
+            _ => self.header().ace_flags().sddl_string(),
+        };
+        let mask = self.header().mask();
+        let ace_rights = match (self, kind) {
+            (Ace::SYSTEM_MANDATORY_LABEL_ACE { .. }, _) => mask.mandatory_label_sddl_string(),
+            (_, Some(kind)) => mask.sddl_string_for(kind),
+            (_, None) => mask.sddl_string(),
+        };
+        let object_guid = self
+            .object_type()
+            .map(|g| g.to_string())
+            .unwrap_or_default();
+        let inherit_object_guid = self
+            .inherited_object_type()
+            .map(|g| g.to_string())
+            .unwrap_or_default();
+        let sid_string = sid_sddl_string(
+            self.trustee()
+                .ok_or_else(|| no_ace_string("ACEs of unknown types"))?,
+        );
         let sep = SDDL_SEPERATOR;
-        write!(f, "{type_string}{sep}{flag_string}{sep}{ace_rights}{sep}{object_guid}{sep}{inherit_object_guid}{sep}{sid_string}")
+        let mut sddl = format!("{type_string}{sep}{flag_string}{sep}{ace_rights}{sep}{object_guid}{sep}{inherit_object_guid}{sep}{sid_string}");
+
+        let undecodable = |error: crate::Error| {
+            crate::Error::NoSddlRepresentation(format!(
+                "the application data of the ACE cannot be decoded: {error}"
+            ))
+        };
+        if let Some(attribute) = self.resource_attribute().map_err(undecodable)? {
+            sddl.push_str(&format!("{sep}({attribute})"));
+        } else if let Some(condition) = self.condition().map_err(undecodable)? {
+            sddl.push_str(&format!(
+                "{sep}{SDDL_ACE_COND_BEGIN}{condition}{SDDL_ACE_COND_END}"
+            ));
+        } else if self
+            .application_data()
+            .is_some_and(|data| data.iter().any(|b| *b != 0))
+        {
+            return Err(crate::Error::NoSddlRepresentation(
+                "the application data of the ACE is not a conditional expression".to_owned(),
+            ));
+        }
+        Ok(sddl)
     }
 }
 
+fn no_ace_string(ace_type: &str) -> crate::Error {
+    crate::Error::NoSddlRepresentation(format!("there is no ACE string for {ace_type}"))
+}
+
+/// returns the SDDL alias of `sid`, or its string representation if there
+/// is no alias
+fn sid_sddl_string(sid: &Sid) -> String {
+    sid.alias()
+        .map(|s| s.short_name().to_owned())
+        .unwrap_or(sid.to_string())
+}
+
 impl RawSize for Ace {
     fn raw_size(&self) -> u16 {
         *self.header().ace_size()
     }
 }
 
+/// returns the size of the fields of an ACE which follow its header, or an
+/// error if the ACE including its header and padding does not fit into the
+/// AceSize field
+fn body_size(size: usize) -> Result<u16, crate::Error> {
+    let ace_size = (usize::from(ACE_HEADER_SIZE) + size).next_multiple_of(4);
+    u16::try_from(ace_size)
+        .and_then(|_| u16::try_from(size))
+        .map_err(|_| crate::Error::AceTooLarge(ace_size))
+}
+
 macro_rules! ctor {
     ($ctor_name: ident, $ty: ident) => {
         pub fn $ctor_name(flags: AceHeaderFlags, mask: AccessMask, sid: Sid) -> Self {
@@ -538,19 +936,21 @@ macro_rules! ctor_object {
             inherited_object_type: Option<Guid>,
             sid: Sid,
         ) -> Self {
+            let object_flags = object_type
+                .and(Some(AceFlags::ACE_OBJECT_TYPE_PRESENT))
+                .unwrap_or(AceFlags::empty())
+                | inherited_object_type
+                    .and(Some(AceFlags::ACE_INHERITED_OBJECT_TYPE_PRESENT))
+                    .unwrap_or(AceFlags::empty());
             let header = AceHeader::new(
                 flags,
-                sid.raw_size()
+                object_flags.raw_size()
+                    + sid.raw_size()
                     + object_type.map(|o| o.raw_size()).unwrap_or(0)
                     + inherited_object_type.map(|o| o.raw_size()).unwrap_or(0),
                 mask,
             );
-            let flags = object_type
-                .and(Some(AceFlags::ACE_OBJECT_TYPE_PRESENT))
-                .unwrap_or(AceFlags::empty())
-                | inherited_object_type
-                    .and(Some(AceFlags::ACE_INHERITED_OBJECT_TYPE_PRESENT))
-                    .unwrap_or(AceFlags::empty());
+            let flags = object_flags;
 
             let _padding = vec![0u8; *header.expected_padding() as usize];
             Self::$ty {
@@ -572,9 +972,12 @@ macro_rules! ctor_appdata {
             mask: AccessMask,
             sid: Sid,
             application_data: Vec<u8>,
-        ) -> Self {
-            let header =
-                AceHeader::new(flags, sid.raw_size() + application_data.len() as u16, mask);
+        ) -> Result<Self, crate::Error> {
+            let header = AceHeader::new(
+                flags,
+                body_size(usize::from(sid.raw_size()) + application_data.len())?,
+                mask,
+            );
 
             let is_conditional = if application_data.len() >= 4 {
                 application_data[0..4] == [0x61, 0x72, 0x74, 0x78]
@@ -583,13 +986,13 @@ macro_rules! ctor_appdata {
             };
 
             let _padding = vec![0u8; *header.expected_padding() as usize];
-            Self::$ty {
+            Ok(Self::$ty {
                 header,
                 sid,
                 application_data,
                 is_conditional,
                 _padding,
-            }
+            })
         }
     };
 }
@@ -603,21 +1006,26 @@ macro_rules! ctor_object_appdata {
             inherited_object_type: Option<Guid>,
             sid: Sid,
             application_data: Vec<u8>,
-        ) -> Self {
-            let header = AceHeader::new(
-                flags,
-                sid.raw_size()
-                    + object_type.map(|o| o.raw_size()).unwrap_or(0)
-                    + inherited_object_type.map(|o| o.raw_size()).unwrap_or(0)
-                    + application_data.len() as u16,
-                mask,
-            );
-            let flags = object_type
+        ) -> Result<Self, crate::Error> {
+            let object_flags = object_type
                 .and(Some(AceFlags::ACE_OBJECT_TYPE_PRESENT))
                 .unwrap_or(AceFlags::empty())
                 | inherited_object_type
                     .and(Some(AceFlags::ACE_INHERITED_OBJECT_TYPE_PRESENT))
                     .unwrap_or(AceFlags::empty());
+            let header = AceHeader::new(
+                flags,
+                body_size(
+                    usize::from(
+                        object_flags.raw_size()
+                            + sid.raw_size()
+                            + object_type.map(|o| o.raw_size()).unwrap_or(0)
+                            + inherited_object_type.map(|o| o.raw_size()).unwrap_or(0),
+                    ) + application_data.len(),
+                )?,
+                mask,
+            );
+            let flags = object_flags;
 
             let is_conditional = if application_data.len() >= 4 {
                 application_data[0..4] == [0x61, 0x72, 0x74, 0x78]
@@ -626,7 +1034,7 @@ macro_rules! ctor_object_appdata {
             };
 
             let _padding = vec![0u8; *header.expected_padding() as usize];
-            Self::$ty {
+            Ok(Self::$ty {
                 header,
                 flags,
                 sid,
@@ -635,7 +1043,7 @@ macro_rules! ctor_object_appdata {
                 application_data,
                 is_conditional,
                 _padding,
-            }
+            })
         }
     };
 }
@@ -662,9 +1070,40 @@ impl Ace {
     ctor_object_appdata!(system_audit_callback_object, SYSTEM_AUDIT_CALLBACK_OBJECT_ACE);
     ctor_appdata!(system_resource_attribute, SYSTEM_RESOURCE_ATTRIBUTE_ACE);
     ctor!(system_scoped_policy_id, SYSTEM_SCOPED_POLICY_ID_ACE);
+    ctor!(system_process_trust_label, SYSTEM_PROCESS_TRUST_LABEL_ACE);
+    ctor_appdata!(system_access_filter, SYSTEM_ACCESS_FILTER_ACE);
+    ctor!(system_alarm, SYSTEM_ALARM_ACE);
+    ctor_object_appdata!(system_alarm_object, SYSTEM_ALARM_OBJECT_ACE);
+    ctor_appdata!(system_alarm_callback, SYSTEM_ALARM_CALLBACK_ACE);
+    ctor_object_appdata!(system_alarm_callback_object, SYSTEM_ALARM_CALLBACK_OBJECT_ACE);
+
+    /// creates an ACCESS_ALLOWED_COMPOUND_ACE of type
+    /// [`CompoundAceType::COMPOUND_ACE_IMPERSONATION`]
+    pub fn access_allowed_compound(
+        flags: AceHeaderFlags,
+        mask: AccessMask,
+        server_sid: Sid,
+        client_sid: Sid,
+    ) -> Self {
+        let header = AceHeader::new(
+            flags,
+            COMPOUND_ACE_FIELDS_SIZE + server_sid.raw_size() + client_sid.raw_size(),
+            mask,
+        );
+        let _padding = vec![0u8; *header.expected_padding() as usize];
+        Self::ACCESS_ALLOWED_COMPOUND_ACE {
+            header,
+            compound_ace_type: CompoundAceType::COMPOUND_ACE_IMPERSONATION,
+            server_sid,
+            client_sid,
+            _padding,
+        }
+    }
 
-    fn type_string(&self) -> &'static str {
-        match self {
+    /// returns the ACE string of this ACE type, or an error for the ACE
+    /// types which have none
+    fn type_string(&self) -> Result<&'static str, crate::Error> {
+        Ok(match self {
             Ace::ACCESS_ALLOWED_ACE { .. } => SDDL_ACCESS_ALLOWED,
             Ace::ACCESS_ALLOWED_OBJECT_ACE { .. } => SDDL_OBJECT_ACCESS_ALLOWED,
             Ace::ACCESS_DENIED_ACE { .. } => SDDL_ACCESS_DENIED,
@@ -672,16 +1111,33 @@ impl Ace {
             Ace::ACCESS_ALLOWED_CALLBACK_ACE { .. } => SDDL_CALLBACK_ACCESS_ALLOWED,
             Ace::ACCESS_DENIED_CALLBACK_ACE { .. } => SDDL_CALLBACK_ACCESS_DENIED,
             Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE { .. } => SDDL_CALLBACK_OBJECT_ACCESS_ALLOWED,
-            Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE { .. } => "ZD",
+            Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE { .. } => SDDL_CALLBACK_OBJECT_ACCESS_DENIED,
             Ace::SYSTEM_AUDIT_ACE { .. } => SDDL_AUDIT,
             Ace::SYSTEM_AUDIT_OBJECT_ACE { .. } => SDDL_OBJECT_AUDIT,
             Ace::SYSTEM_AUDIT_CALLBACK_ACE { .. } => SDDL_CALLBACK_AUDIT,
             Ace::SYSTEM_MANDATORY_LABEL_ACE { .. } => SDDL_MANDATORY_LABEL,
-            Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE { .. } => "ZU",
+            Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE { .. } => SDDL_CALLBACK_OBJECT_AUDIT,
             Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE { .. } => SDDL_RESOURCE_ATTRIBUTE,
             Ace::SYSTEM_SCOPED_POLICY_ID_ACE { .. } => SDDL_SCOPED_POLICY_ID,
-        }
+            Ace::SYSTEM_PROCESS_TRUST_LABEL_ACE { .. } => SDDL_PROCESS_TRUST_LABEL,
+            Ace::SYSTEM_ACCESS_FILTER_ACE { .. } => SDDL_ACCESS_FILTER,
+            Ace::SYSTEM_ALARM_ACE { .. } => SDDL_ALARM,
+            Ace::SYSTEM_ALARM_OBJECT_ACE { .. } => SDDL_OBJECT_ALARM,
+            Ace::SYSTEM_ALARM_CALLBACK_ACE { .. } => {
+                return Err(no_ace_string("SYSTEM_ALARM_CALLBACK_ACE"))
+            }
+            Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE { .. } => {
+                return Err(no_ace_string("SYSTEM_ALARM_CALLBACK_OBJECT_ACE"))
+            }
+            Ace::ACCESS_ALLOWED_COMPOUND_ACE { .. } => {
+                return Err(no_ace_string("ACCESS_ALLOWED_COMPOUND_ACE"))
+            }
+            Ace::Unknown { ace_type, .. } => {
+                return Err(no_ace_string(&format!("ACE type {ace_type:#04x}")))
+            }
+        })
     }
+
     pub fn header(&self) -> &AceHeader {
         match self {
             Ace::ACCESS_ALLOWED_ACE { header, .. }
@@ -698,12 +1154,169 @@ impl Ace {
             | Ace::SYSTEM_MANDATORY_LABEL_ACE { header, .. }
             | Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE { header, .. }
             | Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE { header, .. }
-            | Ace::SYSTEM_SCOPED_POLICY_ID_ACE { header, .. } => header,
+            | Ace::SYSTEM_SCOPED_POLICY_ID_ACE { header, .. }
+            | Ace::SYSTEM_PROCESS_TRUST_LABEL_ACE { header, .. }
+            | Ace::SYSTEM_ACCESS_FILTER_ACE { header, .. }
+            | Ace::SYSTEM_ALARM_ACE { header, .. }
+            | Ace::SYSTEM_ALARM_OBJECT_ACE { header, .. }
+            | Ace::SYSTEM_ALARM_CALLBACK_ACE { header, .. }
+            | Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE { header, .. }
+            | Ace::ACCESS_ALLOWED_COMPOUND_ACE { header, .. }
+            | Ace::Unknown { header, .. } => header,
         }
     }
 
-    pub fn sid(&self) -> &Sid {
+    /// returns `true` if this ACE carries object type GUIDs, which requires
+    /// the containing ACL to have the revision `ACL_REVISION_DS`
+    pub fn is_object_ace(&self) -> bool {
+        matches!(
+            self,
+            Ace::ACCESS_ALLOWED_OBJECT_ACE { .. }
+                | Ace::ACCESS_DENIED_OBJECT_ACE { .. }
+                | Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE { .. }
+                | Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE { .. }
+                | Ace::SYSTEM_AUDIT_OBJECT_ACE { .. }
+                | Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE { .. }
+                | Ace::SYSTEM_ALARM_OBJECT_ACE { .. }
+                | Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE { .. }
+        )
+    }
+
+    /// returns the application data of callback ACEs
+    pub fn application_data(&self) -> Option<&[u8]> {
+        match self {
+            Ace::ACCESS_ALLOWED_CALLBACK_ACE {
+                application_data, ..
+            }
+            | Ace::ACCESS_DENIED_CALLBACK_ACE {
+                application_data, ..
+            }
+            | Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE {
+                application_data, ..
+            }
+            | Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_AUDIT_OBJECT_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_AUDIT_CALLBACK_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_ACCESS_FILTER_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_ALARM_OBJECT_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_ALARM_CALLBACK_ACE {
+                application_data, ..
+            }
+            | Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE {
+                application_data, ..
+            } => Some(application_data),
+            _ => None,
+        }
+    }
+
+    /// decodes the conditional expression of a conditional ACE. Returns
+    /// `Ok(None)` if this ACE has no conditional expression.
+    pub fn condition(&self) -> Result<Option<ConditionalExpression>, crate::Error> {
+        match self {
+            Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE { .. } => Ok(None),
+            _ => match self.application_data() {
+                Some(data) if data.starts_with(&CONDITIONAL_ACE_SIGNATURE) => {
+                    ConditionalExpression::from_bytes(data).map(Some)
+                }
+                _ => Ok(None),
+            },
+        }
+    }
+
+    /// decodes the attribute of a resource attribute ACE. Returns `Ok(None)`
+    /// if this is not a resource attribute ACE.
+    pub fn resource_attribute(&self) -> Result<Option<ClaimAttribute>, crate::Error> {
+        match self {
+            Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE {
+                application_data, ..
+            } => ClaimAttribute::from_bytes(application_data).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// returns the object type GUID of object ACEs
+    pub fn object_type(&self) -> Option<&Guid> {
         match self {
+            Ace::ACCESS_ALLOWED_OBJECT_ACE { object_type, .. }
+            | Ace::ACCESS_DENIED_OBJECT_ACE { object_type, .. }
+            | Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE { object_type, .. }
+            | Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE { object_type, .. }
+            | Ace::SYSTEM_AUDIT_OBJECT_ACE { object_type, .. }
+            | Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE { object_type, .. }
+            | Ace::SYSTEM_ALARM_OBJECT_ACE { object_type, .. }
+            | Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE { object_type, .. } => object_type.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// returns the inherited object type GUID of object ACEs
+    pub fn inherited_object_type(&self) -> Option<&Guid> {
+        match self {
+            Ace::ACCESS_ALLOWED_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            }
+            | Ace::ACCESS_DENIED_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            }
+            | Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            }
+            | Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            }
+            | Ace::SYSTEM_AUDIT_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            }
+            | Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            }
+            | Ace::SYSTEM_ALARM_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            }
+            | Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE {
+                inherited_object_type,
+                ..
+            } => inherited_object_type.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// returns the trustee of this ACE. For compound ACEs, this is the SID of
+    /// the server. ACEs of unknown types, which
+    /// [`crate::SecurityDescriptor::from_bytes_lenient`] creates, have no
+    /// trustee, so the NULL SID (`S-1-0-0`) is returned for them. Use
+    /// [`Ace::trustee`] to tell both cases apart.
+    pub fn sid(&self) -> &Sid {
+        self.trustee().unwrap_or(&NULL_SID)
+    }
+
+    /// returns the trustee of this ACE, or `None` if the ACE type is unknown.
+    /// For compound ACEs, this is the SID of the server.
+    pub fn trustee(&self) -> Option<&Sid> {
+        Some(match self {
             Ace::ACCESS_ALLOWED_ACE { header: _, sid, .. }
             | Ace::ACCESS_DENIED_ACE { header: _, sid, .. }
             | Ace::SYSTEM_AUDIT_ACE { header: _, sid, .. }
@@ -754,7 +1367,129 @@ impl Ace {
                 sid,
                 ..
             }
-            | Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE { header: _, sid, .. } => sid,
-        }
+            | Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE { header: _, sid, .. }
+            | Ace::SYSTEM_PROCESS_TRUST_LABEL_ACE { sid, .. }
+            | Ace::SYSTEM_ACCESS_FILTER_ACE { sid, .. }
+            | Ace::SYSTEM_ALARM_ACE { sid, .. }
+            | Ace::SYSTEM_ALARM_OBJECT_ACE { sid, .. }
+            | Ace::SYSTEM_ALARM_CALLBACK_ACE { sid, .. }
+            | Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE { sid, .. } => sid,
+            Ace::ACCESS_ALLOWED_COMPOUND_ACE { server_sid, .. } => server_sid,
+            Ace::Unknown { .. } => return None,
+        })
+    }
+
+    /// returns a copy of this ACE with other header flags, access mask and
+    /// trustee, which is used to create inherited ACEs. All other fields are
+    /// kept. ACEs of unknown types have no trustee, so `sid` is ignored for
+    /// them. Fails if the new trustee makes the ACE too large.
+    pub(crate) fn copy_with(
+        &self,
+        flags: AceHeaderFlags,
+        mask: AccessMask,
+        sid: Sid,
+    ) -> Result<Self, crate::Error> {
+        let object_type = self.object_type().copied();
+        let inherited_object_type = self.inherited_object_type().copied();
+        let data = self.application_data().unwrap_or_default().to_vec();
+        Ok(match self {
+            Ace::ACCESS_ALLOWED_ACE { .. } => Self::access_allowed(flags, mask, sid),
+            Ace::ACCESS_DENIED_ACE { .. } => Self::access_denied(flags, mask, sid),
+            Ace::ACCESS_ALLOWED_OBJECT_ACE { .. } => {
+                Self::access_allowed_object(flags, mask, object_type, inherited_object_type, sid)
+            }
+            Ace::ACCESS_DENIED_OBJECT_ACE { .. } => {
+                Self::access_denied_object(flags, mask, object_type, inherited_object_type, sid)
+            }
+            Ace::ACCESS_ALLOWED_CALLBACK_ACE { .. } => {
+                Self::access_allowed_callback(flags, mask, sid, data)?
+            }
+            Ace::ACCESS_DENIED_CALLBACK_ACE { .. } => {
+                Self::access_denied_callback(flags, mask, sid, data)?
+            }
+            Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE { .. } => Self::access_allowed_object_callback(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                data,
+            )?,
+            Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE { .. } => Self::access_denied_object_callback(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                data,
+            )?,
+            Ace::SYSTEM_AUDIT_ACE { .. } => Self::system_audit(flags, mask, sid),
+            Ace::SYSTEM_AUDIT_OBJECT_ACE { .. } => Self::system_audit_object(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                data,
+            )?,
+            Ace::SYSTEM_AUDIT_CALLBACK_ACE { .. } => {
+                Self::system_audit_callback(flags, mask, sid, data)?
+            }
+            Ace::SYSTEM_MANDATORY_LABEL_ACE { .. } => Self::system_mandatory_label(flags, mask, sid),
+            Ace::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE { .. } => Self::system_audit_callback_object(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                data,
+            )?,
+            Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE { .. } => {
+                Self::system_resource_attribute(flags, mask, sid, data)?
+            }
+            Ace::SYSTEM_SCOPED_POLICY_ID_ACE { .. } => {
+                Self::system_scoped_policy_id(flags, mask, sid)
+            }
+            Ace::SYSTEM_PROCESS_TRUST_LABEL_ACE { .. } => {
+                Self::system_process_trust_label(flags, mask, sid)
+            }
+            Ace::SYSTEM_ACCESS_FILTER_ACE { .. } => {
+                Self::system_access_filter(flags, mask, sid, data)?
+            }
+            Ace::SYSTEM_ALARM_ACE { .. } => Self::system_alarm(flags, mask, sid),
+            Ace::SYSTEM_ALARM_OBJECT_ACE { .. } => Self::system_alarm_object(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                data,
+            )?,
+            Ace::SYSTEM_ALARM_CALLBACK_ACE { .. } => {
+                Self::system_alarm_callback(flags, mask, sid, data)?
+            }
+            Ace::SYSTEM_ALARM_CALLBACK_OBJECT_ACE { .. } => Self::system_alarm_callback_object(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                data,
+            )?,
+            Ace::ACCESS_ALLOWED_COMPOUND_ACE { client_sid, .. } => {
+                Self::access_allowed_compound(flags, mask, sid, client_sid.clone())
+            }
+            Ace::Unknown { ace_type, raw, .. } => Self::Unknown {
+                ace_type: *ace_type,
+                header: AceHeader::new(flags, body_size(raw.len())?, mask),
+                raw: raw.clone(),
+            },
+        })
+    }
+
+    /// returns `true` if ACEs of type `ace_type` can be parsed from their
+    /// binary representation
+    pub(crate) fn is_supported_type(ace_type: u8) -> bool {
+        matches!(ace_type, 0x00..=0x15)
     }
 }
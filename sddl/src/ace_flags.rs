@@ -26,8 +26,12 @@ impl BinRead for AceFlags {
         endian: binrw::Endian,
         args: Self::Args<'_>,
     ) -> binrw::BinResult<Self> {
+        let pos = reader.stream_position()?;
         let raw_value: u32 = reader.read_type_args(endian, args)?;
-        Ok(AceFlags::from_bits(raw_value).unwrap())
+        AceFlags::from_bits(raw_value).ok_or_else(|| binrw::Error::AssertFail {
+            pos,
+            message: format!("invalid object ACE flags {raw_value:#x}"),
+        })
     }
 }
 
@@ -22,13 +22,13 @@ pub struct AceHeader {
     /// boundary. In cases where the AceSize field encompasses additional data
     /// for the callback ACEs types, that data is implementation-specific.
     /// Otherwise, this additional data is not interpreted and MUST be ignored.
-    #[brw(assert(ace_size%4 == 0))]
+    #[brw(assert(ace_size.is_multiple_of(4)))]
     #[serde(skip)]
     ace_size: u16,
 
     mask: AccessMask,
 
-    #[br(calc=std::cmp::min(0, 4-(ace_size%4)))]
+    #[br(calc = padding_to_dword(ace_size))]
     #[bw(ignore)]
     #[serde(skip)]
     expected_padding: u16,
@@ -37,9 +37,8 @@ pub struct AceHeader {
 impl AceHeader {
     pub fn new(ace_flags: AceHeaderFlags, ace_size_without_header: u16, mask: AccessMask) -> Self {
         // make sure the size is a multiple of 4
+        let expected_padding = padding_to_dword(ace_size_without_header);
 
-        let expected_padding = std::cmp::min(0, 4 - (ace_size_without_header % 4));
-        
         let ace_size = ACE_HEADER_SIZE + ace_size_without_header + expected_padding;
         Self {
             ace_flags,
@@ -50,6 +49,27 @@ impl AceHeader {
     }
 }
 
+/// returns the number of bytes which are needed to align `size` bytes to a
+/// multiple of 4. The AceSize of an ACE which is read is always aligned, so
+/// any padding is part of the data it covers.
+fn padding_to_dword(size: u16) -> u16 {
+    (4 - size % 4) % 4
+}
+
+/// returns the number of bytes of an ACE of `ace_size` bytes which follow
+/// the first `used` bytes, without reading anything. Fails at the current
+/// position of the reader if the ACE is smaller than `used` bytes.
+#[binrw::parser(reader)]
+pub(crate) fn remaining_ace_size(ace_size: u16, used: usize) -> binrw::BinResult<usize> {
+    let pos = reader.stream_position()?;
+    usize::from(ace_size)
+        .checked_sub(used)
+        .ok_or_else(|| binrw::Error::AssertFail {
+            pos,
+            message: format!("AceSize {ace_size} is too small, expected at least {used}"),
+        })
+}
+
 /// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/628ebb1d-c509-4ea0-a10f-77ef97ca4586>
 #[repr(u8)]
 #[binrw]
@@ -130,6 +150,13 @@ pub enum AceType {
     /// Mandatory label ACE that uses the SYSTEM_MANDATORY_LABEL_ACE
     /// (section 2.4.4.13) structure.
     SYSTEM_SCOPED_POLICY_ID_ACE_TYPE = 0x13,
+
+    /// Process trust label ACE that uses the SYSTEM_PROCESS_TRUST_LABEL_ACE
+    /// structure.
+    SYSTEM_PROCESS_TRUST_LABEL_ACE_TYPE = 0x14,
+
+    /// Access filter ACE that uses the SYSTEM_ACCESS_FILTER_ACE structure.
+    SYSTEM_ACCESS_FILTER_ACE_TYPE = 0x15,
 }
 
 bitflags! {
@@ -177,12 +204,28 @@ bitflags! {
         const SUCCESSFUL_ACCESS_ACE_FLAG = 0x40;
 
         const CRITICAL = 0x20;
+
+        /// Used with access filter ACEs to prevent them from being modified
+        /// by processes which are not protected. This flag shares its value
+        /// with `SUCCESSFUL_ACCESS_ACE_FLAG`.
         const TRUST_PROTECTED_FILTER = 0x40;
     }
 }
 
 impl AceHeaderFlags {
+    /// returns the SDDL representation of these flags in an ACE which is
+    /// not an access filter ACE
     pub fn sddl_string(&self) -> String {
+        self.write_sddl(SDDL_AUDIT_SUCCESS)
+    }
+
+    /// returns the SDDL representation of these flags in an access filter
+    /// ACE, where the value `0x40` means `TRUST_PROTECTED_FILTER`
+    pub fn access_filter_sddl_string(&self) -> String {
+        self.write_sddl(SDDL_TRUST_PROTECTED_FILTER)
+    }
+
+    fn write_sddl(&self, flag_0x40: &str) -> String {
         let mut sddl = String::with_capacity(16);
         let mut flag = |f: AceHeaderFlags, s: &str| {
             if self.contains(f) { sddl.push_str(s);}
@@ -192,10 +235,9 @@ impl AceHeaderFlags {
         flag(Self::NO_PROPAGATE_INHERIT_ACE, SDDL_NO_PROPAGATE);
         flag(Self::INHERIT_ONLY_ACE, SDDL_INHERIT_ONLY);
         flag(Self::INHERITED_ACE, SDDL_INHERITED);
-        flag(Self::SUCCESSFUL_ACCESS_ACE_FLAG, SDDL_AUDIT_SUCCESS);
+        flag(Self::SUCCESSFUL_ACCESS_ACE_FLAG, flag_0x40);
         flag(Self::FAILED_ACCESS_ACE_FLAG, SDDL_AUDIT_FAILURE);
         flag(Self::CRITICAL, SDDL_CRITICAL);
-        flag(Self::OBJECT_INHERIT_ACE, SDDL_TRUST_PROTECTED_FILTER);
         sddl
     }
 }
@@ -209,7 +251,7 @@ impl BinRead for AceHeaderFlags {
         args: Self::Args<'_>,
     ) -> binrw::BinResult<Self> {
         let raw_value: u8 = reader.read_type_args(endian, args)?;
-        Ok(AceHeaderFlags::from_bits(raw_value).unwrap())
+        Ok(AceHeaderFlags::from_bits_retain(raw_value))
     }
 }
 
@@ -227,3 +269,53 @@ impl BinWrite for AceHeaderFlags {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::{BinReaderExt, BinWriterExt};
+
+    use super::remaining_ace_size;
+    use crate::{AccessMask, Ace, AceHeaderFlags, Sid};
+
+    #[test]
+    fn test_unaligned_application_data_roundtrip() {
+        let ace = Ace::access_allowed_callback(
+            AceHeaderFlags::empty(),
+            AccessMask::READ_CONTROL,
+            Sid::try_from("S-1-1-0").unwrap(),
+            vec![1, 2, 3, 4, 5],
+        )
+        .unwrap();
+        assert_eq!(*ace.header().ace_size(), 8 + 12 + 8);
+        assert_eq!(*ace.header().expected_padding(), 3);
+
+        let mut writer = Cursor::new(Vec::new());
+        writer.write_le(&ace).unwrap();
+        let bytes = writer.into_inner();
+        assert_eq!(bytes.len(), 28);
+
+        let read: Ace = Cursor::new(&bytes).read_le().unwrap();
+        assert_eq!(read.header().ace_size(), ace.header().ace_size());
+        assert_eq!(*read.header().expected_padding(), 0);
+
+        let mut writer = Cursor::new(Vec::new());
+        writer.write_le(&read).unwrap();
+        assert_eq!(writer.into_inner(), bytes);
+    }
+
+    #[test]
+    fn test_remaining_ace_size() {
+        let mut reader = Cursor::new([0u8; 32]);
+        reader.set_position(28);
+        assert_eq!(
+            remaining_ace_size(&mut reader, binrw::Endian::Little, (24, 20)).unwrap(),
+            4
+        );
+
+        match remaining_ace_size(&mut reader, binrw::Endian::Little, (16, 20)) {
+            Err(binrw::Error::AssertFail { pos, .. }) => assert_eq!(pos, 28),
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+}
@@ -1,20 +1,35 @@
+use std::convert::Infallible;
 use std::fmt::Display;
 
 use crate::raw_size::RawSize;
 use crate::sddl_h::*;
 use crate::Ace;
 use crate::ControlFlags;
-use binrw::binrw;
+use crate::ObjectKind;
+use crate::ParseWarning;
+use crate::SddlComponent;
+use crate::{AceHeader, ACE_HEADER_SIZE};
+use binrw::{binrw, BinReaderExt};
+use std::io::Cursor;
 use derivative::Derivative;
 use getset::Getters;
 use serde::Serialize;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize)]
 pub enum AclType {
     SACL,
     DACL,
 }
 
+impl From<AclType> for SddlComponent {
+    fn from(acl_type: AclType) -> Self {
+        match acl_type {
+            AclType::SACL => Self::Sacl,
+            AclType::DACL => Self::Dacl,
+        }
+    }
+}
+
 impl AclType {
     pub fn sddl_string(&self) -> &'static str {
         match self {
@@ -53,10 +68,12 @@ pub struct Acl {
 
     /// Specifies the size, in bytes, of the ACL. This value includes the ACL
     /// structure, all the ACEs, and the potential unused memory.
+    #[bw(try_map(|_: &u16| binary_size(ace_list)))]
     #[serde(skip)]
     acl_size: u16,
 
     /// Specifies the number of ACEs stored in the ACL.
+    #[bw(try_map(|_: &u16| u16::try_from(ace_list.len())))]
     #[serde(skip)]
     ace_count: u16,
 
@@ -91,11 +108,13 @@ pub struct Acl {
 
 impl Display for Acl {
     /// <https://learn.microsoft.com/de-de/windows/win32/secauthz/security-descriptor-string-format>
+    ///
+    /// ACEs which have no SDDL representation are written as described in
+    /// the `Display` implementation of [`Ace`]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for ace in self.ace_list() {
-            write!(f, "{SDDL_ACE_BEGIN}{ace}{SDDL_ACE_END}")?;
-        }
-        Ok(())
+        let mut sddl = String::new();
+        let Ok(()) = self.write_aces(&mut sddl, display_string(None));
+        f.write_str(&sddl)
     }
 }
 
@@ -111,21 +130,44 @@ pub enum AclRevision {
     #[default]
     ACL_REVISION = 0x02,
 
+    /// ACLs of revision 0x03 can also contain compound ACEs (AceType 0x04).
+    ACL_REVISION3 = 0x03,
+
     /// When set to 0x04, AceTypes 0x05, 0x06, 0x07, 0x08, and 0x11 are allowed.
     /// ACLs of revision 0x04 are applicable only to directory service objects.
     /// An AceType of 0x11 is used for SACLs but not for DACLs.
     ACL_REVISION_DS = 0x04,
 }
 
+/// returns the size of an ACL which contains `ace_list`, or an error if the
+/// ACEs do not fit into an ACL
+fn binary_size(ace_list: &[Ace]) -> Result<u16, std::num::TryFromIntError> {
+    let size = usize::from(ACL_HEADER_SIZE)
+        + ace_list
+            .iter()
+            .map(|ace| usize::from(ace.raw_size()))
+            .sum::<usize>();
+    u16::try_from(size)
+}
+
 impl Acl {
+    /// creates a new ACL.
+    ///
+    /// The revision is raised if `ace_list` contains ACEs which are not
+    /// allowed in ACLs of `acl_revision`: object ACEs require
+    /// `ACL_REVISION_DS`, and compound ACEs require at least `ACL_REVISION3`.
+    /// If there are too many ACEs for a binary ACL, `acl_size` and
+    /// `ace_count` are saturated, and writing the ACL fails.
     pub fn new(
         acl_revision: AclRevision,
         acl_type: AclType,
         control_flags: ControlFlags,
         ace_list: Vec<Ace>,
     ) -> Self {
-        let acl_size = ACL_HEADER_SIZE + ace_list.iter().map(|ace| ace.raw_size()).sum::<u16>();
-        let ace_count = ace_list.len().try_into().unwrap();
+        let acl_size = binary_size(&ace_list).unwrap_or(u16::MAX);
+        let ace_count = u16::try_from(ace_list.len()).unwrap_or(u16::MAX);
+
+        let acl_revision = required_revision(acl_revision, &ace_list);
         Self {
             acl_revision,
             acl_size,
@@ -136,6 +178,130 @@ impl Acl {
         }
     }
 
+    /// parses the binary ACL at the beginning of `data` and skips all ACEs
+    /// which cannot be parsed. `offset` is the position of `data` in the
+    /// input, which is used in the warnings. Returns `None` if not even the
+    /// ACL header can be parsed.
+    pub(crate) fn from_bytes_lenient(
+        data: &[u8],
+        offset: usize,
+        acl_type: AclType,
+        control_flags: ControlFlags,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Option<Self> {
+        let header_size = usize::from(ACL_HEADER_SIZE);
+        if data.len() < header_size {
+            warnings.push(ParseWarning::InvalidComponent {
+                component: acl_type.into(),
+                offset,
+                reason: format!("the ACL header needs {header_size} bytes, but only {} are available", data.len()),
+            });
+            return None;
+        }
+
+        let acl_revision = match data[0] {
+            0x02 => AclRevision::ACL_REVISION,
+            0x03 => AclRevision::ACL_REVISION3,
+            0x04 => AclRevision::ACL_REVISION_DS,
+            revision => {
+                warnings.push(ParseWarning::InvalidAclRevision { acl_type, revision });
+                AclRevision::ACL_REVISION
+            }
+        };
+        let acl_size = u16::from_le_bytes([data[2], data[3]]);
+        let ace_count = u16::from_le_bytes([data[4], data[5]]);
+
+        let end = if (header_size..=data.len()).contains(&usize::from(acl_size)) {
+            usize::from(acl_size)
+        } else {
+            warnings.push(ParseWarning::InvalidAclSize {
+                acl_type,
+                acl_size,
+                available: data.len(),
+            });
+            data.len()
+        };
+
+        let mut ace_list = Vec::new();
+        let mut pos = header_size;
+        let mut found = 0;
+        while found < ace_count && pos + 4 <= end {
+            let component = SddlComponent::Ace {
+                acl_type,
+                index: usize::from(found),
+            };
+            let ace_size = usize::from(u16::from_le_bytes([data[pos + 2], data[pos + 3]]));
+            found += 1;
+
+            let reason = if ace_size < usize::from(ACE_HEADER_SIZE) {
+                Some(format!("AceSize {ace_size} is smaller than the ACE header"))
+            } else if pos + ace_size > end {
+                Some(format!("AceSize {ace_size} exceeds the end of the ACL"))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                // without a valid size, the beginning of the next ACE is unknown
+                warnings.push(ParseWarning::MalformedAce {
+                    component,
+                    offset: offset + pos,
+                    reason,
+                });
+                break;
+            }
+
+            let ace_data = &data[pos..pos + ace_size];
+            let ace_type = ace_data[0];
+            let ace = if Ace::is_supported_type(ace_type) {
+                Cursor::new(ace_data).read_le::<Ace>()
+            } else {
+                Cursor::new(&ace_data[1..]).read_le::<AceHeader>().map(|header| {
+                    warnings.push(ParseWarning::UnknownAceType {
+                        component,
+                        offset: offset + pos,
+                        ace_type,
+                    });
+                    Ace::Unknown {
+                        ace_type,
+                        header,
+                        raw: ace_data[usize::from(ACE_HEADER_SIZE)..].to_vec(),
+                    }
+                })
+            };
+            match ace {
+                Ok(ace) => ace_list.push(ace),
+                Err(why) => warnings.push(ParseWarning::MalformedAce {
+                    component,
+                    offset: offset + pos,
+                    reason: ace_error_reason(&why),
+                }),
+            }
+            pos += ace_size;
+        }
+
+        if found < ace_count {
+            warnings.push(ParseWarning::MissingAces {
+                acl_type,
+                expected: ace_count,
+                found,
+            });
+        }
+        // keep the revision which has been read, so that the ACL can be
+        // inspected and written back unchanged
+        let required = required_revision(acl_revision, &ace_list);
+        if required != acl_revision {
+            warnings.push(ParseWarning::InconsistentAclRevision {
+                acl_type,
+                revision: acl_revision,
+                required,
+            });
+        }
+        Some(Self {
+            acl_revision,
+            ..Self::new(acl_revision, acl_type, control_flags, ace_list)
+        })
+    }
+
     /// parses an SDDL string
     /// 
     /// # Example
@@ -147,19 +313,102 @@ impl Acl {
     /// assert_eq!(*acl.ace_count(), 4);
     /// ```
     pub fn from_sddl(value: &str, domain_rid: Option<&[u32]>) -> Result<Self, crate::Error> {
-        Ok(crate::parser::AclParser::new().parse(domain_rid, value)?)
+        crate::parser::AclParser::new()
+            .parse(domain_rid, value)
+            .map_err(|e| crate::SddlParseError::from_lalrpop(value, e))
     }
 
+    /// returns the SDDL representation of this ACL. ACEs which have no SDDL
+    /// representation are written as described in the `Display`
+    /// implementation of [`Ace`]. Use [`Acl::try_sddl_string`] to reject
+    /// such ACEs.
     pub fn sddl_string(&self) -> String {
-        let ace_list = self
-            .ace_list()
-            .iter()
-            .map(|ace: &Ace| format!("{SDDL_ACE_BEGIN}{ace}{SDDL_ACE_END}"))
-            .fold(String::new(), |a, b| a + &b);
+        let Ok(sddl) = self.sddl_string_with(display_string(None));
+        sddl
+    }
+
+    /// returns the SDDL representation of this ACL, or an error if an ACE
+    /// has no SDDL representation (see [`Ace::to_sddl`])
+    pub fn try_sddl_string(&self) -> Result<String, crate::Error> {
+        self.sddl_string_with(|ace| ace.sddl_string_with(None))
+    }
+
+    /// returns the SDDL representation of this ACL, using the access right
+    /// shorthands which are appropriate for an object of type `kind`
+    pub fn sddl_string_for(&self, kind: ObjectKind) -> Result<String, crate::Error> {
+        self.sddl_string_with(|ace| ace.sddl_string_with(Some(kind)))
+    }
 
+    fn sddl_string_with<E>(
+        &self,
+        ace_string: impl Fn(&Ace) -> Result<String, E>,
+    ) -> Result<String, E> {
         let acl_type = self.acl_type().sddl_string();
         let flags = self.control_flags().sddl_string(*self.acl_type());
-        format!("{acl_type}{SDDL_DELIMINATOR}{flags}{ace_list}")
+        let mut sddl = format!("{acl_type}{SDDL_DELIMINATOR}{flags}");
+        self.write_aces(&mut sddl, ace_string)?;
+        Ok(sddl)
+    }
+
+    /// appends the ACEs, without the ACL type and flags, to `sddl`.
+    /// `ace_string` returns the string representation of a single ACE.
+    pub(crate) fn write_aces<E>(
+        &self,
+        sddl: &mut String,
+        ace_string: impl Fn(&Ace) -> Result<String, E>,
+    ) -> Result<(), E> {
+        for ace in self.ace_list() {
+            sddl.push_str(SDDL_ACE_BEGIN);
+            sddl.push_str(&ace_string(ace)?);
+            sddl.push_str(SDDL_ACE_END);
+        }
+        Ok(())
+    }
+}
+
+/// writes ACEs like the `Display` implementation of [`Ace`] does, which never
+/// fails
+pub(crate) fn display_string(
+    kind: Option<ObjectKind>,
+) -> impl Fn(&Ace) -> Result<String, Infallible> {
+    move |ace| Ok(ace.display_string(kind))
+}
+
+/// returns `acl_revision`, raised to the lowest revision which allows all
+/// ACEs of `ace_list`. Object ACEs are only allowed in ACLs of revision
+/// ACL_REVISION_DS, and compound ACEs require at least ACL_REVISION3.
+fn required_revision(acl_revision: AclRevision, ace_list: &[Ace]) -> AclRevision {
+    if ace_list.iter().any(Ace::is_object_ace) {
+        AclRevision::ACL_REVISION_DS
+    } else if acl_revision == AclRevision::ACL_REVISION
+        && ace_list
+            .iter()
+            .any(|ace| matches!(ace, Ace::ACCESS_ALLOWED_COMPOUND_ACE { .. }))
+    {
+        AclRevision::ACL_REVISION3
+    } else {
+        acl_revision
+    }
+}
+
+/// returns the reason why an ACE could not be parsed, ignoring the errors of
+/// all ACE types whose type byte did not match
+fn ace_error_reason(error: &binrw::Error) -> String {
+    match error.root_cause() {
+        binrw::Error::EnumErrors { variant_errors, .. } => variant_errors
+            .iter()
+            .filter(|(variant, _)| *variant != "Unknown")
+            .map(|(_, error)| error.root_cause())
+            .find(|error| !matches!(error, binrw::Error::BadMagic { .. }))
+            .unwrap_or(error)
+            .to_string(),
+        error => error.to_string(),
+    }
+}
+
+impl RawSize for Acl {
+    fn raw_size(&self) -> u16 {
+        binary_size(self.ace_list()).unwrap_or(u16::MAX)
     }
 }
 
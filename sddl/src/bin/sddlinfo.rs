@@ -1,19 +1,170 @@
-use anyhow::Result;
-use clap::Parser;
+use std::{collections::HashSet, fs, path::PathBuf, sync::Arc};
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
 use getset::Getters;
-use sddl::SecurityDescriptor;
+use serde::Deserialize;
+use sddl::{
+    Guid, NameContext, NameFormat, ObjectKind, PathStyle, SecuredObject, SecurityDescriptor,
+    SecurityDescriptorTree,
+};
 
 /// parse an SDDL string and print its meaning
 #[derive(Parser, Debug, Getters)]
-#[clap(name="sddlinfo", author, version, about, long_about = None)]
+#[clap(name="sddlinfo", author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 #[getset(get="pub")]
 struct Cli {
-    sddl_string: String,
+    sddl_string: Option<String>,
+
+    /// LDIF, CSV or JSON export of a directory, which contains the names of
+    /// SIDs (`objectSid` and `sAMAccountName`) and schema GUIDs
+    /// (`schemaIDGUID` or `rightsGuid`)
+    #[clap(long)]
+    names: Option<PathBuf>,
+
+    /// the type of the object, which determines the names of the
+    /// object-specific access rights
+    #[clap(long)]
+    kind: Option<ObjectKind>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// compare the inherited ACEs of a tree of objects with the ACEs of their
+    /// parents
+    CheckInheritance {
+        /// JSON file which contains a list of objects with the fields `path`
+        /// and `sddl`, as created by
+        /// `Get-ChildItem -Recurse | Get-Acl | Select-Object Path, Sddl | ConvertTo-Json`
+        input: PathBuf,
+
+        /// the paths are distinguished names of Active Directory objects
+        #[clap(long)]
+        ds: bool,
+
+        /// the type of the objects, which determines how generic access
+        /// rights are mapped (defaults to `file` or `ds-object`)
+        #[clap(long)]
+        kind: Option<ObjectKind>,
+    },
+}
+
+/// an object in the input of `check-inheritance`
+#[derive(Deserialize)]
+struct TreeEntry {
+    #[serde(alias = "Path", alias = "distinguishedName", alias = "DistinguishedName")]
+    path: String,
+
+    #[serde(alias = "Sddl")]
+    sddl: String,
+
+    /// if this is missing, objects are containers if they have children
+    #[serde(default, alias = "PSIsContainer")]
+    is_container: Option<bool>,
+
+    /// the `schemaIDGUID` of the class of an AD object
+    #[serde(default)]
+    object_type: Option<String>,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let sd = SecurityDescriptor::from_sddl(cli.sddl_string(), None)?;
-    println!("{}", serde_json::to_string_pretty(&sd)?);
+    match cli.command() {
+        Some(Command::CheckInheritance { input, ds, kind }) => {
+            check_inheritance(input, *ds, *kind)
+        }
+        None => match cli.sddl_string() {
+            Some(sddl_string) => {
+                let sd = SecurityDescriptor::from_sddl(sddl_string, None)?;
+                let mut context = match cli.names() {
+                    Some(names) => load_names(names)?,
+                    None => NameContext::new(),
+                };
+                if let Some(kind) = cli.kind() {
+                    context = context.with_object_kind(*kind);
+                }
+                let context = Arc::new(context);
+                println!("{}", context.scope(|| serde_json::to_string_pretty(&sd))?);
+                Ok(())
+            }
+            None => bail!("either an SDDL string or a subcommand is required"),
+        },
+    }
+}
+
+fn load_names(path: &PathBuf) -> Result<NameContext> {
+    let Some(format) = path
+        .extension()
+        .and_then(|extension| NameFormat::from_extension(&extension.to_string_lossy()))
+    else {
+        bail!("unknown format of {}, expected .ldf, .ldif, .csv or .json", path.display());
+    };
+    let mut context = NameContext::new();
+    let (_, warnings) = context.load(format, &fs::read_to_string(path)?)?;
+    for warning in warnings {
+        eprintln!("{}: {warning}", path.display());
+    }
+    Ok(context)
+}
+
+fn check_inheritance(input: &PathBuf, ds: bool, kind: Option<ObjectKind>) -> Result<()> {
+    let (style, default_kind) = if ds {
+        (PathStyle::DistinguishedName, ObjectKind::DsObject)
+    } else {
+        (PathStyle::Filesystem, ObjectKind::File)
+    };
+    let mapping = kind.unwrap_or(default_kind).generic_mapping();
+
+    let entries: Vec<TreeEntry> = serde_json::from_str(&fs::read_to_string(input)?)?;
+
+    // PowerShell prefixes paths with the name of the provider
+    let path_of = |entry: &TreeEntry| {
+        entry
+            .path
+            .rsplit_once("::")
+            .map_or(entry.path.clone(), |(_, path)| path.to_owned())
+    };
+    let parents: HashSet<String> = entries
+        .iter()
+        .filter_map(|entry| style.parent(&path_of(entry)).map(str::to_lowercase))
+        .collect();
+
+    let mut tree = SecurityDescriptorTree::new(style);
+    for entry in &entries {
+        let path = path_of(entry);
+        let sd = SecurityDescriptor::from_sddl(&entry.sddl, None)?;
+        let is_container = entry
+            .is_container
+            .unwrap_or(ds || parents.contains(&path.to_lowercase()));
+        let mut object = SecuredObject::new(path, sd, is_container);
+        if let Some(object_type) = &entry.object_type {
+            object = object.with_object_type(Guid::try_from(&object_type[..])?);
+        }
+        tree.insert(object);
+    }
+
+    let deviations = tree.check_inheritance(&mapping);
+    for deviation in &deviations {
+        for ace in deviation.unexpected() {
+            println!(
+                "{}: {:?}: unexpected inherited ACE ({ace})",
+                deviation.path(),
+                deviation.acl_type()
+            );
+        }
+        for ace in deviation.missing() {
+            println!(
+                "{}: {:?}: missing inherited ACE ({ace})",
+                deviation.path(),
+                deviation.acl_type()
+            );
+        }
+    }
+    if !deviations.is_empty() {
+        bail!("{} ACLs do not match the ACLs of their parents", deviations.len());
+    }
     Ok(())
-}
\ No newline at end of file
+}
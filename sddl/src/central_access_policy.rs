@@ -0,0 +1,313 @@
+use getset::Getters;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::constants::{FILE_ALL, SECURITY_CREATOR_SID_AUTHORITY, SECURITY_NT_AUTHORITY};
+use crate::{
+    Ace, AceHeaderFlags, Acl, AclRevision, AclType, ConditionalExpression, ControlFlags,
+    SecurityDescriptor, Sid,
+};
+
+/// A rule of a central access policy, as it is stored in an
+/// `msAuthz-CentralAccessRule` object
+#[derive(Debug, Clone, Getters, Deserialize)]
+#[serde(try_from = "RawCentralAccessRule")]
+#[getset(get = "pub")]
+pub struct CentralAccessRule {
+    name: String,
+
+    /// the resources to which this rule applies (`msAuthz-ResourceCondition`).
+    /// The rule applies to all resources if there is no condition.
+    applies_to: Option<ConditionalExpression>,
+
+    /// the permissions which are enforced (`msAuthz-EffectiveSecurityPolicy`)
+    effective_dacl: Acl,
+
+    /// the permissions which are only evaluated for auditing
+    /// (`msAuthz-ProposedSecurityPolicy`)
+    staged_dacl: Option<Acl>,
+}
+
+impl CentralAccessRule {
+    pub fn new(name: impl Into<String>, effective_dacl: Acl) -> Self {
+        Self {
+            name: name.into(),
+            applies_to: None,
+            effective_dacl,
+            staged_dacl: None,
+        }
+    }
+
+    /// creates a rule from the string attributes of an
+    /// `msAuthz-CentralAccessRule` object. The policies are security
+    /// descriptors in SDDL format, of which only the DACL is used.
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let rule = CentralAccessRule::from_sddl(
+    ///     "Finance Documents",
+    ///     Some(r#"(@Resource.Department == "Finance")"#),
+    ///     "D:(A;;FA;;;BA)(XA;;FR;;;AU;(@User.Department == \"Finance\"))",
+    ///     None,
+    /// ).unwrap();
+    /// assert_eq!(rule.effective_dacl().ace_list().len(), 2);
+    /// ```
+    pub fn from_sddl(
+        name: impl Into<String>,
+        applies_to: Option<&str>,
+        effective_policy: &str,
+        staged_policy: Option<&str>,
+    ) -> Result<Self, crate::Error> {
+        let applies_to = applies_to
+            .filter(|condition| !condition.trim().is_empty())
+            .map(|condition| ConditionalExpression::from_sddl(condition, None))
+            .transpose()?;
+        Ok(Self {
+            name: name.into(),
+            applies_to,
+            effective_dacl: dacl_of(effective_policy)?,
+            staged_dacl: staged_policy.map(dacl_of).transpose()?,
+        })
+    }
+
+    pub fn with_applies_to(mut self, condition: ConditionalExpression) -> Self {
+        self.applies_to = Some(condition);
+        self
+    }
+
+    pub fn with_staged_dacl(mut self, staged_dacl: Acl) -> Self {
+        self.staged_dacl = Some(staged_dacl);
+        self
+    }
+}
+
+/// returns the DACL of a security descriptor in SDDL format
+fn dacl_of(policy: &str) -> Result<Acl, crate::Error> {
+    SecurityDescriptor::from_sddl(policy, None)?
+        .dacl()
+        .clone()
+        .ok_or_else(|| {
+            crate::Error::IllegalCentralAccessPolicy(format!("the policy '{policy}' has no DACL"))
+        })
+}
+
+/// A central access policy, which is referenced by the
+/// `SYSTEM_SCOPED_POLICY_ID_ACE` in the SACL of an object. The access which
+/// is granted by the DACL of the object is limited to the access which is
+/// granted by every applicable rule of the policy.
+///
+/// Policies can be loaded from JSON, in which conditions and DACLs are
+/// written in SDDL:
+///
+/// ```
+/// use sddl::*;
+/// let policies: Vec<CentralAccessPolicy> = serde_json::from_str(r#"[{
+///     "name": "Finance Policy",
+///     "capid": "S-1-17-1",
+///     "rules": [{
+///         "name": "Finance Documents",
+///         "applies_to": "(@Resource.Department == \"Finance\")",
+///         "effective_dacl": "D:(A;;FA;;;BA)",
+///         "staged_dacl": "D:(A;;FR;;;BA)"
+///     }]
+/// }]"#).unwrap();
+///
+/// let policy = policies.resolve(&Sid::try_from("S-1-17-1").unwrap()).unwrap();
+/// assert_eq!(policy.rules()[0].name(), "Finance Documents");
+/// ```
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct CentralAccessPolicy {
+    name: String,
+
+    /// the ID of this policy (`msAuthz-CentralAccessPolicyID`), which is
+    /// referenced by `SYSTEM_SCOPED_POLICY_ID_ACE`s
+    capid: Sid,
+
+    /// the rules of this policy (`msAuthz-MemberRulesInCAP`)
+    rules: Vec<CentralAccessRule>,
+}
+
+impl CentralAccessPolicy {
+    pub fn new(name: impl Into<String>, capid: Sid) -> Self {
+        Self {
+            name: name.into(),
+            capid,
+            rules: Vec::new(),
+        }
+    }
+
+    pub fn with_rule(mut self, rule: CentralAccessRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// returns the policy which Windows applies if the policy which is
+    /// referenced by an object cannot be found. It grants full access to
+    /// administrators, SYSTEM and the owner of the object.
+    pub fn recovery(capid: Sid) -> Self {
+        // D:(A;;FA;;;BA)(A;;FA;;;SY)(A;;FA;;;OW)
+        let ace_list = [
+            Sid::new_builtin(544),
+            SECURITY_NT_AUTHORITY.new_sid(&[18]),
+            SECURITY_CREATOR_SID_AUTHORITY.new_sid(&[4]),
+        ]
+        .into_iter()
+        .map(|sid| Ace::access_allowed(AceHeaderFlags::empty(), *FILE_ALL, sid))
+        .collect();
+        let dacl = Acl::new(
+            AclRevision::ACL_REVISION,
+            AclType::DACL,
+            ControlFlags::empty(),
+            ace_list,
+        );
+        let rule = CentralAccessRule::new("Recovery Rule", dacl);
+        Self::new("Recovery Policy", capid).with_rule(rule)
+    }
+}
+
+/// Looks up central access policies by their ID. This is implemented for
+/// lists of policies, which can be created from JSON or from the
+/// `msAuthz-CentralAccessPolicy` objects of an Active Directory.
+pub trait CentralAccessPolicyResolver {
+    /// returns the policy with the ID `capid`, or `None` if the policy is
+    /// unknown
+    fn resolve(&self, capid: &Sid) -> Option<&CentralAccessPolicy>;
+}
+
+impl CentralAccessPolicyResolver for [CentralAccessPolicy] {
+    fn resolve(&self, capid: &Sid) -> Option<&CentralAccessPolicy> {
+        self.iter().find(|policy| policy.capid() == capid)
+    }
+}
+
+impl CentralAccessPolicyResolver for Vec<CentralAccessPolicy> {
+    fn resolve(&self, capid: &Sid) -> Option<&CentralAccessPolicy> {
+        self.as_slice().resolve(capid)
+    }
+}
+
+/// the JSON representation of a [`CentralAccessRule`], which contains SDDL
+/// strings instead of parsed conditions and ACLs
+#[derive(Serialize, Deserialize)]
+struct RawCentralAccessRule {
+    name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    applies_to: Option<String>,
+
+    effective_dacl: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    staged_dacl: Option<String>,
+}
+
+impl TryFrom<RawCentralAccessRule> for CentralAccessRule {
+    type Error = crate::Error;
+
+    fn try_from(value: RawCentralAccessRule) -> Result<Self, Self::Error> {
+        Self::from_sddl(
+            value.name,
+            value.applies_to.as_deref(),
+            &value.effective_dacl,
+            value.staged_dacl.as_deref(),
+        )
+    }
+}
+
+impl TryFrom<&CentralAccessRule> for RawCentralAccessRule {
+    type Error = crate::Error;
+
+    /// fails if an ACL contains an ACE which has no SDDL representation
+    fn try_from(value: &CentralAccessRule) -> Result<Self, Self::Error> {
+        let dacl_string =
+            |dacl: &Acl| SecurityDescriptor::new(None, None, Some(dacl.clone()), None).to_sddl();
+        Ok(Self {
+            name: value.name.clone(),
+            applies_to: value
+                .applies_to
+                .as_ref()
+                .map(|condition| format!("({condition})")),
+            effective_dacl: dacl_string(&value.effective_dacl)?,
+            staged_dacl: value.staged_dacl.as_ref().map(dacl_string).transpose()?,
+        })
+    }
+}
+
+impl Serialize for CentralAccessRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawCentralAccessRule::try_from(self)
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_roundtrip() {
+        let rule = CentralAccessRule::from_sddl(
+            "r",
+            Some(r#"(@Resource.Department == "Finance")"#),
+            "D:(A;;FA;;;BA)",
+            Some("D:(A;;FR;;;BA)"),
+        )
+        .unwrap();
+        let policy =
+            CentralAccessPolicy::new("p", Sid::try_from("S-1-17-1").unwrap()).with_rule(rule);
+
+        let json = serde_json::to_value(&policy).unwrap();
+        assert_eq!(json["rules"][0]["effective_dacl"], "D:(A;;FA;;;BA)");
+        assert_eq!(
+            json["rules"][0]["applies_to"],
+            r#"(@Resource.Department == "Finance")"#
+        );
+
+        let decoded: CentralAccessPolicy = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.capid(), policy.capid());
+        assert_eq!(
+            decoded.rules()[0]
+                .staged_dacl()
+                .as_ref()
+                .unwrap()
+                .ace_list(),
+            policy.rules()[0].staged_dacl().as_ref().unwrap().ace_list()
+        );
+    }
+
+    #[test]
+    fn test_recovery_policy() {
+        let policy = CentralAccessPolicy::recovery(Sid::try_from("S-1-17-1").unwrap());
+        let expected = Acl::from_sddl("D:(A;;FA;;;BA)(A;;FA;;;SY)(A;;FA;;;OW)", None).unwrap();
+        assert_eq!(policy.rules()[0].effective_dacl(), &expected);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(CentralAccessRule::from_sddl("r", None, "O:BA", None).is_err());
+        assert!(CentralAccessRule::from_sddl("r", Some("(@Resource.x =="), "D:", None).is_err());
+        assert!(serde_json::from_str::<CentralAccessRule>(
+            r#"{ "name": "r", "effective_dacl": "D:(A;;XX;;;BA)" }"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_rule_without_sddl_representation() {
+        let compound = crate::Ace::access_allowed_compound(
+            crate::AceHeaderFlags::empty(),
+            crate::AccessMask::from(0x001f_01ff),
+            Sid::try_from("S-1-5-18").unwrap(),
+            Sid::try_from("S-1-5-32-544").unwrap(),
+        );
+        let dacl = Acl::new(
+            crate::AclRevision::ACL_REVISION,
+            crate::AclType::DACL,
+            crate::ControlFlags::empty(),
+            vec![compound],
+        );
+        let rule = CentralAccessRule::new("r", dacl);
+        assert!(serde_json::to_value(&rule).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+use std::fmt::Display;
+use std::io::Cursor;
+
+use binrw::{BinReaderExt, BinWriterExt};
+use bitflags::bitflags;
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use strum::FromRepr;
+
+use crate::{ClaimValue, Sid};
+
+/// size of the fixed fields of a CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1
+/// structure, without the value offsets
+const HEADER_SIZE: usize = 16;
+
+/// Type of the values of a claim attribute, as it is stored in the ValueType
+/// field of a CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1 structure
+#[repr(u16)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Serialize, Deserialize, FromRepr)]
+pub enum ClaimValueType {
+    Int64 = 0x0001,
+    UInt64 = 0x0002,
+    String = 0x0003,
+    Sid = 0x0005,
+    Boolean = 0x0006,
+    OctetString = 0x0010,
+}
+
+impl ClaimValueType {
+    /// returns the type of `value`
+    pub fn of(value: &ClaimValue) -> Self {
+        match value {
+            ClaimValue::Int64(_) => Self::Int64,
+            ClaimValue::UInt64(_) => Self::UInt64,
+            ClaimValue::String(_) => Self::String,
+            ClaimValue::Sid(_) => Self::Sid,
+            ClaimValue::Boolean(_) => Self::Boolean,
+            ClaimValue::OctetString(_) => Self::OctetString,
+        }
+    }
+
+    /// returns the abbreviation of this type which is used in SDDL
+    pub fn sddl_string(&self) -> &'static str {
+        match self {
+            Self::Int64 => "TI",
+            Self::UInt64 => "TU",
+            Self::String => "TS",
+            Self::Sid => "TD",
+            Self::Boolean => "TB",
+            Self::OctetString => "TX",
+        }
+    }
+}
+
+bitflags! {
+    /// Flags of a claim attribute. The lower 16 bits are defined by
+    /// [MS-DTYP] section 2.4.10.1, the upper 16 bits are used by the file
+    /// classification infrastructure.
+    #[derive(Eq, PartialEq, Debug, Copy, Clone, Serialize)]
+    pub struct ClaimAttributeFlags: u32 {
+        const NON_INHERITABLE = 0x0001;
+        const VALUE_CASE_SENSITIVE = 0x0002;
+        const USE_FOR_DENY_ONLY = 0x0004;
+        const DISABLED_BY_DEFAULT = 0x0008;
+        const DISABLED = 0x0010;
+        const MANDATORY = 0x0020;
+        const MANUAL = 0x0001_0000;
+        const POLICY_DERIVED = 0x0002_0000;
+    }
+}
+
+/// A resource attribute, which is stored in a SYSTEM_RESOURCE_ATTRIBUTE_ACE
+/// in the CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1 format ([MS-DTYP] section
+/// 2.4.10.1)
+///
+/// ```
+/// use sddl::*;
+/// let attribute = ClaimAttribute::new(
+///     "Secrecy",
+///     ClaimValueType::UInt64,
+///     ClaimAttributeFlags::empty(),
+///     vec![3u64.into()],
+/// )
+/// .unwrap();
+/// assert_eq!(attribute.to_string(), r#""Secrecy",TU,0x0,3"#);
+/// assert_eq!(ClaimAttribute::from_bytes(&attribute.to_bytes()).unwrap(), attribute);
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Getters, CopyGetters, Serialize)]
+pub struct ClaimAttribute {
+    #[getset(get = "pub")]
+    name: String,
+
+    #[getset(get_copy = "pub")]
+    value_type: ClaimValueType,
+
+    #[getset(get_copy = "pub")]
+    flags: ClaimAttributeFlags,
+
+    #[getset(get = "pub")]
+    values: Vec<ClaimValue>,
+}
+
+impl ClaimAttribute {
+    /// creates a new attribute, or returns an error if one of `values` is
+    /// not of type `value_type`
+    pub fn new(
+        name: impl Into<String>,
+        value_type: ClaimValueType,
+        flags: ClaimAttributeFlags,
+        values: Vec<ClaimValue>,
+    ) -> Result<Self, crate::Error> {
+        let name = name.into();
+        if let Some(value) = values.iter().find(|v| ClaimValueType::of(v) != value_type) {
+            return Err(crate::Error::IllegalClaimAttribute(format!(
+                "value {value} of attribute '{name}' is not of type {value_type:?}"
+            )));
+        }
+        Ok(Self {
+            name,
+            value_type,
+            flags,
+            values,
+        })
+    }
+
+    /// decodes an attribute in the CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1
+    /// format, in which all strings and values are referenced by their
+    /// offset from the beginning of `bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, crate::Error> {
+        let name = read_string(bytes, read_u32(bytes, 0)? as usize)?;
+        let raw_type = read_u16(bytes, 4)?;
+        let value_type = ClaimValueType::from_repr(raw_type).ok_or_else(|| {
+            crate::Error::IllegalClaimAttribute(format!("unknown value type {raw_type:#06x}"))
+        })?;
+        let flags = ClaimAttributeFlags::from_bits_retain(read_u32(bytes, 8)?);
+        let value_count = read_u32(bytes, 12)? as usize;
+
+        let mut values = Vec::new();
+        for index in 0..value_count {
+            let offset = read_u32(bytes, HEADER_SIZE + 4 * index)? as usize;
+            values.push(match value_type {
+                ClaimValueType::Int64 => ClaimValue::Int64(read_u64(bytes, offset)? as i64),
+                ClaimValueType::UInt64 => ClaimValue::UInt64(read_u64(bytes, offset)?),
+                ClaimValueType::Boolean => ClaimValue::Boolean(read_u64(bytes, offset)? != 0),
+                ClaimValueType::String => ClaimValue::String(read_string(bytes, offset)?),
+                ClaimValueType::OctetString => {
+                    ClaimValue::OctetString(read_octet_string(bytes, offset)?.to_vec())
+                }
+                ClaimValueType::Sid => {
+                    let sid: Sid = Cursor::new(read_octet_string(bytes, offset)?).read_le()?;
+                    ClaimValue::Sid(sid)
+                }
+            });
+        }
+        Self::new(name, value_type, flags, values)
+    }
+
+    /// encodes this attribute in the CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1
+    /// format. The name is stored directly after the value offsets, and is
+    /// followed by the values.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        let name_offset = HEADER_SIZE + 4 * self.values.len();
+        encode_string(&mut data, &self.name);
+
+        let mut value_offsets = Vec::with_capacity(self.values.len());
+        for value in &self.values {
+            value_offsets.push((name_offset + data.len()) as u32);
+            match value {
+                ClaimValue::Int64(v) => data.extend_from_slice(&v.to_le_bytes()),
+                ClaimValue::UInt64(v) => data.extend_from_slice(&v.to_le_bytes()),
+                ClaimValue::Boolean(v) => data.extend_from_slice(&u64::from(*v).to_le_bytes()),
+                ClaimValue::String(v) => encode_string(&mut data, v),
+                ClaimValue::OctetString(v) => encode_octet_string(&mut data, v),
+                ClaimValue::Sid(sid) => {
+                    let mut cursor = Cursor::new(Vec::new());
+                    cursor
+                        .write_le(sid)
+                        .expect("writing to a Vec<u8> must not fail");
+                    encode_octet_string(&mut data, &cursor.into_inner());
+                }
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(name_offset + data.len());
+        bytes.extend_from_slice(&(name_offset as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.value_type as u16).to_le_bytes());
+        bytes.extend_from_slice(&0u16.to_le_bytes());
+        bytes.extend_from_slice(&self.flags.bits().to_le_bytes());
+        bytes.extend_from_slice(&(self.values.len() as u32).to_le_bytes());
+        for offset in value_offsets {
+            bytes.extend_from_slice(&offset.to_le_bytes());
+        }
+        bytes.extend(data);
+        bytes
+    }
+}
+
+/// writes the attribute in the format which is used in the SDDL
+/// representation of a resource attribute ACE, without the enclosing
+/// parentheses
+impl Display for ClaimAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "\"{}\",{},{:#x}",
+            self.name,
+            self.value_type.sddl_string(),
+            self.flags.bits()
+        )?;
+        for value in &self.values {
+            match value {
+                ClaimValue::Boolean(v) => write!(f, ",{}", u8::from(*v))?,
+                ClaimValue::Sid(sid) => match sid.alias() {
+                    Some(alias) => write!(f, ",SID({})", alias.short_name())?,
+                    None => write!(f, ",SID({sid})")?,
+                },
+                value => write!(f, ",{value}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_bytes(bytes: &[u8], offset: usize, count: usize) -> Result<&[u8], crate::Error> {
+    offset
+        .checked_add(count)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or_else(|| {
+            crate::Error::IllegalClaimAttribute(format!(
+                "{count} bytes at offset {offset} are out of bounds"
+            ))
+        })
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, crate::Error> {
+    let b = read_bytes(bytes, offset, 2)?;
+    Ok(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, crate::Error> {
+    let b = read_bytes(bytes, offset, 4)?;
+    Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, crate::Error> {
+    let b = read_bytes(bytes, offset, 8)?;
+    Ok(u64::from_le_bytes(b.try_into().expect("slice has 8 bytes")))
+}
+
+/// reads a null-terminated UTF-16 string
+fn read_string(bytes: &[u8], offset: usize) -> Result<String, crate::Error> {
+    let chars: Vec<u16> = bytes
+        .get(offset..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0)
+        .collect();
+    if offset + 2 * chars.len() + 2 > bytes.len() {
+        return Err(crate::Error::IllegalClaimAttribute(format!(
+            "unterminated string at offset {offset}"
+        )));
+    }
+    String::from_utf16(&chars).map_err(|why| {
+        crate::Error::IllegalClaimAttribute(format!("illegal unicode string: {why}"))
+    })
+}
+
+/// reads a CLAIM_SECURITY_ATTRIBUTE_OCTET_STRING_RELATIVE structure
+fn read_octet_string(bytes: &[u8], offset: usize) -> Result<&[u8], crate::Error> {
+    let length = read_u32(bytes, offset)? as usize;
+    read_bytes(bytes, offset + 4, length)
+}
+
+fn encode_string(bytes: &mut Vec<u8>, value: &str) {
+    for c in value.encode_utf16().chain(std::iter::once(0)) {
+        bytes.extend_from_slice(&c.to_le_bytes());
+    }
+}
+
+fn encode_octet_string(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_layout() {
+        let attribute = ClaimAttribute::new(
+            "Ab",
+            ClaimValueType::UInt64,
+            ClaimAttributeFlags::MANUAL,
+            vec![3u64.into()],
+        )
+        .unwrap();
+
+        #[rustfmt::skip]
+        let expected = [
+            0x14, 0x00, 0x00, 0x00, // name offset
+            0x02, 0x00, 0x00, 0x00, // value type, reserved
+            0x00, 0x00, 0x01, 0x00, // flags
+            0x01, 0x00, 0x00, 0x00, // value count
+            0x1a, 0x00, 0x00, 0x00, // value offset
+            0x41, 0x00, 0x62, 0x00, 0x00, 0x00, // "Ab"
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(attribute.to_bytes(), expected);
+        assert_eq!(ClaimAttribute::from_bytes(&expected).unwrap(), attribute);
+    }
+
+    #[test]
+    fn test_roundtrip_all_types() {
+        for (value_type, values) in [
+            (ClaimValueType::Int64, vec![(-5i64).into(), 7i64.into()]),
+            (ClaimValueType::UInt64, vec![u64::MAX.into()]),
+            (ClaimValueType::String, vec!["a".into(), "bcd".into()]),
+            (
+                ClaimValueType::Sid,
+                vec![Sid::try_from("S-1-5-32-544").unwrap().into()],
+            ),
+            (ClaimValueType::Boolean, vec![true.into(), false.into()]),
+            (
+                ClaimValueType::OctetString,
+                vec![ClaimValue::OctetString(vec![1, 2, 3])],
+            ),
+            (ClaimValueType::String, vec![]),
+        ] {
+            let attribute =
+                ClaimAttribute::new("x", value_type, ClaimAttributeFlags::empty(), values).unwrap();
+            assert_eq!(
+                ClaimAttribute::from_bytes(&attribute.to_bytes()).unwrap(),
+                attribute
+            );
+        }
+    }
+
+    #[test]
+    fn test_mismatching_value_type() {
+        assert!(ClaimAttribute::new(
+            "x",
+            ClaimValueType::Int64,
+            ClaimAttributeFlags::empty(),
+            vec!["a".into()]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_malformed_data() {
+        let bytes = ClaimAttribute::new(
+            "Secrecy",
+            ClaimValueType::String,
+            ClaimAttributeFlags::empty(),
+            vec!["High".into()],
+        )
+        .unwrap()
+        .to_bytes();
+
+        for length in 0..bytes.len() {
+            assert!(ClaimAttribute::from_bytes(&bytes[..length]).is_err());
+        }
+
+        let mut unknown_type = bytes.clone();
+        unknown_type[4] = 0x04;
+        assert!(ClaimAttribute::from_bytes(&unknown_type).is_err());
+    }
+}
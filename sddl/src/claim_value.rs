@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Literal, Sid};
+
+/// A single value of a claim or resource attribute
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub enum ClaimValue {
+    Int64(i64),
+    UInt64(u64),
+    String(String),
+    Sid(Sid),
+    Boolean(bool),
+    OctetString(Vec<u8>),
+}
+
+impl ClaimValue {
+    fn as_number(&self) -> Option<i128> {
+        match self {
+            ClaimValue::Int64(v) => Some(i128::from(*v)),
+            ClaimValue::UInt64(v) => Some(i128::from(*v)),
+            ClaimValue::Boolean(v) => Some(i128::from(*v)),
+            _ => None,
+        }
+    }
+
+    /// compares two values the way conditional expressions do: numbers are
+    /// compared by their value, strings are compared case-insensitive.
+    /// Returns `None` if the values cannot be compared, which includes SIDs,
+    /// because they have no order. Use [`ClaimValue::equals`] to check for
+    /// equality.
+    pub fn compare(&self, other: &ClaimValue) -> Option<Ordering> {
+        if let (Some(lhs), Some(rhs)) = (self.as_number(), other.as_number()) {
+            return Some(lhs.cmp(&rhs));
+        }
+        match (self, other) {
+            (ClaimValue::String(lhs), ClaimValue::String(rhs)) => {
+                Some(lhs.to_lowercase().cmp(&rhs.to_lowercase()))
+            }
+            (ClaimValue::OctetString(lhs), ClaimValue::OctetString(rhs)) => Some(lhs.cmp(rhs)),
+            _ => None,
+        }
+    }
+
+    /// checks whether two values are equal the way conditional expressions
+    /// do. Returns `None` if the values cannot be compared.
+    pub fn equals(&self, other: &ClaimValue) -> Option<bool> {
+        match (self, other) {
+            (ClaimValue::Sid(lhs), ClaimValue::Sid(rhs)) => Some(lhs == rhs),
+            _ => self.compare(other).map(Ordering::is_eq),
+        }
+    }
+
+    /// returns `true` if both values are comparable and equal
+    pub fn matches(&self, other: &ClaimValue) -> bool {
+        self.equals(other) == Some(true)
+    }
+
+    /// converts a literal of a conditional expression into a list of values.
+    /// Composite literals are flattened.
+    pub(crate) fn from_literal(literal: &Literal) -> Vec<Self> {
+        match literal {
+            Literal::Integer(i) => vec![ClaimValue::Int64(i.value())],
+            Literal::String(s) => vec![ClaimValue::String(s.clone())],
+            Literal::OctetString(o) => vec![ClaimValue::OctetString(o.clone())],
+            Literal::Sid(sid) => vec![ClaimValue::Sid(sid.clone())],
+            Literal::Composite(elements) => elements.iter().flat_map(Self::from_literal).collect(),
+        }
+    }
+}
+
+impl Display for ClaimValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClaimValue::Int64(v) => write!(f, "{v}"),
+            ClaimValue::UInt64(v) => write!(f, "{v}"),
+            ClaimValue::String(v) => write!(f, "\"{v}\""),
+            ClaimValue::Sid(v) => write!(f, "{v}"),
+            ClaimValue::Boolean(v) => write!(f, "{v}"),
+            ClaimValue::OctetString(v) => {
+                write!(f, "#")?;
+                for o in v {
+                    write!(f, "{o:02x}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<i64> for ClaimValue {
+    fn from(value: i64) -> Self {
+        Self::Int64(value)
+    }
+}
+
+impl From<u64> for ClaimValue {
+    fn from(value: u64) -> Self {
+        Self::UInt64(value)
+    }
+}
+
+impl From<bool> for ClaimValue {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl From<&str> for ClaimValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for ClaimValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Sid> for ClaimValue {
+    fn from(value: Sid) -> Self {
+        Self::Sid(value)
+    }
+}
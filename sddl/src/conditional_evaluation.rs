@@ -0,0 +1,401 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Not;
+
+use serde::Serialize;
+
+use crate::{
+    Attribute, AttributeSource, ClaimValue, ConditionalExpression, Operand, RelationalOperator,
+    Sid, UnaryOperator,
+};
+
+/// Result of the evaluation of a conditional expression
+///
+/// Conditional expressions use a three-valued logic: if an operand
+/// cannot be evaluated (e.g. because an attribute is missing), the result is
+/// `Unknown`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize)]
+pub enum Tristate {
+    True,
+    False,
+    Unknown,
+}
+
+impl Tristate {
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::False, _) | (_, Self::False) => Self::False,
+            (Self::True, Self::True) => Self::True,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::True, _) | (_, Self::True) => Self::True,
+            (Self::False, Self::False) => Self::False,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn is_true(&self) -> bool {
+        *self == Self::True
+    }
+}
+
+impl From<bool> for Tristate {
+    fn from(value: bool) -> Self {
+        if value {
+            Self::True
+        } else {
+            Self::False
+        }
+    }
+}
+
+impl Not for Tristate {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        match self {
+            Self::True => Self::False,
+            Self::False => Self::True,
+            Self::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl Display for Tristate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::True => write!(f, "TRUE"),
+            Self::False => write!(f, "FALSE"),
+            Self::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+/// Information about the security context in which a conditional expression
+/// is evaluated
+///
+/// Attribute names are compared case-insensitive.
+///
+/// ```
+/// use sddl::*;
+/// let ctx = EvaluationContext::default()
+///     .with_user_claim("Title", vec!["PM".into()]);
+/// let expr = ConditionalExpression::from_sddl(r#"(@User.title == "pm")"#, None).unwrap();
+/// assert_eq!(expr.evaluate(&ctx), Tristate::True);
+/// ```
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct EvaluationContext {
+    user_claims: HashMap<String, Vec<ClaimValue>>,
+    device_claims: HashMap<String, Vec<ClaimValue>>,
+    local_claims: HashMap<String, Vec<ClaimValue>>,
+    resource_attributes: HashMap<String, Vec<ClaimValue>>,
+    sids: Vec<Sid>,
+    device_sids: Vec<Sid>,
+}
+
+impl EvaluationContext {
+    pub fn with_user_claim(mut self, name: &str, values: Vec<ClaimValue>) -> Self {
+        self.user_claims.insert(name.to_lowercase(), values);
+        self
+    }
+
+    pub fn with_device_claim(mut self, name: &str, values: Vec<ClaimValue>) -> Self {
+        self.device_claims.insert(name.to_lowercase(), values);
+        self
+    }
+
+    pub fn with_local_claim(mut self, name: &str, values: Vec<ClaimValue>) -> Self {
+        self.local_claims.insert(name.to_lowercase(), values);
+        self
+    }
+
+    pub fn with_resource_attribute(mut self, name: &str, values: Vec<ClaimValue>) -> Self {
+        self.resource_attributes.insert(name.to_lowercase(), values);
+        self
+    }
+
+    /// adds a SID (the user SID or a group SID) to the token
+    pub fn with_sid(mut self, sid: Sid) -> Self {
+        self.sids.push(sid);
+        self
+    }
+
+    /// adds a group SID of the device
+    pub fn with_device_sid(mut self, sid: Sid) -> Self {
+        self.device_sids.push(sid);
+        self
+    }
+
+    pub fn sids(&self) -> &[Sid] {
+        &self.sids
+    }
+
+    pub fn device_sids(&self) -> &[Sid] {
+        &self.device_sids
+    }
+
+    /// returns the values of an attribute, or `None` if the attribute does
+    /// not exist
+    pub fn attribute(&self, attribute: &Attribute) -> Option<&[ClaimValue]> {
+        let claims = match attribute.source() {
+            AttributeSource::Token => &self.local_claims,
+            AttributeSource::User => &self.user_claims,
+            AttributeSource::Resource => &self.resource_attributes,
+            AttributeSource::Device => &self.device_claims,
+        };
+        claims
+            .get(&attribute.name().to_lowercase())
+            .map(Vec::as_slice)
+    }
+
+    fn values(&self, operand: &Operand) -> Option<Vec<ClaimValue>> {
+        match operand {
+            Operand::Attribute(attribute) => self.attribute(attribute).map(<[ClaimValue]>::to_vec),
+            Operand::Literal(literal) => Some(ClaimValue::from_literal(literal)),
+        }
+    }
+}
+
+impl ConditionalExpression {
+    /// evaluates the expression in the given context
+    pub fn evaluate(&self, ctx: &EvaluationContext) -> Tristate {
+        match self {
+            Self::And(lhs, rhs) => lhs.evaluate(ctx).and(rhs.evaluate(ctx)),
+            Self::Or(lhs, rhs) => lhs.evaluate(ctx).or(rhs.evaluate(ctx)),
+            Self::Not(expr) => expr.evaluate(ctx).not(),
+            Self::Attribute(attribute) => evaluate_attribute(ctx, attribute),
+            Self::Unary(op, operand) => evaluate_unary(ctx, *op, operand),
+            Self::Relational(op, lhs, rhs) => evaluate_relational(ctx, *op, lhs, rhs),
+        }
+    }
+}
+
+/// an attribute used as boolean is `TRUE` if it has a single non-zero
+/// numeric value, and `FALSE` if that value is zero
+fn evaluate_attribute(ctx: &EvaluationContext, attribute: &Attribute) -> Tristate {
+    match ctx.attribute(attribute) {
+        Some([value]) => match value.compare(&ClaimValue::Int64(0)) {
+            Some(Ordering::Equal) => Tristate::False,
+            Some(_) => Tristate::True,
+            None => Tristate::Unknown,
+        },
+        _ => Tristate::Unknown,
+    }
+}
+
+fn evaluate_unary(ctx: &EvaluationContext, op: UnaryOperator, operand: &Operand) -> Tristate {
+    match op {
+        UnaryOperator::Exists => exists(ctx, operand),
+        UnaryOperator::NotExists => exists(ctx, operand).not(),
+        UnaryOperator::MemberOf => member_of(ctx.sids(), ctx, operand, true),
+        UnaryOperator::NotMemberOf => member_of(ctx.sids(), ctx, operand, true).not(),
+        UnaryOperator::MemberOfAny => member_of(ctx.sids(), ctx, operand, false),
+        UnaryOperator::NotMemberOfAny => member_of(ctx.sids(), ctx, operand, false).not(),
+        UnaryOperator::DeviceMemberOf => member_of(ctx.device_sids(), ctx, operand, true),
+        UnaryOperator::NotDeviceMemberOf => member_of(ctx.device_sids(), ctx, operand, true).not(),
+        UnaryOperator::DeviceMemberOfAny => member_of(ctx.device_sids(), ctx, operand, false),
+        UnaryOperator::NotDeviceMemberOfAny => {
+            member_of(ctx.device_sids(), ctx, operand, false).not()
+        }
+    }
+}
+
+fn exists(ctx: &EvaluationContext, operand: &Operand) -> Tristate {
+    match operand {
+        Operand::Attribute(attribute) => ctx.attribute(attribute).is_some().into(),
+        Operand::Literal(_) => Tristate::Unknown,
+    }
+}
+
+/// `Member_of` requires all SIDs to be present in the token, while
+/// `Member_of_Any` requires at least one of them
+fn member_of(
+    token_sids: &[Sid],
+    ctx: &EvaluationContext,
+    operand: &Operand,
+    require_all: bool,
+) -> Tristate {
+    let Some(values) = ctx.values(operand) else {
+        return Tristate::Unknown;
+    };
+    let mut sids = Vec::with_capacity(values.len());
+    for value in values {
+        match value {
+            ClaimValue::Sid(sid) => sids.push(sid),
+            _ => return Tristate::Unknown,
+        }
+    }
+    if sids.is_empty() {
+        return Tristate::Unknown;
+    }
+
+    let mut found = sids.iter().map(|sid| token_sids.contains(sid));
+    if require_all {
+        found.all(|f| f).into()
+    } else {
+        found.any(|f| f).into()
+    }
+}
+
+fn evaluate_relational(
+    ctx: &EvaluationContext,
+    op: RelationalOperator,
+    lhs: &Operand,
+    rhs: &Operand,
+) -> Tristate {
+    let (Some(lhs), Some(rhs)) = (ctx.values(lhs), ctx.values(rhs)) else {
+        return Tristate::Unknown;
+    };
+
+    match op {
+        RelationalOperator::Equals => equals(&lhs, &rhs),
+        RelationalOperator::NotEquals => equals(&lhs, &rhs).not(),
+        RelationalOperator::LessThan => ordering(&lhs, &rhs, |o| o.is_lt()),
+        RelationalOperator::LessThanOrEqual => ordering(&lhs, &rhs, |o| o.is_le()),
+        RelationalOperator::GreaterThan => ordering(&lhs, &rhs, |o| o.is_gt()),
+        RelationalOperator::GreaterThanOrEqual => ordering(&lhs, &rhs, |o| o.is_ge()),
+        RelationalOperator::Contains => contains(&lhs, &rhs),
+        RelationalOperator::NotContains => contains(&lhs, &rhs).not(),
+        RelationalOperator::AnyOf => any_of(&lhs, &rhs),
+        RelationalOperator::NotAnyOf => any_of(&lhs, &rhs).not(),
+    }
+}
+
+/// single values are compared directly, multiple values are equal if they
+/// contain the same elements
+fn equals(lhs: &[ClaimValue], rhs: &[ClaimValue]) -> Tristate {
+    match (lhs, rhs) {
+        ([l], [r]) => match l.equals(r) {
+            Some(equal) => equal.into(),
+            None => Tristate::Unknown,
+        },
+        _ => (is_subset(lhs, rhs) && is_subset(rhs, lhs)).into(),
+    }
+}
+
+fn ordering(lhs: &[ClaimValue], rhs: &[ClaimValue], f: impl Fn(Ordering) -> bool) -> Tristate {
+    match (lhs, rhs) {
+        ([l], [r]) => match l.compare(r) {
+            Some(o) => f(o).into(),
+            None => Tristate::Unknown,
+        },
+        _ => Tristate::Unknown,
+    }
+}
+
+fn contains(lhs: &[ClaimValue], rhs: &[ClaimValue]) -> Tristate {
+    is_subset(rhs, lhs).into()
+}
+
+fn any_of(lhs: &[ClaimValue], rhs: &[ClaimValue]) -> Tristate {
+    lhs.iter().any(|l| rhs.iter().any(|r| l.matches(r))).into()
+}
+
+fn is_subset(subset: &[ClaimValue], superset: &[ClaimValue]) -> bool {
+    subset.iter().all(|s| superset.iter().any(|v| s.matches(v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConditionalExpression, EvaluationContext, Sid, Tristate};
+
+    fn eval(sddl: &str, ctx: &EvaluationContext) -> Tristate {
+        ConditionalExpression::from_sddl(sddl, None)
+            .unwrap()
+            .evaluate(ctx)
+    }
+
+    #[test]
+    fn test_three_valued_logic() {
+        let ctx = EvaluationContext::default()
+            .with_user_claim("clearance", vec![3i64.into()])
+            .with_device_claim("managed", vec![true.into()]);
+
+        assert_eq!(eval("(@User.clearance >= 2)", &ctx), Tristate::True);
+        assert_eq!(eval("(@User.clearance < 2)", &ctx), Tristate::False);
+        assert_eq!(eval("(@User.missing == 1)", &ctx), Tristate::Unknown);
+        assert_eq!(eval("(!(@User.missing == 1))", &ctx), Tristate::Unknown);
+        assert_eq!(
+            eval("(@User.missing == 1 || @Device.managed)", &ctx),
+            Tristate::True
+        );
+        assert_eq!(
+            eval("(@User.missing == 1 && @User.clearance < 2)", &ctx),
+            Tristate::False
+        );
+        assert_eq!(eval("(Exists @User.missing)", &ctx), Tristate::False);
+        assert_eq!(eval("(Not_Exists @User.missing)", &ctx), Tristate::True);
+        assert_eq!(eval(r#"(@User.clearance == "3")"#, &ctx), Tristate::Unknown);
+    }
+
+    #[test]
+    fn test_sets() {
+        let ctx = EvaluationContext::default()
+            .with_user_claim("project", vec!["Alpha".into(), "Beta".into()])
+            .with_resource_attribute("project", vec!["beta".into()]);
+
+        assert_eq!(
+            eval(r#"(@User.project Contains {"alpha", "beta"})"#, &ctx),
+            Tristate::True
+        );
+        assert_eq!(
+            eval(r#"(@User.project Contains {"alpha", "gamma"})"#, &ctx),
+            Tristate::False
+        );
+        assert_eq!(
+            eval(r#"(@User.project Any_of {"gamma", "BETA"})"#, &ctx),
+            Tristate::True
+        );
+        assert_eq!(
+            eval(r#"(@User.project Not_Any_of {"gamma"})"#, &ctx),
+            Tristate::True
+        );
+        assert_eq!(
+            eval("(@User.project Contains @Resource.project)", &ctx),
+            Tristate::True
+        );
+        assert_eq!(
+            eval(r#"(@User.project == {"beta", "alpha"})"#, &ctx),
+            Tristate::True
+        );
+    }
+
+    #[test]
+    fn test_member_of() {
+        let ctx = EvaluationContext::default()
+            .with_sid(Sid::try_from("S-1-5-32-544").unwrap())
+            .with_sid(Sid::try_from("S-1-5-11").unwrap());
+
+        assert_eq!(eval("(Member_of {SID(BA), SID(AU)})", &ctx), Tristate::True);
+        assert_eq!(
+            eval("(Member_of {SID(BA), SID(BU)})", &ctx),
+            Tristate::False
+        );
+        assert_eq!(
+            eval("(Member_of_Any {SID(BA), SID(BU)})", &ctx),
+            Tristate::True
+        );
+        assert_eq!(eval("(Not_Member_of {SID(BU)})", &ctx), Tristate::True);
+        assert_eq!(eval("(Device_Member_of {SID(BA)})", &ctx), Tristate::False);
+        assert_eq!(eval(r#"(Member_of {"BA"})"#, &ctx), Tristate::Unknown);
+    }
+
+    #[test]
+    fn test_sid_equality() {
+        let ctx = EvaluationContext::default().with_user_claim(
+            "manager",
+            vec![Sid::try_from("S-1-5-32-544").unwrap().into()],
+        );
+
+        assert_eq!(eval("(@User.manager == SID(BA))", &ctx), Tristate::True);
+        assert_eq!(eval("(@User.manager == SID(BU))", &ctx), Tristate::False);
+        assert_eq!(eval("(@User.manager != SID(BU))", &ctx), Tristate::True);
+        assert_eq!(eval("(@User.manager < SID(BU))", &ctx), Tristate::Unknown);
+        assert_eq!(!Tristate::Unknown, Tristate::Unknown);
+    }
+}
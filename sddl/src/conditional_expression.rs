@@ -0,0 +1,990 @@
+use std::fmt::Display;
+
+use binrw::{BinReaderExt, BinWrite};
+use serde::Serialize;
+use strum::FromRepr;
+
+use crate::Sid;
+
+/// Every conditional expression stored in the application data of a callback
+/// ACE starts with these four bytes (`artx`).
+pub const CONDITIONAL_ACE_SIGNATURE: [u8; 4] = [0x61, 0x72, 0x74, 0x78];
+
+/// A conditional expression of a conditional ACE, as specified in
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/18fc3d6c-2b5b-4e96-b4d7-2d2ce2f1b3cb>
+///
+/// # Example
+/// ```rust
+/// use sddl::ConditionalExpression;
+///
+/// let expr = ConditionalExpression::from_sddl(r#"(@User.Title == "PM" && Member_of {SID(BA)})"#, None).unwrap();
+/// assert_eq!(expr.to_string(), r#"(@User.Title == "PM") && (Member_of {SID(BA)})"#);
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Serialize)]
+pub enum ConditionalExpression {
+    /// logical AND (`&&`) of two expressions
+    And(Box<ConditionalExpression>, Box<ConditionalExpression>),
+
+    /// logical OR (`||`) of two expressions
+    Or(Box<ConditionalExpression>, Box<ConditionalExpression>),
+
+    /// logical NOT (`!`) of an expression
+    Not(Box<ConditionalExpression>),
+
+    /// an operator with one operand, such as `Exists` or `Member_of`
+    Unary(UnaryOperator, Operand),
+
+    /// an operator with two operands, such as `==` or `Contains`
+    Relational(RelationalOperator, Operand, Operand),
+
+    /// an attribute which is used as boolean value
+    Attribute(Attribute),
+}
+
+/// Operand of a unary or relational operator
+#[derive(Eq, PartialEq, Debug, Clone, Serialize)]
+pub enum Operand {
+    Attribute(Attribute),
+    Literal(Literal),
+}
+
+/// The origin of the value of an attribute
+#[repr(u8)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, FromRepr)]
+pub enum AttributeSource {
+    /// local claims of the token, written as `@Token.` in SDDL
+    Token = 0xf8,
+
+    /// user claims, written as `@User.` in SDDL
+    User = 0xf9,
+
+    /// resource attributes, written as `@Resource.` in SDDL
+    Resource = 0xfa,
+
+    /// device claims, written as `@Device.` in SDDL
+    Device = 0xfb,
+}
+
+impl AttributeSource {
+    pub fn sddl_prefix(&self) -> &'static str {
+        match self {
+            AttributeSource::Token => "@Token.",
+            AttributeSource::User => "@User.",
+            AttributeSource::Resource => "@Resource.",
+            AttributeSource::Device => "@Device.",
+        }
+    }
+}
+
+/// A named attribute whose value is looked up at evaluation time
+#[derive(Eq, PartialEq, Debug, Clone, Serialize)]
+pub struct Attribute {
+    source: AttributeSource,
+    name: String,
+}
+
+impl Attribute {
+    pub fn new(source: AttributeSource, name: impl Into<String>) -> Self {
+        Self {
+            source,
+            name: name.into(),
+        }
+    }
+
+    pub fn source(&self) -> AttributeSource {
+        self.source
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// parses an attribute name including its prefix, such as
+    /// `@User.Title`. Characters can be escaped using `%XXXX`.
+    pub(crate) fn from_sddl(value: &str) -> Result<Self, crate::Error> {
+        let (prefix, name) = value.split_once('.').ok_or_else(|| {
+            crate::Error::IllegalConditionalExpression(format!("missing attribute prefix in '{value}'"))
+        })?;
+        let source = match prefix.to_ascii_lowercase().as_str() {
+            "@token" => AttributeSource::Token,
+            "@user" => AttributeSource::User,
+            "@resource" => AttributeSource::Resource,
+            "@device" => AttributeSource::Device,
+            _ => {
+                return Err(crate::Error::IllegalConditionalExpression(format!(
+                    "unknown attribute prefix in '{value}'"
+                )))
+            }
+        };
+
+        let mut unescaped = String::with_capacity(name.len());
+        let mut chars = name.chars();
+        while let Some(c) = chars.next() {
+            if c == '%' {
+                let code: String = chars.by_ref().take(4).collect();
+                let c = u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        crate::Error::IllegalConditionalExpression(format!(
+                            "illegal escape sequence in '{value}'"
+                        ))
+                    })?;
+                unescaped.push(c);
+            } else {
+                unescaped.push(c);
+            }
+        }
+        Ok(Self::new(source, unescaped))
+    }
+}
+
+impl Display for Attribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source.sddl_prefix())?;
+        for c in self.name.chars() {
+            if c.is_ascii_alphanumeric() || ":./_".contains(c) {
+                write!(f, "{c}")?;
+            } else {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(f, "%{unit:04X}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A constant value
+#[derive(Eq, PartialEq, Debug, Clone, Serialize)]
+pub enum Literal {
+    Integer(IntegerLiteral),
+    String(String),
+    OctetString(Vec<u8>),
+    Composite(Vec<Literal>),
+    Sid(Sid),
+}
+
+/// the binary token type of an integer literal
+#[repr(u8)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, FromRepr)]
+pub enum IntegerSize {
+    Int8 = 0x01,
+    Int16 = 0x02,
+    Int32 = 0x03,
+    Int64 = 0x04,
+}
+
+/// the sign which was used when the integer has been written
+#[repr(u8)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, FromRepr)]
+pub enum IntegerSign {
+    Plus = 0x01,
+    Minus = 0x02,
+    None = 0x03,
+}
+
+/// the base which was used when the integer has been written
+#[repr(u8)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, FromRepr)]
+pub enum IntegerBase {
+    Octal = 0x01,
+    Decimal = 0x02,
+    Hexadecimal = 0x03,
+}
+
+/// An integer value, together with the information needed to print it the
+/// same way it has been written
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize)]
+pub struct IntegerLiteral {
+    value: i64,
+    size: IntegerSize,
+    sign: IntegerSign,
+    base: IntegerBase,
+}
+
+impl IntegerLiteral {
+    pub fn new(value: i64, size: IntegerSize, sign: IntegerSign, base: IntegerBase) -> Self {
+        Self {
+            value,
+            size,
+            sign,
+            base,
+        }
+    }
+
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    pub fn size(&self) -> IntegerSize {
+        self.size
+    }
+
+    pub fn sign(&self) -> IntegerSign {
+        self.sign
+    }
+
+    pub fn base(&self) -> IntegerBase {
+        self.base
+    }
+
+    /// parses the digits of an integer, as they are written in SDDL. The
+    /// prefix `0x` denotes a hexadecimal value, a leading `0` denotes an octal
+    /// value.
+    pub(crate) fn from_sddl(sign: IntegerSign, digits: &str) -> Result<Self, crate::Error> {
+        let (base, radix, digits) = if let Some(hex) = digits.strip_prefix("0x") {
+            (IntegerBase::Hexadecimal, 16, hex)
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            (IntegerBase::Octal, 8, &digits[1..])
+        } else {
+            (IntegerBase::Decimal, 10, digits)
+        };
+        let magnitude = u64::from_str_radix(digits, radix).map_err(|why| {
+            crate::Error::IllegalConditionalExpression(format!("illegal integer '{digits}': {why}"))
+        })?;
+
+        // values are stored as QWORD, so we accept the full unsigned range
+        let value = if sign == IntegerSign::Minus {
+            (magnitude as i64).wrapping_neg()
+        } else {
+            magnitude as i64
+        };
+        Ok(Self::new(value, IntegerSize::Int64, sign, base))
+    }
+}
+
+impl Display for IntegerLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let magnitude = if self.sign == IntegerSign::Minus {
+            self.value.wrapping_neg() as u64
+        } else {
+            self.value as u64
+        };
+        match self.sign {
+            IntegerSign::Plus => write!(f, "+")?,
+            IntegerSign::Minus => write!(f, "-")?,
+            IntegerSign::None => (),
+        }
+        match self.base {
+            IntegerBase::Octal if magnitude != 0 => write!(f, "0{magnitude:o}"),
+            IntegerBase::Octal => write!(f, "0"),
+            IntegerBase::Decimal => write!(f, "{magnitude}"),
+            IntegerBase::Hexadecimal => write!(f, "0x{magnitude:x}"),
+        }
+    }
+}
+
+/// Operators which take a single operand
+#[repr(u8)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, FromRepr, strum::Display)]
+pub enum UnaryOperator {
+    #[strum(serialize = "Exists")]
+    Exists = 0x87,
+
+    #[strum(serialize = "Member_of")]
+    MemberOf = 0x89,
+
+    #[strum(serialize = "Device_Member_of")]
+    DeviceMemberOf = 0x8a,
+
+    #[strum(serialize = "Member_of_Any")]
+    MemberOfAny = 0x8b,
+
+    #[strum(serialize = "Device_Member_of_Any")]
+    DeviceMemberOfAny = 0x8c,
+
+    #[strum(serialize = "Not_Exists")]
+    NotExists = 0x8d,
+
+    #[strum(serialize = "Not_Member_of")]
+    NotMemberOf = 0x90,
+
+    #[strum(serialize = "Not_Device_Member_of")]
+    NotDeviceMemberOf = 0x91,
+
+    #[strum(serialize = "Not_Member_of_Any")]
+    NotMemberOfAny = 0x92,
+
+    #[strum(serialize = "Not_Device_Member_of_Any")]
+    NotDeviceMemberOfAny = 0x93,
+}
+
+/// Operators which take two operands
+#[repr(u8)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, FromRepr, strum::Display)]
+pub enum RelationalOperator {
+    #[strum(serialize = "==")]
+    Equals = 0x80,
+
+    #[strum(serialize = "!=")]
+    NotEquals = 0x81,
+
+    #[strum(serialize = "<")]
+    LessThan = 0x82,
+
+    #[strum(serialize = "<=")]
+    LessThanOrEqual = 0x83,
+
+    #[strum(serialize = ">")]
+    GreaterThan = 0x84,
+
+    #[strum(serialize = ">=")]
+    GreaterThanOrEqual = 0x85,
+
+    #[strum(serialize = "Contains")]
+    Contains = 0x86,
+
+    #[strum(serialize = "Any_of")]
+    AnyOf = 0x88,
+
+    #[strum(serialize = "Not_Contains")]
+    NotContains = 0x8e,
+
+    #[strum(serialize = "Not_Any_of")]
+    NotAnyOf = 0x8f,
+}
+
+pub(crate) const TOKEN_PADDING: u8 = 0x00;
+pub(crate) const TOKEN_UNICODE_STRING: u8 = 0x10;
+pub(crate) const TOKEN_OCTET_STRING: u8 = 0x18;
+pub(crate) const TOKEN_COMPOSITE: u8 = 0x50;
+pub(crate) const TOKEN_SID: u8 = 0x51;
+pub(crate) const TOKEN_AND: u8 = 0xa0;
+pub(crate) const TOKEN_OR: u8 = 0xa1;
+pub(crate) const TOKEN_NOT: u8 = 0xa2;
+
+/// Conditional expressions and composite literals are rejected if they are
+/// nested deeper than this, because formatting, encoding, evaluating and
+/// dropping an expression recurse into its operands.
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+/// an expression, operand or literal together with its nesting depth, which
+/// is tracked while parsing
+pub(crate) struct Nested<T> {
+    pub(crate) value: T,
+    pub(crate) depth: usize,
+}
+
+impl<T> Nested<T> {
+    /// a value which does not contain other expressions or literals
+    pub(crate) fn leaf(value: T) -> Self {
+        Self { value, depth: 1 }
+    }
+
+    /// a value whose deepest operand has the depth `operand_depth`. Fails if
+    /// this exceeds [`MAX_NESTING_DEPTH`].
+    pub(crate) fn new(value: T, operand_depth: usize) -> Result<Self, crate::Error> {
+        let depth = operand_depth + 1;
+        if depth > MAX_NESTING_DEPTH {
+            return Err(too_deep());
+        }
+        Ok(Self { value, depth })
+    }
+
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> Nested<U> {
+        Nested {
+            value: f(self.value),
+            depth: self.depth,
+        }
+    }
+}
+
+fn too_deep() -> crate::Error {
+    crate::Error::IllegalConditionalExpression(format!(
+        "expression is nested deeper than {MAX_NESTING_DEPTH} levels"
+    ))
+}
+
+impl ConditionalExpression {
+    /// parses a conditional expression, such as
+    /// `(@User.Title == "PM")`
+    pub fn from_sddl(value: &str, domain_rid: Option<&[u32]>) -> Result<Self, crate::Error> {
+        crate::parser::ConditionalExpressionParser::new()
+            .parse(domain_rid, value)
+            .map_err(|e| crate::SddlParseError::from_lalrpop(value, e))
+    }
+
+    /// encodes this expression in the binary format which is used in the
+    /// application data of callback ACEs. The result starts with
+    /// [`CONDITIONAL_ACE_SIGNATURE`] and is padded to a multiple of 4 bytes.
+    /// Fails if a string or literal is too long for the binary format.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, crate::Error> {
+        let mut bytes = Vec::from(CONDITIONAL_ACE_SIGNATURE);
+        self.encode(&mut bytes)?;
+        while !bytes.len().is_multiple_of(4) {
+            bytes.push(0);
+        }
+        Ok(bytes)
+    }
+
+    /// decodes a conditional expression from the application data of a
+    /// callback ACE
+    ///
+    /// ```rust
+    /// use sddl::ConditionalExpression;
+    ///
+    /// let expr = ConditionalExpression::from_sddl(r#"(@User.Title == "PM")"#, None).unwrap();
+    /// let decoded = ConditionalExpression::from_bytes(&expr.to_bytes().unwrap()).unwrap();
+    /// assert_eq!(expr, decoded);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, crate::Error> {
+        let tokens = bytes.strip_prefix(&CONDITIONAL_ACE_SIGNATURE).ok_or_else(|| {
+            crate::Error::IllegalConditionalExpression("missing signature".into())
+        })?;
+
+        let mut reader = TokenReader::new(tokens);
+        let mut stack = Vec::new();
+        while let Some(token) = reader.next_byte() {
+            let item = match token {
+                TOKEN_PADDING => continue,
+                TOKEN_AND | TOKEN_OR => {
+                    let rhs = pop(&mut stack)?.into_expression()?;
+                    let lhs = pop(&mut stack)?.into_expression()?;
+                    let depth = lhs.depth.max(rhs.depth);
+                    let (lhs, rhs) = (Box::new(lhs.value), Box::new(rhs.value));
+                    let expr = if token == TOKEN_AND {
+                        ConditionalExpression::And(lhs, rhs)
+                    } else {
+                        ConditionalExpression::Or(lhs, rhs)
+                    };
+                    StackItem::Expression(Nested::new(expr, depth)?)
+                }
+                TOKEN_NOT => {
+                    let expr = pop(&mut stack)?.into_expression()?;
+                    let depth = expr.depth;
+                    let expr = ConditionalExpression::Not(Box::new(expr.value));
+                    StackItem::Expression(Nested::new(expr, depth)?)
+                }
+                token => {
+                    if let Some(op) = UnaryOperator::from_repr(token) {
+                        let operand = pop(&mut stack)?.into_operand()?;
+                        let expr = ConditionalExpression::Unary(op, operand.value);
+                        StackItem::Expression(Nested::new(expr, operand.depth)?)
+                    } else if let Some(op) = RelationalOperator::from_repr(token) {
+                        let rhs = pop(&mut stack)?.into_operand()?;
+                        let lhs = pop(&mut stack)?.into_operand()?;
+                        let depth = lhs.depth.max(rhs.depth);
+                        let expr = ConditionalExpression::Relational(op, lhs.value, rhs.value);
+                        StackItem::Expression(Nested::new(expr, depth)?)
+                    } else if let Some(source) = AttributeSource::from_repr(token) {
+                        let name = reader.read_unicode_string()?;
+                        StackItem::Operand(Nested::leaf(Operand::Attribute(Attribute::new(
+                            source, name,
+                        ))))
+                    } else {
+                        StackItem::Operand(reader.read_literal(token)?.map(Operand::Literal))
+                    }
+                }
+            };
+            stack.push(item);
+        }
+
+        let expr = pop(&mut stack)?.into_expression()?;
+        if stack.is_empty() {
+            Ok(expr.value)
+        } else {
+            Err(crate::Error::IllegalConditionalExpression(format!(
+                "{} unused operands",
+                stack.len()
+            )))
+        }
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), crate::Error> {
+        match self {
+            ConditionalExpression::And(lhs, rhs) => {
+                lhs.encode(bytes)?;
+                rhs.encode(bytes)?;
+                bytes.push(TOKEN_AND);
+            }
+            ConditionalExpression::Or(lhs, rhs) => {
+                lhs.encode(bytes)?;
+                rhs.encode(bytes)?;
+                bytes.push(TOKEN_OR);
+            }
+            ConditionalExpression::Not(expr) => {
+                expr.encode(bytes)?;
+                bytes.push(TOKEN_NOT);
+            }
+            ConditionalExpression::Unary(op, operand) => {
+                operand.encode(bytes)?;
+                bytes.push(*op as u8);
+            }
+            ConditionalExpression::Relational(op, lhs, rhs) => {
+                lhs.encode(bytes)?;
+                rhs.encode(bytes)?;
+                bytes.push(*op as u8);
+            }
+            ConditionalExpression::Attribute(attribute) => attribute.encode(bytes)?,
+        }
+        Ok(())
+    }
+
+    /// writes an operand of a logical operator. Every operand which is not
+    /// a plain attribute or a negation is put in parentheses.
+    fn fmt_operand(
+        f: &mut std::fmt::Formatter<'_>,
+        operand: &ConditionalExpression,
+    ) -> std::fmt::Result {
+        match operand {
+            ConditionalExpression::Attribute(_) | ConditionalExpression::Not(_) => {
+                write!(f, "{operand}")
+            }
+            _ => write!(f, "({operand})"),
+        }
+    }
+}
+
+impl Operand {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), crate::Error> {
+        match self {
+            Operand::Attribute(attribute) => attribute.encode(bytes),
+            Operand::Literal(literal) => literal.encode(bytes),
+        }
+    }
+}
+
+impl Attribute {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), crate::Error> {
+        bytes.push(self.source as u8);
+        encode_unicode_string(bytes, &self.name)
+    }
+}
+
+impl Literal {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), crate::Error> {
+        match self {
+            Literal::Integer(i) => {
+                bytes.push(i.size as u8);
+                bytes.extend_from_slice(&i.value.to_le_bytes());
+                bytes.push(i.sign as u8);
+                bytes.push(i.base as u8);
+            }
+            Literal::String(s) => {
+                bytes.push(TOKEN_UNICODE_STRING);
+                encode_unicode_string(bytes, s)?;
+            }
+            Literal::OctetString(octets) => {
+                bytes.push(TOKEN_OCTET_STRING);
+                encode_length(bytes, octets.len())?;
+                bytes.extend_from_slice(octets);
+            }
+            Literal::Composite(elements) => {
+                let mut contents = Vec::new();
+                for element in elements {
+                    element.encode(&mut contents)?;
+                }
+                bytes.push(TOKEN_COMPOSITE);
+                encode_length(bytes, contents.len())?;
+                bytes.extend(contents);
+            }
+            Literal::Sid(sid) => {
+                let mut cursor = std::io::Cursor::new(Vec::new());
+                sid.write_le(&mut cursor)
+                    .expect("writing to a Vec<u8> must not fail");
+                let sid = cursor.into_inner();
+                bytes.push(TOKEN_SID);
+                encode_length(bytes, sid.len())?;
+                bytes.extend(sid);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// intermediate result of decoding the postfix notation of a conditional
+/// expression
+enum StackItem {
+    Expression(Nested<ConditionalExpression>),
+    Operand(Nested<Operand>),
+}
+
+impl StackItem {
+    fn into_expression(self) -> Result<Nested<ConditionalExpression>, crate::Error> {
+        match self {
+            StackItem::Expression(expr) => Ok(expr),
+            StackItem::Operand(Nested {
+                value: Operand::Attribute(attribute),
+                depth,
+            }) => Ok(Nested {
+                value: ConditionalExpression::Attribute(attribute),
+                depth,
+            }),
+            StackItem::Operand(Nested {
+                value: Operand::Literal(literal),
+                ..
+            }) => Err(crate::Error::IllegalConditionalExpression(format!(
+                "literal {literal} cannot be used as boolean expression"
+            ))),
+        }
+    }
+
+    fn into_operand(self) -> Result<Nested<Operand>, crate::Error> {
+        match self {
+            StackItem::Operand(operand) => Ok(operand),
+            StackItem::Expression(expr) => Err(crate::Error::IllegalConditionalExpression(
+                format!("expression ({}) cannot be used as operand", expr.value),
+            )),
+        }
+    }
+}
+
+fn pop(stack: &mut Vec<StackItem>) -> Result<StackItem, crate::Error> {
+    stack
+        .pop()
+        .ok_or_else(|| crate::Error::IllegalConditionalExpression("missing operand".into()))
+}
+
+struct TokenReader<'b> {
+    bytes: &'b [u8],
+
+    /// number of composite literals which contain the tokens of this reader
+    depth: usize,
+}
+
+impl<'b> TokenReader<'b> {
+    fn new(bytes: &'b [u8]) -> Self {
+        Self { bytes, depth: 0 }
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let (first, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(*first)
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&'b [u8], crate::Error> {
+        if count > self.bytes.len() {
+            return Err(crate::Error::IllegalConditionalExpression(
+                "unexpected end of data".into(),
+            ));
+        }
+        let (bytes, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    fn read_length(&mut self) -> Result<usize, crate::Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn read_unicode_string(&mut self) -> Result<String, crate::Error> {
+        let length = self.read_length()?;
+        if length % 2 != 0 {
+            return Err(crate::Error::IllegalConditionalExpression(format!(
+                "unicode string has an odd length of {length} bytes"
+            )));
+        }
+        let chars: Vec<u16> = self
+            .read_bytes(length)?
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16(&chars).map_err(|why| {
+            crate::Error::IllegalConditionalExpression(format!("illegal unicode string: {why}"))
+        })
+    }
+
+    fn read_literal(&mut self, token: u8) -> Result<Nested<Literal>, crate::Error> {
+        if let Some(size) = IntegerSize::from_repr(token) {
+            let value = self.read_bytes(8)?;
+            let value = i64::from_le_bytes(value.try_into().unwrap());
+            let sign = self.next_byte().and_then(IntegerSign::from_repr);
+            let base = self.next_byte().and_then(IntegerBase::from_repr);
+            return match (sign, base) {
+                (Some(sign), Some(base)) => Ok(Nested::leaf(Literal::Integer(
+                    IntegerLiteral::new(value, size, sign, base),
+                ))),
+                _ => Err(crate::Error::IllegalConditionalExpression(
+                    "illegal sign or base of integer literal".into(),
+                )),
+            };
+        }
+
+        match token {
+            TOKEN_UNICODE_STRING => Ok(Nested::leaf(Literal::String(self.read_unicode_string()?))),
+            TOKEN_OCTET_STRING => {
+                let length = self.read_length()?;
+                Ok(Nested::leaf(Literal::OctetString(
+                    self.read_bytes(length)?.to_vec(),
+                )))
+            }
+            TOKEN_COMPOSITE => {
+                // the depth of the elements is only known after they have
+                // been read, so the recursion needs a limit of its own
+                if self.depth >= MAX_NESTING_DEPTH {
+                    return Err(too_deep());
+                }
+                let length = self.read_length()?;
+                let mut reader = TokenReader {
+                    bytes: self.read_bytes(length)?,
+                    depth: self.depth + 1,
+                };
+                let mut elements = Vec::new();
+                let mut depth = 0;
+                while let Some(token) = reader.next_byte() {
+                    let element = reader.read_literal(token)?;
+                    depth = depth.max(element.depth);
+                    elements.push(element.value);
+                }
+                Nested::new(Literal::Composite(elements), depth)
+            }
+            TOKEN_SID => {
+                let length = self.read_length()?;
+                let mut cursor = std::io::Cursor::new(self.read_bytes(length)?);
+                Ok(Nested::leaf(Literal::Sid(cursor.read_le()?)))
+            }
+            token => Err(crate::Error::IllegalConditionalExpression(format!(
+                "unknown token 0x{token:02x}"
+            ))),
+        }
+    }
+}
+
+fn encode_length(bytes: &mut Vec<u8>, length: usize) -> Result<(), crate::Error> {
+    let length = u32::try_from(length).map_err(|_| {
+        crate::Error::IllegalConditionalExpression(format!(
+            "{length} bytes exceed the maximum length of a token"
+        ))
+    })?;
+    bytes.extend_from_slice(&length.to_le_bytes());
+    Ok(())
+}
+
+fn encode_unicode_string(bytes: &mut Vec<u8>, value: &str) -> Result<(), crate::Error> {
+    let chars: Vec<u8> = value.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    encode_length(bytes, chars.len())?;
+    bytes.extend(chars);
+    Ok(())
+}
+
+impl Display for ConditionalExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConditionalExpression::And(lhs, rhs) => {
+                Self::fmt_operand(f, lhs)?;
+                write!(f, " && ")?;
+                Self::fmt_operand(f, rhs)
+            }
+            ConditionalExpression::Or(lhs, rhs) => {
+                Self::fmt_operand(f, lhs)?;
+                write!(f, " || ")?;
+                Self::fmt_operand(f, rhs)
+            }
+            ConditionalExpression::Not(expr) => {
+                write!(f, "!")?;
+                Self::fmt_operand(f, expr)
+            }
+            ConditionalExpression::Unary(op, operand) => write!(f, "{op} {operand}"),
+            ConditionalExpression::Relational(op, lhs, rhs) => write!(f, "{lhs} {op} {rhs}"),
+            ConditionalExpression::Attribute(attribute) => write!(f, "{attribute}"),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Attribute(attribute) => write!(f, "{attribute}"),
+            Operand::Literal(literal) => write!(f, "{literal}"),
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Integer(i) => write!(f, "{i}"),
+            Literal::String(s) => write!(f, "\"{s}\""),
+            Literal::OctetString(octets) => {
+                write!(f, "#")?;
+                for o in octets {
+                    write!(f, "{o:02x}")?;
+                }
+                Ok(())
+            }
+            Literal::Composite(elements) => {
+                write!(f, "{{")?;
+                for (idx, element) in elements.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                write!(f, "}}")
+            }
+            Literal::Sid(sid) => match sid.alias() {
+                Some(alias) => write!(f, "SID({})", alias.short_name()),
+                None => write!(f, "SID({sid})"),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::FILE_ALL, Ace, AceHeaderFlags, ConditionalExpression, Error, Sid,
+        MAX_NESTING_DEPTH,
+    };
+
+    #[test]
+    fn test_encode_member_of() {
+        let expr = ConditionalExpression::from_sddl("(Member_of {SID(BA)})", None).unwrap();
+        assert_eq!(
+            expr.to_bytes().unwrap(),
+            vec![
+                0x61, 0x72, 0x74, 0x78, // artx
+                0x50, 0x15, 0x00, 0x00, 0x00, // composite
+                0x51, 0x10, 0x00, 0x00, 0x00, // SID
+                0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x20, 0x00, 0x00, 0x00, 0x20,
+                0x02, 0x00, 0x00, // S-1-5-32-544
+                0x89, // Member_of
+                0x00, // padding
+            ]
+        );
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expr = ConditionalExpression::from_sddl(
+            r#"(@User.a == 1 || @User.b == 2 && !(@Device.c))"#,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(expr, ConditionalExpression::Or(_, _)));
+        assert_eq!(
+            expr.to_string(),
+            r#"(@User.a == 1) || ((@User.b == 2) && !@Device.c)"#
+        );
+        let reparsed = ConditionalExpression::from_sddl(&format!("({expr})"), None).unwrap();
+        assert_eq!(expr, reparsed);
+    }
+
+    #[test]
+    fn test_literals() {
+        let sddl = r#"@Resource.x Any_of {-0x10, 017, +3, "a b", #00ff, SID(S-1-5-21-2623811015-3361044348-130300820-1013)}"#;
+        let expr = ConditionalExpression::from_sddl(&format!("({sddl})"), None).unwrap();
+        assert_eq!(expr.to_string(), sddl);
+    }
+
+    #[test]
+    fn test_decode() {
+        let sddl = r#"((@User.a == 1) || ((@Device.b Any_of {"x", 0x2}) && !@Token.c)) && (Not_Member_of {SID(WD), SID(S-1-5-21-2623811015-3361044348-130300820-1013)})"#;
+        let expr = ConditionalExpression::from_sddl(&format!("({sddl})"), None).unwrap();
+        let decoded = ConditionalExpression::from_bytes(&expr.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded, expr);
+        assert_eq!(decoded.to_string(), sddl);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        for bytes in [
+            &b"artx"[..],
+            &b"artx\xa0"[..],
+            &b"xtra\x87"[..],
+            &[0x61, 0x72, 0x74, 0x78, 0x10, 0xff, 0xff, 0xff, 0xff][..],
+            &[0x61, 0x72, 0x74, 0x78, 0x04, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x03, 0x02][..],
+        ] {
+            assert!(ConditionalExpression::from_bytes(bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode_odd_string_length() {
+        // @User.ab Exists
+        let bytes = b"artx\xf9\x04\0\0\0a\0b\0\x87";
+        assert_eq!(
+            ConditionalExpression::from_bytes(bytes).unwrap().to_string(),
+            "Exists @User.ab"
+        );
+        // the length does not cover the second byte of the last character
+        let bytes = b"artx\xf9\x03\0\0\0a\0b\x87";
+        assert!(matches!(
+            ConditionalExpression::from_bytes(bytes),
+            Err(Error::IllegalConditionalExpression(_))
+        ));
+    }
+
+    #[test]
+    fn test_callback_ace() {
+        let ace = crate::parser::AceParser::new()
+            .parse(None, r#"(XA;;FX;;;S-1-1-0;(@User.Title == "PM"))"#)
+            .unwrap();
+        match ace {
+            Ace::ACCESS_ALLOWED_CALLBACK_ACE {
+                application_data,
+                is_conditional,
+                ..
+            } => {
+                assert!(is_conditional);
+                assert_eq!(application_data.len() % 4, 0);
+            }
+            _ => panic!("unexpected ACE type"),
+        }
+    }
+
+    #[test]
+    fn test_undecodable_application_data() {
+        let everyone = Sid::try_from("S-1-1-0").unwrap();
+        let callback = |data: &[u8]| {
+            Ace::access_allowed_callback(
+                AceHeaderFlags::empty(),
+                *FILE_ALL,
+                everyone.clone(),
+                data.to_vec(),
+            )
+            .unwrap()
+        };
+        assert_eq!(callback(&[]).to_sddl().unwrap(), "XA;;FA;;;WD");
+        assert_eq!(callback(&[0; 4]).to_sddl().unwrap(), "XA;;FA;;;WD");
+
+        // the ACE must not be written without the data which it contains
+        for data in [&b"artx\xa0\0\0\0"[..], &b"xtra"[..]] {
+            assert!(matches!(
+                callback(data).to_sddl(),
+                Err(Error::NoSddlRepresentation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let negations = |count: usize| format!("{}@User.x", "!".repeat(count));
+        assert!(ConditionalExpression::from_sddl(&negations(MAX_NESTING_DEPTH - 1), None).is_ok());
+        assert!(ConditionalExpression::from_sddl(&negations(MAX_NESTING_DEPTH), None).is_err());
+
+        // the SID, the composite literals and Member_of
+        let composites = |count: usize| {
+            let (open, close) = ("{".repeat(count), "}".repeat(count));
+            let sddl = format!("Member_of {open}SID(BA){close}");
+            ConditionalExpression::from_sddl(&sddl, None)
+        };
+        let expr = composites(MAX_NESTING_DEPTH - 2).unwrap();
+        assert!(composites(MAX_NESTING_DEPTH - 1).is_err());
+
+        let mut bytes = expr.to_bytes().unwrap();
+        assert_eq!(ConditionalExpression::from_bytes(&bytes).unwrap(), expr);
+        bytes.push(super::TOKEN_NOT);
+        assert!(matches!(
+            ConditionalExpression::from_bytes(&bytes),
+            Err(Error::IllegalConditionalExpression(_))
+        ));
+
+        // composite literals are rejected before their elements are decoded
+        let mut literal = vec![super::TOKEN_COMPOSITE, 0, 0, 0, 0];
+        for _ in 0..10_000 {
+            let mut composite = vec![super::TOKEN_COMPOSITE];
+            composite.extend_from_slice(&(literal.len() as u32).to_le_bytes());
+            composite.extend(literal);
+            literal = composite;
+        }
+        let bytes = [&b"artx"[..], &literal, &[0x89]].concat();
+        assert!(matches!(
+            ConditionalExpression::from_bytes(&bytes),
+            Err(Error::IllegalConditionalExpression(_))
+        ));
+    }
+}
@@ -1,10 +1,11 @@
-use lalrpop_util::ParseError;
 use thiserror::Error;
 
+use crate::{AceType, SddlParseError};
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("ParseError: {0}")]
-    ParseError(String),
+    ParseError(Box<SddlParseError>),
 
     #[error("IllegalSid: '{0}' (reason: {1})")]
     IllegalSidFormat(String, &'static str),
@@ -15,16 +16,36 @@ pub enum Error {
     #[error("illegal SID alias: {0}")]
     IllegalSidAlias(String),
 
+    #[error("illegal conditional expression: {0}")]
+    IllegalConditionalExpression(String),
+
+    #[error("illegal claim attribute: {0}")]
+    IllegalClaimAttribute(String),
+
+    #[error("illegal central access policy: {0}")]
+    IllegalCentralAccessPolicy(String),
+
+    #[error("illegal name map: {0}")]
+    IllegalNameMap(String),
+
+    #[error("illegal number '{0}': {1}")]
+    IllegalNumber(String, std::num::ParseIntError),
+
+    #[error("illegal GUID: {0}")]
+    IllegalGuid(#[from] uuid::Error),
+
+    #[error("ACE type {0} is not supported")]
+    UnsupportedAceType(AceType),
+
+    #[error("the ACE cannot be represented in SDDL: {0}")]
+    NoSddlRepresentation(String),
+
+    #[error("the ACE would be {0} bytes long, but the AceSize field allows at most 65532")]
+    AceTooLarge(usize),
+
+    #[error("the security descriptor contains more than one {0}")]
+    DuplicateComponent(&'static str),
+
     #[error("Error while parsing the binary security descriptor: {0}")]
     BinReadError(#[from] binrw::Error)
 }
-
-impl<L, T, E> From<ParseError<L, T, E>> for Error 
-where
-    L: core::fmt::Display,
-    T: core::fmt::Display,
-    E: core::fmt::Display {
-    fn from(value: ParseError<L, T, E>) -> Self {
-        Self::ParseError(format!("{value}"))
-    }
-}
\ No newline at end of file
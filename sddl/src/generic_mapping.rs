@@ -0,0 +1,82 @@
+use getset::CopyGetters;
+use serde::Serialize;
+
+use crate::AccessMask;
+
+/// Defines the mapping of generic access rights to specific and standard
+/// access rights for an object
+///
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-generic_mapping>
+#[derive(Eq, PartialEq, Debug, Clone, Copy, CopyGetters, Serialize)]
+#[getset(get_copy = "pub")]
+pub struct GenericMapping {
+    generic_read: AccessMask,
+    generic_write: AccessMask,
+    generic_execute: AccessMask,
+    generic_all: AccessMask,
+}
+
+impl GenericMapping {
+    /// files (`FILE_GENERIC_READ`, `FILE_GENERIC_WRITE`,
+    /// `FILE_GENERIC_EXECUTE`, `FILE_ALL_ACCESS`)
+    pub const FILE: Self = Self::from_bits(0x0012_0089, 0x0012_0116, 0x0012_00a0, 0x001f_01ff);
+
+    /// directories, which share the access rights of files
+    pub const DIRECTORY: Self = Self::FILE;
+
+    /// named pipes, which share the access rights of files
+    pub const NAMED_PIPE: Self = Self::FILE;
+
+    /// registry keys (`KEY_READ`, `KEY_WRITE`, `KEY_EXECUTE`,
+    /// `KEY_ALL_ACCESS`)
+    pub const REGISTRY_KEY: Self =
+        Self::from_bits(0x0002_0019, 0x0002_0006, 0x0002_0019, 0x000f_003f);
+
+    /// services, as managed by the service control manager
+    pub const SERVICE: Self = Self::from_bits(0x0002_008d, 0x0002_0002, 0x0002_0170, 0x000f_01ff);
+
+    pub const PROCESS: Self = Self::from_bits(0x0002_0410, 0x0002_0bea, 0x0012_1000, 0x001f_ffff);
+
+    pub const THREAD: Self = Self::from_bits(0x0002_0048, 0x0002_0437, 0x0012_1800, 0x001f_ffff);
+
+    /// access tokens (`TOKEN_READ`, `TOKEN_WRITE`, `TOKEN_EXECUTE`,
+    /// `TOKEN_ALL_ACCESS`)
+    pub const TOKEN: Self = Self::from_bits(0x0002_0008, 0x0002_00e0, 0x0002_0000, 0x000f_01ff);
+
+    /// Active Directory objects (`DS_GENERIC_READ`, `DS_GENERIC_WRITE`,
+    /// `DS_GENERIC_EXECUTE`, `DS_GENERIC_ALL`)
+    pub const DS_OBJECT: Self = Self::from_bits(0x0002_0094, 0x0002_0028, 0x0002_0004, 0x000f_01ff);
+
+    /// printers (`PRINTER_READ`, `PRINTER_WRITE`, `PRINTER_EXECUTE`,
+    /// `PRINTER_ALL_ACCESS`)
+    pub const PRINTER: Self = Self::from_bits(0x0002_0008, 0x0002_0008, 0x0002_0008, 0x000f_000c);
+
+    /// WMI namespaces. Reading requires `WBEM_ENABLE` and
+    /// `WBEM_REMOTE_ACCESS`, writing covers the repository and provider
+    /// rights, and executing allows to call methods.
+    pub const WMI_NAMESPACE: Self =
+        Self::from_bits(0x0002_0021, 0x0000_001c, 0x0000_0002, 0x0006_00ff);
+
+    pub const fn new(
+        generic_read: AccessMask,
+        generic_write: AccessMask,
+        generic_execute: AccessMask,
+        generic_all: AccessMask,
+    ) -> Self {
+        Self {
+            generic_read,
+            generic_write,
+            generic_execute,
+            generic_all,
+        }
+    }
+
+    const fn from_bits(read: u32, write: u32, execute: u32, all: u32) -> Self {
+        Self::new(
+            AccessMask::from_bits_retain(read),
+            AccessMask::from_bits_retain(write),
+            AccessMask::from_bits_retain(execute),
+            AccessMask::from_bits_retain(all),
+        )
+    }
+}
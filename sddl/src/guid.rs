@@ -2,12 +2,14 @@ use std::fmt::{Debug, Display};
 
 use binrw::{BinRead, BinReaderExt, BinWrite, BinWriterExt};
 
-use serde::Serialize;
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::RawSize;
+use crate::{ActiveDirectorySchema, GuidResolver, NameContext, RawSize, SchemaEntry};
 
-#[derive(Eq, PartialEq, Copy, Clone)]
+#[derive(Eq, PartialEq, Copy, Clone, Hash)]
 pub struct Guid(Uuid);
 
 impl BinRead for Guid {
@@ -63,10 +65,92 @@ impl RawSize for Guid {
     }
 }
 
+impl Guid {
+    /// creates a GUID from its binary representation, which is used in ACEs
+    /// and in the `schemaIDGUID` attribute
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(Uuid::from_bytes_le(bytes))
+    }
+
+    /// returns the binary representation of this GUID
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.0.to_bytes_le()
+    }
+
+    /// returns the entry of the default Active Directory schema for this
+    /// GUID, if it is a well-known class, attribute or right
+    pub fn schema_entry(&self) -> Option<&'static SchemaEntry> {
+        ActiveDirectorySchema::builtin().resolve_guid(self)
+    }
+}
+
+/// serializes the GUID as plain string. If a [`NameContext`] is active (see
+/// [`NameContext::scope`]) and the GUID is known to it or to the default
+/// schema, a structure with the GUID, its name and its kind is created
+/// instead.
 impl Serialize for Guid {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer {
-        serializer.serialize_str(&self.to_string())
+        let entry = NameContext::with_active(|context| {
+            context.and_then(|context| {
+                context
+                    .resolve_guid(self)
+                    .or_else(|| self.schema_entry())
+                    .cloned()
+            })
+        });
+        let Some(entry) = entry else {
+            return serializer.serialize_str(&self.to_string());
+        };
+        let mut ser = serializer.serialize_struct("GUID", 3)?;
+        ser.serialize_field("guid", &self.to_string())?;
+        ser.serialize_field("name", entry.name())?;
+        ser.serialize_field("kind", entry.kind())?;
+        ser.end()
+    }
+}
+
+/// accepts either a plain GUID string or the structure which is created by
+/// the `Serialize` implementation
+impl<'de> Deserialize<'de> for Guid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct GuidVisitor;
+
+        impl<'de> Visitor<'de> for GuidVisitor {
+            type Value = Guid;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a GUID string or a GUID structure")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Guid::try_from(v).map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut guid = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "guid" {
+                        let value: String = map.next_value()?;
+                        guid = Some(Guid::try_from(&value[..]).map_err(de::Error::custom)?);
+                    } else {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+                guid.ok_or_else(|| de::Error::missing_field("guid"))
+            }
+        }
+
+        deserializer.deserialize_any(GuidVisitor)
     }
 }
\ No newline at end of file
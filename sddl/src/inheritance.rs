@@ -0,0 +1,306 @@
+use crate::{
+    sddl_h::*, AccessMask, Ace, AceHeaderFlags, Acl, AclRevision, AclType, ControlFlags,
+    GenericMapping, Guid, SecurityDescriptor, Sid, Token,
+};
+
+/// the ACE flags which control inheritance
+const INHERITANCE_FLAGS: AceHeaderFlags = AceHeaderFlags::OBJECT_INHERIT_ACE
+    .union(AceHeaderFlags::CONTAINER_INHERIT_ACE)
+    .union(AceHeaderFlags::NO_PROPAGATE_INHERIT_ACE)
+    .union(AceHeaderFlags::INHERIT_ONLY_ACE);
+
+const GENERIC_RIGHTS: AccessMask = AccessMask::GENERIC_READ
+    .union(AccessMask::GENERIC_WRITE)
+    .union(AccessMask::GENERIC_EXECUTE)
+    .union(AccessMask::GENERIC_ALL);
+
+impl SecurityDescriptor {
+    /// computes the security descriptor of a new object which is created in
+    /// a container with the security descriptor `parent`, similar to
+    /// `CreatePrivateObjectSecurityEx`. `creator` is the security
+    /// descriptor which is explicitly specified by the creator of the object,
+    /// if any. The owner and group are taken from `creator` or from `token`.
+    ///
+    /// Inheritable ACEs of the parent become `INHERITED_ACE`s of the child:
+    /// - objects inherit `OBJECT_INHERIT_ACE`s and containers inherit
+    ///   `CONTAINER_INHERIT_ACE`s. `NO_PROPAGATE_INHERIT_ACE`s only apply to
+    ///   the immediate children.
+    /// - object ACEs whose inherited object type differs from `object_type`
+    ///   do not apply to the child, but are propagated by containers.
+    /// - `CREATOR OWNER` and `CREATOR GROUP` are replaced by the owner and
+    ///   group of the child, and generic rights are mapped using `mapping`.
+    ///   If such an ACE is still inheritable, an inherit-only copy of the
+    ///   original ACE is kept for the children of the child.
+    ///
+    /// If the parent ACL is auto-inherited, the inherited ACEs follow the
+    /// explicit ACEs of `creator`, unless the ACL of `creator` is protected.
+    /// Otherwise, the ACL of `creator` replaces the inherited ACEs. ACEs of
+    /// unknown types are not inherited. If there is neither an inherited nor
+    /// an explicit DACL, the default DACL of `token` is used. Fails if an
+    /// inherited ACE with a replaced trustee would be too large.
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let parent = SecurityDescriptor::from_sddl(
+    ///     "O:BAG:SYD:AI(A;OICI;FA;;;SY)(A;OICIIO;GA;;;CO)(A;;FA;;;BA)", None).unwrap();
+    /// let token = Token::new(Sid::try_from("S-1-5-32-545").unwrap());
+    ///
+    /// let file = SecurityDescriptor::create_child(
+    ///     &parent, None, false, None, &token, &GenericMapping::FILE).unwrap();
+    /// assert_eq!(file.owner().as_ref(), Some(token.user()));
+    /// assert_eq!(file.dacl_as_sddl_string().unwrap(), "D:AI(A;ID;FA;;;SY)(A;ID;FA;;;BU)");
+    ///
+    /// let folder = SecurityDescriptor::create_child(
+    ///     &parent, None, true, None, &token, &GenericMapping::FILE).unwrap();
+    /// assert_eq!(
+    ///     folder.dacl_as_sddl_string().unwrap(),
+    ///     "D:AI(A;OICIID;FA;;;SY)(A;ID;FA;;;BU)(A;OICIIOID;GA;;;CO)"
+    /// );
+    /// ```
+    pub fn create_child(
+        parent: &SecurityDescriptor,
+        creator: Option<&SecurityDescriptor>,
+        is_container: bool,
+        object_type: Option<Guid>,
+        token: &Token,
+        mapping: &GenericMapping,
+    ) -> Result<Self, crate::Error> {
+        let owner = creator
+            .and_then(|sd| sd.owner().clone())
+            .unwrap_or_else(|| token.default_owner().clone());
+        let group = creator
+            .and_then(|sd| sd.group().clone())
+            .or_else(|| token.primary_group().clone());
+
+        let child = ChildObject {
+            owner: Some(&owner),
+            group: group.as_ref(),
+            is_container,
+            object_type,
+            mapping,
+        };
+        let dacl = child
+            .acl(
+                AclType::DACL,
+                parent.dacl().as_ref(),
+                creator.and_then(|sd| sd.dacl().as_ref()),
+            )?
+            .or_else(|| token.default_dacl().clone());
+        let sacl = child.acl(
+            AclType::SACL,
+            parent.sacl().as_ref(),
+            creator.and_then(|sd| sd.sacl().as_ref()),
+        )?;
+        Ok(Self::new(Some(owner), group, dacl, sacl))
+    }
+}
+
+/// the properties of a new object which determine the ACEs it inherits
+pub(crate) struct ChildObject<'a> {
+    pub(crate) owner: Option<&'a Sid>,
+    pub(crate) group: Option<&'a Sid>,
+    pub(crate) is_container: bool,
+    pub(crate) object_type: Option<Guid>,
+    pub(crate) mapping: &'a GenericMapping,
+}
+
+impl ChildObject<'_> {
+    /// merges the ACEs inherited from `parent` with the `explicit` ACL of the
+    /// creator
+    fn acl(
+        &self,
+        acl_type: AclType,
+        parent: Option<&Acl>,
+        explicit: Option<&Acl>,
+    ) -> Result<Option<Acl>, crate::Error> {
+        let (protected, auto_inherited) = match acl_type {
+            AclType::DACL => (
+                ControlFlags::DiscretionaryAclProtected,
+                ControlFlags::DiscretionaryAclAutoInherited,
+            ),
+            AclType::SACL => (
+                ControlFlags::SystemAclProtected,
+                ControlFlags::SystemAclAutoInherited,
+            ),
+        };
+        let parent_auto_inherited =
+            parent.is_some_and(|acl| acl.control_flags().contains(auto_inherited));
+
+        if let Some(explicit) = explicit {
+            if explicit.control_flags().contains(protected) || !parent_auto_inherited {
+                return Ok(Some(explicit.clone()));
+            }
+        }
+
+        let inherited = match parent {
+            Some(acl) => self.inherited_aces(acl)?,
+            None => Vec::new(),
+        };
+        let control_flags = if parent_auto_inherited {
+            auto_inherited
+        } else {
+            ControlFlags::empty()
+        };
+
+        Ok(match explicit {
+            Some(explicit) => {
+                let ace_list = explicit
+                    .ace_list()
+                    .iter()
+                    .filter(|ace| {
+                        !ace.header()
+                            .ace_flags()
+                            .contains(AceHeaderFlags::INHERITED_ACE)
+                    })
+                    .cloned()
+                    .chain(inherited)
+                    .collect();
+                Some(Acl::new(
+                    *explicit.acl_revision(),
+                    acl_type,
+                    control_flags,
+                    ace_list,
+                ))
+            }
+            None if inherited.is_empty() => None,
+            None => Some(Acl::new(
+                AclRevision::ACL_REVISION,
+                acl_type,
+                control_flags,
+                inherited,
+            )),
+        })
+    }
+
+    /// returns the ACEs which the child inherits from the ACL `parent`
+    pub(crate) fn inherited_aces(&self, parent: &Acl) -> Result<Vec<Ace>, crate::Error> {
+        let mut aces = Vec::new();
+        for ace in parent.ace_list() {
+            aces.extend(self.inherit(ace)?);
+        }
+        Ok(aces)
+    }
+
+    /// returns the ACEs which the child inherits from the parent ACE `ace`
+    fn inherit(&self, ace: &Ace) -> Result<Vec<Ace>, crate::Error> {
+        let Some(sid) = ace.trustee() else {
+            return Ok(Vec::new());
+        };
+        let flags = *ace.header().ace_flags();
+        let mask = *ace.header().mask();
+
+        let applies = if self.is_container {
+            flags.contains(AceHeaderFlags::CONTAINER_INHERIT_ACE)
+        } else {
+            flags.contains(AceHeaderFlags::OBJECT_INHERIT_ACE)
+        };
+        let matches_type = ace
+            .inherited_object_type()
+            .is_none_or(|guid| Some(*guid) == self.object_type);
+
+        // containers pass inheritable ACEs on to their own children
+        let inheritable = self.is_container
+            && !flags.contains(AceHeaderFlags::NO_PROPAGATE_INHERIT_ACE)
+            && flags.intersects(
+                AceHeaderFlags::OBJECT_INHERIT_ACE | AceHeaderFlags::CONTAINER_INHERIT_ACE,
+            );
+        let effective = applies && matches_type;
+
+        let replacement = match *sid.alias() {
+            Some(SDDL_CREATOR_OWNER) => Some(self.owner),
+            Some(SDDL_CREATOR_GROUP) => Some(self.group),
+            _ => None,
+        };
+        let inherit_only_copy = AceHeaderFlags::INHERIT_ONLY_ACE | AceHeaderFlags::INHERITED_ACE;
+
+        let mut aces = Vec::new();
+        if effective && inheritable && replacement.is_none() && !mask.intersects(GENERIC_RIGHTS) {
+            // the ACE can be inherited unchanged
+            let flags = (flags - AceHeaderFlags::INHERIT_ONLY_ACE) | AceHeaderFlags::INHERITED_ACE;
+            aces.push(ace.copy_with(flags, mask, sid.clone())?);
+            return Ok(aces);
+        }
+
+        if effective {
+            let trustee = match replacement {
+                Some(trustee) => trustee,
+                None => Some(sid),
+            };
+            if let Some(trustee) = trustee {
+                aces.push(ace.copy_with(
+                    (flags - INHERITANCE_FLAGS) | AceHeaderFlags::INHERITED_ACE,
+                    mask.map_generic(self.mapping),
+                    trustee.clone(),
+                )?);
+            }
+        }
+        if inheritable {
+            aces.push(ace.copy_with(flags | inherit_only_copy, mask, sid.clone())?);
+        }
+        Ok(aces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenericMapping, Guid, SecurityDescriptor, Sid, Token};
+
+    fn child(parent: &str, is_container: bool, object_type: Option<Guid>) -> String {
+        let parent = SecurityDescriptor::from_sddl(parent, None).unwrap();
+        let token = Token::new(Sid::try_from("S-1-5-32-545").unwrap())
+            .with_primary_group(Sid::try_from("S-1-5-32-546").unwrap());
+        SecurityDescriptor::create_child(
+            &parent,
+            None,
+            is_container,
+            object_type,
+            &token,
+            &GenericMapping::FILE,
+        )
+        .unwrap()
+        .dacl_as_sddl_string()
+        .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_no_propagate() {
+        let parent = "D:AI(A;OICINP;FA;;;SY)";
+        assert_eq!(child(parent, true, None), "D:AI(A;ID;FA;;;SY)");
+        assert_eq!(child(parent, false, None), "D:AI(A;ID;FA;;;SY)");
+    }
+
+    #[test]
+    fn test_object_inherit_on_containers() {
+        let parent = "D:AI(A;OI;FR;;;WD)(A;CI;FW;;;WD)";
+        assert_eq!(
+            child(parent, true, None),
+            "D:AI(A;OIIOID;FR;;;WD)(A;CIID;FW;;;WD)"
+        );
+        assert_eq!(child(parent, false, None), "D:AI(A;ID;FR;;;WD)");
+    }
+
+    #[test]
+    fn test_creator_group() {
+        let parent = "D:AI(A;OICIIO;GR;;;CG)";
+        assert_eq!(
+            child(parent, true, None),
+            "D:AI(A;ID;FR;;;BG)(A;OICIIOID;GR;;;CG)"
+        );
+        assert_eq!(child(parent, false, None), "D:AI(A;ID;FR;;;BG)");
+    }
+
+    #[test]
+    fn test_inherited_object_type() {
+        let user = Guid::try_from("bf967aba-0de6-11d0-a285-00aa003049e2").unwrap();
+        let group = Guid::try_from("bf967a9c-0de6-11d0-a285-00aa003049e2").unwrap();
+        let parent = "D:AI(OA;CI;RP;;bf967aba-0de6-11d0-a285-00aa003049e2;AU)";
+        assert_eq!(
+            child(parent, true, Some(user)),
+            "D:AI(OA;CIID;RP;;bf967aba-0de6-11d0-a285-00aa003049e2;AU)"
+        );
+        assert_eq!(
+            child(parent, true, Some(group)),
+            "D:AI(OA;CIIOID;RP;;bf967aba-0de6-11d0-a285-00aa003049e2;AU)"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use bitflags::bitflags;
+use getset::CopyGetters;
+use serde::{Deserialize, Serialize};
+use strum::FromRepr;
+
+use crate::{
+    constants::{
+        MANDATORY_LABEL_AUTHORITY, SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP,
+        SYSTEM_MANDATORY_LABEL_NO_READ_UP, SYSTEM_MANDATORY_LABEL_NO_WRITE_UP,
+    },
+    AccessMask, GenericMapping, Sid,
+};
+
+/// Mandatory integrity level of a token or an object, which is encoded as
+/// RID of a SID in the `S-1-16` authority
+///
+/// <https://learn.microsoft.com/en-us/windows/win32/secauthz/mandatory-integrity-control>
+#[repr(u32)]
+#[derive(
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    FromRepr,
+)]
+pub enum IntegrityLevel {
+    Untrusted = 0x0000,
+    Low = 0x1000,
+
+    /// tokens without an explicit integrity level run at medium integrity
+    #[default]
+    Medium = 0x2000,
+    MediumPlus = 0x2100,
+    High = 0x3000,
+    System = 0x4000,
+    ProtectedProcess = 0x5000,
+}
+
+impl IntegrityLevel {
+    pub fn rid(&self) -> u32 {
+        *self as u32
+    }
+
+    /// returns the integrity level which is represented by `sid`, or `None`
+    /// if `sid` is not a mandatory label SID
+    pub fn from_sid(sid: &Sid) -> Option<Self> {
+        match &sid.sub_authority()[..] {
+            [rid] if *sid.identifier_authority() == MANDATORY_LABEL_AUTHORITY => {
+                Self::from_repr(*rid)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn sid(&self) -> Sid {
+        Sid::new(MANDATORY_LABEL_AUTHORITY, &[self.rid()])
+    }
+}
+
+bitflags! {
+    /// The access policy of a mandatory label, which is stored in the access
+    /// mask of the `SYSTEM_MANDATORY_LABEL_ACE`
+    #[derive(Eq, PartialEq, Debug, Copy, Clone, Serialize)]
+    pub struct MandatoryPolicy: u32 {
+        const NO_WRITE_UP = SYSTEM_MANDATORY_LABEL_NO_WRITE_UP.bits();
+        const NO_READ_UP = SYSTEM_MANDATORY_LABEL_NO_READ_UP.bits();
+        const NO_EXECUTE_UP = SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP.bits();
+    }
+}
+
+impl From<AccessMask> for MandatoryPolicy {
+    fn from(value: AccessMask) -> Self {
+        Self::from_bits_truncate(value.bits())
+    }
+}
+
+/// The mandatory integrity label of an object
+#[derive(Eq, PartialEq, Debug, Clone, Copy, CopyGetters, Serialize)]
+#[getset(get_copy = "pub")]
+pub struct IntegrityLabel {
+    level: IntegrityLevel,
+    policy: MandatoryPolicy,
+}
+
+impl IntegrityLabel {
+    pub fn new(level: IntegrityLevel, policy: MandatoryPolicy) -> Self {
+        Self { level, policy }
+    }
+
+    /// returns the access rights which a principal with the integrity level
+    /// `token_level` can get at most
+    pub fn allowed_access(
+        &self,
+        token_level: IntegrityLevel,
+        mapping: &GenericMapping,
+    ) -> AccessMask {
+        if token_level >= self.level {
+            return AccessMask::all();
+        }
+
+        let mut allowed = AccessMask::empty();
+        if !self.policy.contains(MandatoryPolicy::NO_READ_UP) {
+            allowed |= mapping.generic_read();
+        }
+        if !self.policy.contains(MandatoryPolicy::NO_WRITE_UP) {
+            allowed |= mapping.generic_write();
+        }
+        if !self.policy.contains(MandatoryPolicy::NO_EXECUTE_UP) {
+            allowed |= mapping.generic_execute();
+        }
+        allowed
+    }
+}
+
+/// Objects without a mandatory label have medium integrity, and principals
+/// with a lower integrity level cannot write to them
+impl Default for IntegrityLabel {
+    fn default() -> Self {
+        Self::new(IntegrityLevel::Medium, MandatoryPolicy::NO_WRITE_UP)
+    }
+}
@@ -11,11 +11,29 @@ mod ace_flags;
 mod ace_header;
 mod access_mask;
 mod guid;
+mod schema;
+mod name_context;
 mod offset;
 mod sddl_h;
 mod error;
 mod raw_size;
 mod parsed_ace_contents;
+mod conditional_expression;
+mod conditional_evaluation;
+mod claim_value;
+mod claim_attribute;
+mod generic_mapping;
+mod token;
+mod integrity_level;
+mod protection_level;
+mod access_check;
+mod central_access_policy;
+mod inheritance;
+mod propagation;
+mod object_type_list;
+mod object_kind;
+mod parse_error;
+mod parse_warning;
 pub (crate) use parsed_ace_contents::*;
 pub (crate) mod parsing;
 
@@ -30,6 +48,23 @@ pub use ace_flags::*;
 pub use ace_header::*;
 pub use access_mask::AccessMask;
 pub use guid::*;
+pub use schema::*;
+pub use name_context::*;
+pub use conditional_expression::*;
+pub use conditional_evaluation::*;
+pub use claim_value::*;
+pub use claim_attribute::*;
+pub use generic_mapping::*;
+pub use token::*;
+pub use integrity_level::*;
+pub use protection_level::*;
+pub use access_check::*;
+pub use central_access_policy::*;
+pub use propagation::*;
+pub use object_type_list::*;
+pub use object_kind::*;
+pub use parse_error::*;
+pub use parse_warning::*;
 pub use error::*;
 pub use raw_size::*;
 pub(crate) use offset::*;
@@ -0,0 +1,729 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display, io::Cursor, sync::Arc};
+
+use base64::prelude::*;
+use binrw::BinReaderExt;
+use strum::{Display, EnumString};
+
+use crate::{
+    ActiveDirectorySchema, Guid, GuidResolver, ObjectKind, SchemaEntry, SchemaObjectKind, Sid,
+    VirtualAccountKind,
+};
+
+thread_local! {
+    static ACTIVE_CONTEXT: RefCell<Option<Arc<NameContext>>> = const { RefCell::new(None) };
+}
+
+/// The format of a file from which names are loaded into a [`NameContext`]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum NameFormat {
+    /// an LDIF export, as created by `ldifde` or `ldapsearch`
+    Ldif,
+
+    /// a CSV export with a header line, as created by `csvde` or
+    /// `Export-Csv`
+    Csv,
+
+    /// a JSON list of objects, as created by `ConvertTo-Json`
+    Json,
+}
+
+impl NameFormat {
+    /// returns the format which is usually stored in files with the extension
+    /// `extension`
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "ldf" | "ldif" => Some(Self::Ldif),
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A record which has been skipped by [`NameContext::load`], because it
+/// contains a value which is not a valid GUID or SID. `record` is the index
+/// of the record in the file, and `dn` its distinguished name, if the record
+/// has one.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum NameWarning {
+    /// the `schemaIDGUID` or `rightsGuid` of a record is not a valid GUID
+    InvalidGuid {
+        record: usize,
+        dn: Option<String>,
+        attribute: String,
+        reason: String,
+    },
+
+    /// the `objectSid` of a record is not a valid SID
+    InvalidSid {
+        record: usize,
+        dn: Option<String>,
+        reason: String,
+    },
+}
+
+impl Display for NameWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (record, dn) = match self {
+            Self::InvalidGuid { record, dn, .. } | Self::InvalidSid { record, dn, .. } => {
+                (record, dn)
+            }
+        };
+        match dn {
+            Some(dn) => write!(f, "skipped record {record} ({dn}): ")?,
+            None => write!(f, "skipped record {record}: ")?,
+        }
+        match self {
+            Self::InvalidGuid {
+                attribute, reason, ..
+            } => write!(f, "invalid {attribute}: {reason}"),
+            Self::InvalidSid { reason, .. } => write!(f, "invalid objectSid: {reason}"),
+        }
+    }
+}
+
+/// Names of the SIDs and schema GUIDs of an environment, which are loaded from
+/// an export of its directory. Objects with a `schemaIDGUID` (classes and
+/// attributes) or a `rightsGuid` (control access rights) are added as
+/// [`SchemaEntry`]s, and objects with an `objectSid` and a `sAMAccountName`
+/// are added as account names.
+///
+/// While a context is active (see [`NameContext::scope`]), the `Serialize`
+/// implementations of [`Sid`] and [`Guid`] add the names of the context, and
+/// [`crate::AccessMask`] names the rights of the context's [`ObjectKind`].
+///
+/// ```
+/// use std::sync::Arc;
+/// use sddl::*;
+/// let ldif = "\
+/// dn: CN=ms-Mcs-AdmPwd,CN=Schema,CN=Configuration,DC=example,DC=com
+/// objectClass: attributeSchema
+/// lDAPDisplayName: ms-Mcs-AdmPwd
+/// schemaIDGUID:: xh5T8zBjjk+NOXpnH7rGBQ==
+///
+/// dn: CN=Alice,CN=Users,DC=example,DC=com
+/// sAMAccountName: alice
+/// objectSid: S-1-5-21-1-2-3-1104
+/// ";
+/// let context = Arc::new(NameContext::from_data(NameFormat::Ldif, ldif).unwrap());
+/// let guid = Guid::try_from("f3531ec6-6330-4f8e-8d39-7a671fbac605").unwrap();
+/// let sid = Sid::try_from("S-1-5-21-1-2-3-1104").unwrap();
+/// assert_eq!(context.resolve_guid(&guid).unwrap().name(), "ms-Mcs-AdmPwd");
+/// assert_eq!(context.sid_name(&sid), Some("alice"));
+///
+/// let json = context.scope(|| serde_json::to_value(&sid).unwrap());
+/// assert_eq!(json["name"], "alice");
+/// ```
+#[derive(Debug, Clone)]
+pub struct NameContext {
+    schema: ActiveDirectorySchema,
+    sids: HashMap<Sid, String>,
+    object_kind: Option<ObjectKind>,
+}
+
+impl Default for NameContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NameContext {
+    /// creates a context without any names. The names of the default schema
+    /// are always known and need not be added.
+    pub fn new() -> Self {
+        Self {
+            schema: ActiveDirectorySchema::empty(),
+            sids: HashMap::new(),
+            object_kind: None,
+        }
+    }
+
+    /// creates a context from the contents of an export file. Records with
+    /// invalid values are skipped; use [`NameContext::load`] to find out
+    /// which records have been skipped.
+    pub fn from_data(format: NameFormat, data: &str) -> Result<Self, crate::Error> {
+        let mut context = Self::new();
+        context.load(format, data)?;
+        Ok(context)
+    }
+
+    pub fn with_sid_name(mut self, sid: Sid, name: impl Into<String>) -> Self {
+        self.sids.insert(sid, name.into());
+        self
+    }
+
+    pub fn with_schema_entry(mut self, entry: SchemaEntry) -> Self {
+        self.schema.insert(entry);
+        self
+    }
+
+    /// sets the kind of the objects whose security descriptors are
+    /// serialized, which determines the names of the object-specific
+    /// access rights
+    pub fn with_object_kind(mut self, kind: ObjectKind) -> Self {
+        self.object_kind = Some(kind);
+        self
+    }
+
+    pub fn object_kind(&self) -> Option<ObjectKind> {
+        self.object_kind
+    }
+
+    /// adds the SIDs which are derived from `names`, such as the names of all
+    /// services of a system, so that they can be named without the system
+    pub fn with_virtual_accounts<'n>(
+        mut self,
+        kind: VirtualAccountKind,
+        names: impl IntoIterator<Item = &'n str>,
+    ) -> Self {
+        for name in names {
+            self.sids.insert(
+                Sid::virtual_account_sid(kind, name),
+                format!(r"{kind}\{name}"),
+            );
+        }
+        self
+    }
+
+    /// returns the account name of `sid`
+    pub fn sid_name(&self, sid: &Sid) -> Option<&str> {
+        self.sids.get(sid).map(String::as_str)
+    }
+
+    /// adds the names of all objects in an export file. Returns the number
+    /// of objects from which a name has been taken, and the records which
+    /// have been skipped because of an invalid GUID or SID. Fails only if
+    /// the file itself cannot be parsed.
+    pub fn load(
+        &mut self,
+        format: NameFormat,
+        data: &str,
+    ) -> Result<(usize, Vec<NameWarning>), crate::Error> {
+        let records = match format {
+            NameFormat::Ldif => parse_ldif(data)?,
+            NameFormat::Csv => parse_csv(data)?,
+            NameFormat::Json => parse_json(data)?,
+        };
+        let mut count = 0;
+        let mut warnings = Vec::new();
+        for (index, record) in records.iter().enumerate() {
+            match self.add_record(index, record) {
+                Ok(true) => count += 1,
+                Ok(false) => (),
+                Err(warning) => warnings.push(warning),
+            }
+        }
+        Ok((count, warnings))
+    }
+
+    /// makes this context the active context of the current thread while `f`
+    /// is running
+    pub fn scope<R>(self: &Arc<Self>, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<Arc<NameContext>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                ACTIVE_CONTEXT.with(|active| *active.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(ACTIVE_CONTEXT.with(|active| active.replace(Some(Arc::clone(self)))));
+        f()
+    }
+
+    /// calls `f` with the active context of the current thread
+    pub(crate) fn with_active<R>(f: impl FnOnce(Option<&NameContext>) -> R) -> R {
+        ACTIVE_CONTEXT.with(|active| f(active.borrow().as_deref()))
+    }
+
+    /// adds the names of a record, or none of them if one of its values is
+    /// invalid
+    fn add_record(&mut self, index: usize, record: &Record) -> Result<bool, NameWarning> {
+        let text = |attribute: &str| {
+            record
+                .get(attribute)
+                .and_then(|values| values.first())
+                .and_then(RawValue::as_text)
+        };
+        let first = |attribute: &str| record.get(attribute).and_then(|values| values.first());
+        let dn = || {
+            text("dn")
+                .or_else(|| text("distinguishedname"))
+                .map(str::to_owned)
+        };
+        let guid = |value: &RawValue, attribute: &str| {
+            value.to_guid().map_err(|reason| NameWarning::InvalidGuid {
+                record: index,
+                dn: dn(),
+                attribute: attribute.to_owned(),
+                reason,
+            })
+        };
+        let mut entries = Vec::new();
+
+        if let Some(value) = first("schemaidguid") {
+            if let Some(name) = text("ldapdisplayname").or_else(|| text("cn")) {
+                let is_class = record.get("objectclass").is_some_and(|classes| {
+                    classes.iter().any(|class| {
+                        class
+                            .as_text()
+                            .is_some_and(|class| class.eq_ignore_ascii_case("classSchema"))
+                    })
+                });
+                let kind = if is_class {
+                    SchemaObjectKind::Class
+                } else {
+                    SchemaObjectKind::Attribute
+                };
+                entries.push(SchemaEntry::new(guid(value, "schemaIDGUID")?, name, kind));
+            }
+        }
+
+        if let Some(value) = first("rightsguid") {
+            if let Some(name) = text("name").or_else(|| text("cn")) {
+                // see the validAccesses attribute in [MS-ADTS] section 5.1.3.2.1
+                let kind = match text("validaccesses").and_then(|v| v.trim().parse::<u32>().ok()) {
+                    Some(0x08) => SchemaObjectKind::ValidatedWrite,
+                    Some(0x30) => SchemaObjectKind::PropertySet,
+                    _ => SchemaObjectKind::ExtendedRight,
+                };
+                entries.push(SchemaEntry::new(guid(value, "rightsGuid")?, name, kind));
+            }
+        }
+
+        let account = match (first("objectsid"), text("samaccountname")) {
+            (Some(value), Some(name)) => {
+                let sid = value.to_sid().map_err(|reason| NameWarning::InvalidSid {
+                    record: index,
+                    dn: dn(),
+                    reason,
+                })?;
+                Some((sid, name.to_owned()))
+            }
+            _ => None,
+        };
+
+        let found = !entries.is_empty() || account.is_some();
+        for entry in entries {
+            self.schema.insert(entry);
+        }
+        self.sids.extend(account);
+        Ok(found)
+    }
+}
+
+impl GuidResolver for NameContext {
+    fn resolve_guid(&self, guid: &Guid) -> Option<&SchemaEntry> {
+        self.schema.resolve_guid(guid)
+    }
+}
+
+/// the value of an attribute in an export file
+#[derive(Debug)]
+enum RawValue {
+    Text(String),
+    Bytes(Vec<u8>),
+
+    /// a value which could not be decoded, and the reason why
+    Invalid(String),
+}
+
+/// the attributes of an object in an export file, by their lowercase names
+type Record = HashMap<String, Vec<RawValue>>;
+
+impl RawValue {
+    fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Bytes(_) | Self::Invalid(_) => None,
+        }
+    }
+
+    /// returns the binary value, which is written as `X'0a1b'` by `csvde`
+    /// and as base64 by other tools
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes.clone()),
+            Self::Invalid(_) => None,
+            Self::Text(text) => match text.strip_prefix("X'").and_then(|t| t.strip_suffix('\'')) {
+                Some(hex) => (0..hex.len())
+                    .step_by(2)
+                    .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+                    .collect(),
+                None => BASE64_STANDARD.decode(text.trim()).ok(),
+            },
+        }
+    }
+
+    fn to_guid(&self) -> Result<Guid, String> {
+        if let Self::Invalid(reason) = self {
+            return Err(reason.clone());
+        }
+        if let Some(guid) = self.as_text().and_then(|text| Guid::try_from(text.trim()).ok()) {
+            return Ok(guid);
+        }
+        self.to_bytes()
+            .and_then(|bytes| <[u8; 16]>::try_from(bytes).ok())
+            .map(Guid::from_bytes)
+            .ok_or_else(|| format!("{self} is not a GUID"))
+    }
+
+    fn to_sid(&self) -> Result<Sid, String> {
+        match self {
+            Self::Invalid(reason) => Err(reason.clone()),
+            Self::Text(text) if text.trim().starts_with("S-") => {
+                Sid::try_from(text.trim()).map_err(|why| why.to_string())
+            }
+            _ => self
+                .to_bytes()
+                .and_then(|bytes| Cursor::new(bytes).read_le::<Sid>().ok())
+                .ok_or_else(|| format!("{self} is not a SID")),
+        }
+    }
+}
+
+impl Display for RawValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text(text) => write!(f, "'{text}'"),
+            Self::Bytes(bytes) => write!(f, "the {} byte value", bytes.len()),
+            Self::Invalid(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+fn add_value(record: &mut Record, attribute: &str, value: RawValue) {
+    // attribute options like `;binary` do not change the attribute
+    let attribute = attribute.split(';').next().unwrap_or_default().trim();
+    record
+        .entry(attribute.to_lowercase())
+        .or_default()
+        .push(value);
+}
+
+/// parses the records of an LDIF file (RFC 2849)
+fn parse_ldif(data: &str) -> Result<Vec<Record>, crate::Error> {
+    // continuation lines begin with a single space
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_comment = false;
+    for line in data.lines() {
+        match line.strip_prefix(' ') {
+            Some(continuation) if !lines.is_empty() => {
+                if !in_comment {
+                    lines.last_mut().unwrap().push_str(continuation);
+                }
+            }
+            _ => {
+                in_comment = line.starts_with('#');
+                if !in_comment {
+                    lines.push(line.to_owned());
+                }
+            }
+        }
+    }
+
+    let mut records = Vec::new();
+    let mut record = Record::new();
+    for line in lines {
+        if line.trim().is_empty() {
+            if !record.is_empty() {
+                records.push(std::mem::take(&mut record));
+            }
+            continue;
+        }
+        let Some((attribute, value)) = line.split_once(':') else {
+            return Err(crate::Error::IllegalNameMap(format!(
+                "invalid LDIF line: '{line}'"
+            )));
+        };
+        let value = if let Some(encoded) = value.strip_prefix(':') {
+            match BASE64_STANDARD.decode(encoded.trim()) {
+                Ok(bytes) => RawValue::Bytes(bytes),
+                Err(why) => RawValue::Invalid(format!("invalid base64 value: {why}")),
+            }
+        } else if value.starts_with('<') {
+            // values which are stored in external files are not supported
+            continue;
+        } else {
+            RawValue::Text(value.trim_start().to_owned())
+        };
+        add_value(&mut record, attribute, value);
+    }
+    if !record.is_empty() {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// parses a CSV file whose first line contains the names of the attributes.
+/// Multiple values of `objectClass` are separated by `;`.
+fn parse_csv(data: &str) -> Result<Vec<Record>, crate::Error> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => (),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(crate::Error::IllegalNameMap(
+            "unterminated quoted CSV field".to_owned(),
+        ));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    let mut rows = rows
+        .into_iter()
+        .filter(|row| !(row.len() == 1 && row[0].trim().is_empty()));
+    let Some(header) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    Ok(rows
+        .map(|row| {
+            let mut record = Record::new();
+            for (attribute, value) in header.iter().zip(row) {
+                if value.is_empty() {
+                    continue;
+                }
+                if attribute.eq_ignore_ascii_case("objectClass") {
+                    for class in value.split(';') {
+                        add_value(&mut record, attribute, RawValue::Text(class.to_owned()));
+                    }
+                } else {
+                    add_value(&mut record, attribute, RawValue::Text(value));
+                }
+            }
+            record
+        })
+        .collect())
+}
+
+/// parses a JSON object or a list of JSON objects. Binary values can be
+/// arrays of bytes, and SIDs can be objects with a `Value` field, as they
+/// are written by PowerShell.
+fn parse_json(data: &str) -> Result<Vec<Record>, crate::Error> {
+    let value: serde_json::Value = serde_json::from_str(data)
+        .map_err(|why| crate::Error::IllegalNameMap(format!("invalid JSON: {why}")))?;
+    let objects = match value {
+        serde_json::Value::Array(objects) => objects,
+        object => vec![object],
+    };
+
+    let mut records = Vec::new();
+    for object in objects {
+        let serde_json::Value::Object(attributes) = object else {
+            return Err(crate::Error::IllegalNameMap(
+                "the JSON data must contain objects".to_owned(),
+            ));
+        };
+        let mut record = Record::new();
+        for (attribute, value) in attributes {
+            for value in json_values(value) {
+                add_value(&mut record, &attribute, value);
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn json_values(value: serde_json::Value) -> Vec<RawValue> {
+    use serde_json::Value;
+    match value {
+        Value::String(text) => vec![RawValue::Text(text)],
+        Value::Number(number) => vec![RawValue::Text(number.to_string())],
+        Value::Array(items) if !items.is_empty() && items.iter().all(|i| i.is_u64()) => {
+            match items.iter().map(|i| u8::try_from(i.as_u64()?).ok()).collect() {
+                Some(bytes) => vec![RawValue::Bytes(bytes)],
+                None => Vec::new(),
+            }
+        }
+        Value::Array(items) => items.into_iter().flat_map(json_values).collect(),
+        Value::Object(mut fields) => ["Value", "value", "sid", "guid"]
+            .into_iter()
+            .find_map(|key| fields.remove(key))
+            .map(json_values)
+            .unwrap_or_default(),
+        Value::Null | Value::Bool(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guid(value: &str) -> Guid {
+        Guid::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_ldif() {
+        let ldif = "\
+version: 1
+
+# the class of LAPS
+dn: CN=ms-Example-Class,CN=Schema,CN=Configuration,DC=example,DC=com
+objectClass: top
+objectClass: classSchema
+lDAPDisplayName: ms-Example
+ -Class
+schemaIDGUID:: xh5T8zBjjk+NOXpnH7rGBQ==
+
+dn: CN=Example-Right,CN=Extended-Rights,CN=Configuration,DC=example,DC=com
+objectClass: controlAccessRight
+name: Example-Right
+rightsGuid: 0d5e1bc8-5f9e-4e35-a5c6-1e32b5d86a08
+validAccesses: 256
+
+dn: CN=Bob,CN=Users,DC=example,DC=com
+sAMAccountName: bob
+objectSid:: AQUAAAAAAAUVAAAAAQAAAAIAAAADAAAAUQQAAA==
+";
+        let context = NameContext::from_data(NameFormat::Ldif, ldif).unwrap();
+        let class = context
+            .resolve_guid(&guid("f3531ec6-6330-4f8e-8d39-7a671fbac605"))
+            .unwrap();
+        assert_eq!(class.name(), "ms-Example-Class");
+        assert_eq!(*class.kind(), SchemaObjectKind::Class);
+
+        let right = context
+            .resolve_guid(&guid("0d5e1bc8-5f9e-4e35-a5c6-1e32b5d86a08"))
+            .unwrap();
+        assert_eq!(*right.kind(), SchemaObjectKind::ExtendedRight);
+
+        let bob = Sid::try_from("S-1-5-21-1-2-3-1105").unwrap();
+        assert_eq!(context.sid_name(&bob), Some("bob"));
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = "\
+DN,objectClass,sAMAccountName,objectSid,schemaIDGUID,lDAPDisplayName
+\"CN=Doe\\, John,CN=Users,DC=example,DC=com\",top;person;user,jdoe,S-1-5-21-1-2-3-1106,,
+\"CN=ms-Example,CN=Schema,CN=Configuration,DC=example,DC=com\",top;attributeSchema,,,X'c61e53f330638e4f8d397a671fbac605',ms-Example
+";
+        let mut context = NameContext::new();
+        assert_eq!(context.load(NameFormat::Csv, csv).unwrap(), (2, Vec::new()));
+        let jdoe = Sid::try_from("S-1-5-21-1-2-3-1106").unwrap();
+        assert_eq!(context.sid_name(&jdoe), Some("jdoe"));
+        let attribute = context
+            .resolve_guid(&guid("f3531ec6-6330-4f8e-8d39-7a671fbac605"))
+            .unwrap();
+        assert_eq!(*attribute.kind(), SchemaObjectKind::Attribute);
+    }
+
+    #[test]
+    fn test_json() {
+        let json = r#"[
+            {
+                "SamAccountName": "svc_sql",
+                "objectSid": { "BinaryLength": 28, "Value": "S-1-5-21-1-2-3-1107" }
+            },
+            {
+                "lDAPDisplayName": "ms-Example",
+                "schemaIDGUID": [198, 30, 83, 243, 48, 99, 142, 79, 141, 57, 122, 103, 31, 186, 198, 5]
+            },
+            { "name": "Example-Property-Set", "rightsGuid": "0d5e1bc8-5f9e-4e35-a5c6-1e32b5d86a08", "validAccesses": 48 }
+        ]"#;
+        let context = NameContext::from_data(NameFormat::Json, json).unwrap();
+        let sid = Sid::try_from("S-1-5-21-1-2-3-1107").unwrap();
+        assert_eq!(context.sid_name(&sid), Some("svc_sql"));
+        assert_eq!(
+            context
+                .resolve_guid(&guid("f3531ec6-6330-4f8e-8d39-7a671fbac605"))
+                .unwrap()
+                .name(),
+            "ms-Example"
+        );
+        assert_eq!(
+            *context
+                .resolve_guid(&guid("0d5e1bc8-5f9e-4e35-a5c6-1e32b5d86a08"))
+                .unwrap()
+                .kind(),
+            SchemaObjectKind::PropertySet
+        );
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        assert!(NameContext::from_data(NameFormat::Ldif, "invalid line").is_err());
+        assert!(NameContext::from_data(NameFormat::Csv, "a,b\n\"x,y").is_err());
+        assert!(NameContext::from_data(NameFormat::Json, "[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_invalid_records_are_skipped() {
+        let ldif = "\
+dn: CN=Broken,CN=Users,DC=example,DC=com
+sAMAccountName: broken
+objectSid:: ###
+
+dn: CN=Broken-Attribute,CN=Schema,CN=Configuration,DC=example,DC=com
+lDAPDisplayName: broken-Attribute
+schemaIDGUID:: AAEC
+
+dn: CN=Bob,CN=Users,DC=example,DC=com
+sAMAccountName: bob
+objectSid: S-1-5-21-1-2-3-1105
+";
+        let mut context = NameContext::new();
+        let (count, warnings) = context.load(NameFormat::Ldif, ldif).unwrap();
+        assert_eq!(count, 1);
+        assert!(matches!(
+            &warnings[..],
+            [
+                NameWarning::InvalidSid { record: 0, dn: Some(dn), .. },
+                NameWarning::InvalidGuid { record: 1, .. },
+            ] if dn == "CN=Broken,CN=Users,DC=example,DC=com"
+        ));
+        assert_eq!(
+            warnings[1].to_string(),
+            "skipped record 1 (CN=Broken-Attribute,CN=Schema,CN=Configuration,DC=example,DC=com): \
+             invalid schemaIDGUID: the 3 byte value is not a GUID"
+        );
+        let bob = Sid::try_from("S-1-5-21-1-2-3-1105").unwrap();
+        assert_eq!(context.sid_name(&bob), Some("bob"));
+
+        let json = r#"[
+            { "sAMAccountName": "x", "objectSid": "invalid" },
+            { "sAMAccountName": "y", "objectSid": "S-1-5-21-1-2-3-1106" }
+        ]"#;
+        let (count, warnings) = context.load(NameFormat::Json, json).unwrap();
+        assert_eq!(count, 1);
+        assert!(matches!(
+            &warnings[..],
+            [NameWarning::InvalidSid {
+                record: 0,
+                dn: None,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_virtual_accounts() {
+        let context = NameContext::new()
+            .with_virtual_accounts(VirtualAccountKind::Service, ["Spooler", "TrustedInstaller"]);
+        let sid = Sid::try_from("S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464")
+            .unwrap();
+        assert_eq!(context.sid_name(&sid), Some(r"NT SERVICE\TrustedInstaller"));
+        assert_eq!(context.sid_name(&Sid::app_pool_sid("Spooler")), None);
+    }
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+use crate::GenericMapping;
+
+/// The type of a securable object, which determines the meaning of the
+/// object-specific access rights (the lower 16 bits of an
+/// [`crate::AccessMask`])
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Serialize, Deserialize, Display, EnumString)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum ObjectKind {
+    File,
+    Directory,
+    NamedPipe,
+    RegistryKey,
+    Service,
+    Process,
+    Thread,
+    Token,
+    DsObject,
+    Printer,
+    WmiNamespace,
+}
+
+impl ObjectKind {
+    /// returns the mapping of generic access rights for this kind of object
+    pub fn generic_mapping(&self) -> GenericMapping {
+        match self {
+            Self::File => GenericMapping::FILE,
+            Self::Directory => GenericMapping::DIRECTORY,
+            Self::NamedPipe => GenericMapping::NAMED_PIPE,
+            Self::RegistryKey => GenericMapping::REGISTRY_KEY,
+            Self::Service => GenericMapping::SERVICE,
+            Self::Process => GenericMapping::PROCESS,
+            Self::Thread => GenericMapping::THREAD,
+            Self::Token => GenericMapping::TOKEN,
+            Self::DsObject => GenericMapping::DS_OBJECT,
+            Self::Printer => GenericMapping::PRINTER,
+            Self::WmiNamespace => GenericMapping::WMI_NAMESPACE,
+        }
+    }
+
+    /// returns the names of the object-specific access rights
+    pub(crate) fn specific_rights(&self) -> &'static [(u32, &'static str)] {
+        match self {
+            Self::File => &[
+                (0x0001, "FILE_READ_DATA"),
+                (0x0002, "FILE_WRITE_DATA"),
+                (0x0004, "FILE_APPEND_DATA"),
+                (0x0008, "FILE_READ_EA"),
+                (0x0010, "FILE_WRITE_EA"),
+                (0x0020, "FILE_EXECUTE"),
+                (0x0080, "FILE_READ_ATTRIBUTES"),
+                (0x0100, "FILE_WRITE_ATTRIBUTES"),
+            ],
+            Self::Directory => &[
+                (0x0001, "FILE_LIST_DIRECTORY"),
+                (0x0002, "FILE_ADD_FILE"),
+                (0x0004, "FILE_ADD_SUBDIRECTORY"),
+                (0x0008, "FILE_READ_EA"),
+                (0x0010, "FILE_WRITE_EA"),
+                (0x0020, "FILE_TRAVERSE"),
+                (0x0040, "FILE_DELETE_CHILD"),
+                (0x0080, "FILE_READ_ATTRIBUTES"),
+                (0x0100, "FILE_WRITE_ATTRIBUTES"),
+            ],
+            Self::NamedPipe => &[
+                (0x0001, "FILE_READ_DATA"),
+                (0x0002, "FILE_WRITE_DATA"),
+                (0x0004, "FILE_CREATE_PIPE_INSTANCE"),
+                (0x0080, "FILE_READ_ATTRIBUTES"),
+                (0x0100, "FILE_WRITE_ATTRIBUTES"),
+            ],
+            Self::RegistryKey => &[
+                (0x0001, "KEY_QUERY_VALUE"),
+                (0x0002, "KEY_SET_VALUE"),
+                (0x0004, "KEY_CREATE_SUB_KEY"),
+                (0x0008, "KEY_ENUMERATE_SUB_KEYS"),
+                (0x0010, "KEY_NOTIFY"),
+                (0x0020, "KEY_CREATE_LINK"),
+                (0x0100, "KEY_WOW64_64KEY"),
+                (0x0200, "KEY_WOW64_32KEY"),
+            ],
+            Self::Service => &[
+                (0x0001, "SERVICE_QUERY_CONFIG"),
+                (0x0002, "SERVICE_CHANGE_CONFIG"),
+                (0x0004, "SERVICE_QUERY_STATUS"),
+                (0x0008, "SERVICE_ENUMERATE_DEPENDENTS"),
+                (0x0010, "SERVICE_START"),
+                (0x0020, "SERVICE_STOP"),
+                (0x0040, "SERVICE_PAUSE_CONTINUE"),
+                (0x0080, "SERVICE_INTERROGATE"),
+                (0x0100, "SERVICE_USER_DEFINED_CONTROL"),
+            ],
+            Self::Process => &[
+                (0x0001, "PROCESS_TERMINATE"),
+                (0x0002, "PROCESS_CREATE_THREAD"),
+                (0x0004, "PROCESS_SET_SESSIONID"),
+                (0x0008, "PROCESS_VM_OPERATION"),
+                (0x0010, "PROCESS_VM_READ"),
+                (0x0020, "PROCESS_VM_WRITE"),
+                (0x0040, "PROCESS_DUP_HANDLE"),
+                (0x0080, "PROCESS_CREATE_PROCESS"),
+                (0x0100, "PROCESS_SET_QUOTA"),
+                (0x0200, "PROCESS_SET_INFORMATION"),
+                (0x0400, "PROCESS_QUERY_INFORMATION"),
+                (0x0800, "PROCESS_SUSPEND_RESUME"),
+                (0x1000, "PROCESS_QUERY_LIMITED_INFORMATION"),
+                (0x2000, "PROCESS_SET_LIMITED_INFORMATION"),
+            ],
+            Self::Thread => &[
+                (0x0001, "THREAD_TERMINATE"),
+                (0x0002, "THREAD_SUSPEND_RESUME"),
+                (0x0008, "THREAD_GET_CONTEXT"),
+                (0x0010, "THREAD_SET_CONTEXT"),
+                (0x0020, "THREAD_SET_INFORMATION"),
+                (0x0040, "THREAD_QUERY_INFORMATION"),
+                (0x0080, "THREAD_SET_THREAD_TOKEN"),
+                (0x0100, "THREAD_IMPERSONATE"),
+                (0x0200, "THREAD_DIRECT_IMPERSONATION"),
+                (0x0400, "THREAD_SET_LIMITED_INFORMATION"),
+                (0x0800, "THREAD_QUERY_LIMITED_INFORMATION"),
+                (0x1000, "THREAD_RESUME"),
+            ],
+            Self::Token => &[
+                (0x0001, "TOKEN_ASSIGN_PRIMARY"),
+                (0x0002, "TOKEN_DUPLICATE"),
+                (0x0004, "TOKEN_IMPERSONATE"),
+                (0x0008, "TOKEN_QUERY"),
+                (0x0010, "TOKEN_QUERY_SOURCE"),
+                (0x0020, "TOKEN_ADJUST_PRIVILEGES"),
+                (0x0040, "TOKEN_ADJUST_GROUPS"),
+                (0x0080, "TOKEN_ADJUST_DEFAULT"),
+                (0x0100, "TOKEN_ADJUST_SESSIONID"),
+            ],
+            Self::DsObject => &[
+                (0x0001, "ADS_RIGHT_DS_CREATE_CHILD"),
+                (0x0002, "ADS_RIGHT_DS_DELETE_CHILD"),
+                (0x0004, "ADS_RIGHT_ACTRL_DS_LIST"),
+                (0x0008, "ADS_RIGHT_DS_SELF"),
+                (0x0010, "ADS_RIGHT_DS_READ_PROP"),
+                (0x0020, "ADS_RIGHT_DS_WRITE_PROP"),
+                (0x0040, "ADS_RIGHT_DS_DELETE_TREE"),
+                (0x0080, "ADS_RIGHT_DS_LIST_OBJECT"),
+                (0x0100, "ADS_RIGHT_DS_CONTROL_ACCESS"),
+            ],
+            Self::Printer => &[
+                (0x0001, "SERVER_ACCESS_ADMINISTER"),
+                (0x0002, "SERVER_ACCESS_ENUMERATE"),
+                (0x0004, "PRINTER_ACCESS_ADMINISTER"),
+                (0x0008, "PRINTER_ACCESS_USE"),
+                (0x0010, "JOB_ACCESS_ADMINISTER"),
+                (0x0020, "JOB_ACCESS_READ"),
+                (0x0040, "PRINTER_ACCESS_MANAGE_LIMITED"),
+            ],
+            Self::WmiNamespace => &[
+                (0x0001, "WBEM_ENABLE"),
+                (0x0002, "WBEM_METHOD_EXECUTE"),
+                (0x0004, "WBEM_FULL_WRITE_REP"),
+                (0x0008, "WBEM_PARTIAL_WRITE_REP"),
+                (0x0010, "WBEM_WRITE_PROVIDER"),
+                (0x0020, "WBEM_REMOTE_ACCESS"),
+                (0x0040, "WBEM_RIGHT_SUBSCRIBE"),
+                (0x0080, "WBEM_RIGHT_PUBLISH"),
+            ],
+        }
+    }
+}
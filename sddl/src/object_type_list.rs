@@ -0,0 +1,103 @@
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+
+use crate::Guid;
+
+/// level of the object class in an [`ObjectTypeList`]
+pub const ACCESS_OBJECT_GUID: u16 = 0;
+
+/// level of property sets and extended rights in an [`ObjectTypeList`]
+pub const ACCESS_PROPERTY_SET_GUID: u16 = 1;
+
+/// level of properties in an [`ObjectTypeList`]
+pub const ACCESS_PROPERTY_GUID: u16 = 2;
+
+/// An element of an [`ObjectTypeList`]
+///
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-object_type_list>
+#[derive(Eq, PartialEq, Debug, Clone, Getters, CopyGetters, Serialize)]
+pub struct ObjectType {
+    #[getset(get_copy = "pub")]
+    level: u16,
+
+    #[getset(get = "pub")]
+    guid: Guid,
+}
+
+/// A hierarchy of object types, which is stored in depth-first order. The
+/// first element is the object class, followed by its property sets, each of
+/// them followed by its properties.
+///
+/// ```
+/// use sddl::*;
+/// let user = Guid::try_from("bf967aba-0de6-11d0-a285-00aa003049e2").unwrap();
+/// let personal_information = Guid::try_from("77b5b886-944a-11d1-aebd-0000f80367c1").unwrap();
+/// let telephone_number = Guid::try_from("bf967a49-0de6-11d0-a285-00aa003049e2").unwrap();
+///
+/// let list = ObjectTypeList::new(user)
+///     .with_property_set(personal_information)
+///     .with_property(telephone_number);
+/// assert_eq!(list.entries().len(), 3);
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct ObjectTypeList {
+    entries: Vec<ObjectType>,
+}
+
+impl ObjectTypeList {
+    /// creates a list which contains only the object class
+    pub fn new(object_class: Guid) -> Self {
+        Self {
+            entries: vec![ObjectType {
+                level: ACCESS_OBJECT_GUID,
+                guid: object_class,
+            }],
+        }
+    }
+
+    /// adds a property set or an extended right to the object class
+    pub fn with_property_set(self, guid: Guid) -> Self {
+        self.with_entry(ACCESS_PROPERTY_SET_GUID, guid)
+    }
+
+    /// adds a property to the most recently added property set
+    pub fn with_property(self, guid: Guid) -> Self {
+        self.with_entry(ACCESS_PROPERTY_GUID, guid)
+    }
+
+    fn with_entry(mut self, level: u16, guid: Guid) -> Self {
+        self.entries.push(ObjectType { level, guid });
+        self
+    }
+
+    /// returns the index of the entry with the given GUID
+    pub fn position(&self, guid: &Guid) -> Option<usize> {
+        self.entries.iter().position(|e| e.guid == *guid)
+    }
+
+    /// returns the indices of the entry at `index` and of all its descendants
+    pub(crate) fn subtree(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let level = self.entries[index].level;
+        std::iter::once(index).chain(
+            self.entries[index + 1..]
+                .iter()
+                .take_while(move |e| e.level > level)
+                .enumerate()
+                .map(move |(i, _)| index + 1 + i),
+        )
+    }
+
+    /// returns the indices of all ancestors of the entry at `index`
+    pub(crate) fn ancestors(&self, index: usize) -> Vec<usize> {
+        let mut level = self.entries[index].level;
+        let mut ancestors = Vec::new();
+        for i in (0..index).rev() {
+            if self.entries[i].level < level {
+                level = self.entries[i].level;
+                ancestors.push(i);
+            }
+        }
+        ancestors
+    }
+}
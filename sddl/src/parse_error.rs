@@ -0,0 +1,423 @@
+use std::{fmt::Display, ops::Range};
+
+use getset::{CopyGetters, Getters};
+use lalrpop_util::ParseError;
+
+use crate::{parsing::SpannedError, AclType, Error};
+
+/// number of characters which are shown before and after the erroneous part
+/// of the input
+const CONTEXT_CHARS: usize = 40;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum ParseErrorKind {
+    /// the input contains a character which cannot start any token
+    InvalidToken,
+
+    /// the input contains a token which is not allowed at this position
+    UnexpectedToken,
+
+    /// the input ends before the SDDL string is complete
+    UnexpectedEof,
+
+    /// the input contains additional tokens after a complete SDDL string
+    ExtraToken,
+
+    /// the input contains a token which is syntactically valid, but whose
+    /// value is not, e.g. a number which does not fit into 32 bits
+    InvalidValue,
+}
+
+/// The part of an SDDL string or of a binary security descriptor in which an
+/// error was found
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum SddlComponent {
+    Owner,
+    Group,
+    Dacl,
+    Sacl,
+
+    /// the ACE with the zero-based index `index` in the DACL or the SACL
+    Ace {
+        acl_type: AclType,
+        index: usize,
+    },
+}
+
+impl Display for SddlComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Owner => write!(f, "owner"),
+            Self::Group => write!(f, "group"),
+            Self::Dacl => write!(f, "DACL"),
+            Self::Sacl => write!(f, "SACL"),
+            Self::Ace { acl_type, index } => {
+                write!(f, "ACE #{} of the {}", index + 1, acl_name(*acl_type))
+            }
+        }
+    }
+}
+
+pub(crate) fn acl_name(acl_type: AclType) -> &'static str {
+    match acl_type {
+        AclType::DACL => "DACL",
+        AclType::SACL => "SACL",
+    }
+}
+
+/// Describes where and why parsing an SDDL string failed. The [`Display`]
+/// implementation shows the input with a caret under the erroneous part:
+///
+/// ```
+/// use sddl::*;
+/// let Err(Error::ParseError(err)) =
+///     SecurityDescriptor::from_sddl("O:BAD:(A;;FA;;;SY)(X;;FA;;;BA)", None)
+/// else {
+///     panic!("this SDDL string is invalid")
+/// };
+/// assert_eq!(err.span(), &(18..19));
+/// assert_eq!(
+///     err.component(),
+///     Some(SddlComponent::Ace { acl_type: AclType::DACL, index: 1 })
+/// );
+/// assert!(err.to_string().ends_with(
+///     "O:BAD:(A;;FA;;;SY)(X;;FA;;;BA)\n                  ^"
+/// ));
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Getters, CopyGetters)]
+pub struct SddlParseError {
+    #[getset(get = "pub")]
+    input: String,
+
+    /// byte offsets of the erroneous part of the input
+    #[getset(get = "pub")]
+    span: Range<usize>,
+
+    #[getset(get_copy = "pub")]
+    kind: ParseErrorKind,
+
+    /// the token which could not be parsed, if any
+    #[getset(get = "pub")]
+    unexpected: Option<String>,
+
+    /// the tokens which would have been valid at this position
+    #[getset(get = "pub")]
+    expected: Vec<String>,
+
+    #[getset(get_copy = "pub")]
+    component: Option<SddlComponent>,
+
+    /// the reason why the value has been rejected, if the kind of this
+    /// error is [`ParseErrorKind::InvalidValue`]
+    #[getset(get = "pub")]
+    reason: Option<String>,
+}
+
+impl SddlParseError {
+    /// converts an error of the generated parser
+    pub(crate) fn from_lalrpop<T: Display>(
+        input: &str,
+        error: ParseError<usize, T, SpannedError>,
+    ) -> Error {
+        let mut reason = None;
+        let (kind, span, unexpected, expected) = match error {
+            ParseError::InvalidToken { location } => {
+                let c = input[location..].chars().next();
+                let end = location + c.map(char::len_utf8).unwrap_or_default();
+                (
+                    ParseErrorKind::InvalidToken,
+                    location..end,
+                    c.map(String::from),
+                    Vec::new(),
+                )
+            }
+            ParseError::UnrecognizedEof { location, expected } => (
+                ParseErrorKind::UnexpectedEof,
+                location..location,
+                None,
+                expected,
+            ),
+            ParseError::UnrecognizedToken {
+                token: (start, token, end),
+                expected,
+            } => (
+                ParseErrorKind::UnexpectedToken,
+                start..end,
+                Some(token.to_string()),
+                expected,
+            ),
+            ParseError::ExtraToken {
+                token: (start, token, end),
+            } => (
+                ParseErrorKind::ExtraToken,
+                start..end,
+                Some(token.to_string()),
+                Vec::new(),
+            ),
+            ParseError::User {
+                error: (start, error, end),
+            } => {
+                reason = Some(error.to_string());
+                (
+                    ParseErrorKind::InvalidValue,
+                    start..end,
+                    Some(input[start..end].to_owned()),
+                    Vec::new(),
+                )
+            }
+        };
+
+        Error::ParseError(Box::new(Self {
+            input: input.to_owned(),
+            component: component_at(input, span.start),
+            span,
+            kind,
+            unexpected,
+            expected,
+            reason,
+        }))
+    }
+
+    /// returns the line with the part of the input around the error, and
+    /// the line with the caret which points to the error
+    fn diagnostic(&self) -> (String, String) {
+        let mut start = self.span.start.saturating_sub(CONTEXT_CHARS * 4);
+        while !self.input.is_char_boundary(start) {
+            start += 1;
+        }
+        let before: Vec<char> = self.input[start..self.span.start].chars().collect();
+        let skip = before.len().saturating_sub(CONTEXT_CHARS);
+
+        let mut excerpt = String::new();
+        if skip > 0 || start > 0 {
+            excerpt.push_str("...");
+        }
+        excerpt.extend(&before[skip..]);
+        let column = excerpt.chars().count();
+
+        let error_chars = self.input[self.span.clone()].chars().count();
+        let mut after = self.input[self.span.start..].chars();
+        excerpt.extend(after.by_ref().take(error_chars + CONTEXT_CHARS));
+        if after.next().is_some() {
+            excerpt.push_str("...");
+        }
+
+        let caret = format!("{}{}", " ".repeat(column), "^".repeat(error_chars.max(1)));
+        (excerpt, caret)
+    }
+}
+
+impl Display for SddlParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.kind, &self.unexpected) {
+            (ParseErrorKind::InvalidToken, Some(token)) => write!(f, "invalid token '{token}'")?,
+            (ParseErrorKind::ExtraToken, Some(token)) => write!(f, "extra token '{token}'")?,
+            (ParseErrorKind::InvalidValue, Some(token)) => write!(f, "invalid value '{token}'")?,
+            (_, Some(token)) => write!(f, "unexpected token '{token}'")?,
+            (_, None) => write!(f, "unexpected end of input")?,
+        }
+        if let Some(component) = self.component {
+            write!(f, " in {component}")?;
+        }
+        write!(f, " at offset {}", self.span.start)?;
+        if let Some(reason) = &self.reason {
+            write!(f, ": {reason}")?;
+        }
+        if !self.expected.is_empty() {
+            write!(f, ", expected one of {}", self.expected.join(", "))?;
+        }
+        let (excerpt, caret) = self.diagnostic();
+        write!(f, "\n{excerpt}\n{caret}")
+    }
+}
+
+/// finds the component of the SDDL string which contains the byte at
+/// `offset`. Returns `None` if `offset` is not inside of a component, e.g.
+/// if the input is not a security descriptor or an ACL.
+fn component_at(input: &str, offset: usize) -> Option<SddlComponent> {
+    let mut component = None;
+    let mut acl_type = None;
+    let mut ace_index = None;
+    let mut depth = 0usize;
+    let mut in_string = false;
+
+    let mut chars = input.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        if idx >= offset {
+            // an error at the beginning of an ACE or of a component belongs
+            // to this ACE or component
+            match c {
+                '(' if depth == 0 => {
+                    if let Some(acl_type) = acl_type {
+                        let index = ace_index.map_or(0, |i| i + 1);
+                        component = Some(SddlComponent::Ace { acl_type, index });
+                    }
+                }
+                'O' | 'G' | 'D' | 'S'
+                    if depth == 0 && chars.peek().map(|(_, c)| *c) == Some(':') =>
+                {
+                    component = Some(match c {
+                        'O' => SddlComponent::Owner,
+                        'G' => SddlComponent::Group,
+                        'D' => SddlComponent::Dacl,
+                        _ => SddlComponent::Sacl,
+                    });
+                }
+                _ => (),
+            }
+            break;
+        }
+
+        match c {
+            '"' if depth > 0 => in_string = !in_string,
+            _ if in_string => (),
+            '(' => {
+                if depth == 0 {
+                    if let Some(acl_type) = acl_type {
+                        let index = ace_index.map_or(0, |i| i + 1);
+                        ace_index = Some(index);
+                        component = Some(SddlComponent::Ace { acl_type, index });
+                    }
+                }
+                depth += 1;
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    component = acl_type.map(SddlComponent::from);
+                }
+            }
+            'O' | 'G' | 'D' | 'S' if depth == 0 && chars.peek().map(|(_, c)| *c) == Some(':') => {
+                (component, acl_type) = match c {
+                    'O' => (Some(SddlComponent::Owner), None),
+                    'G' => (Some(SddlComponent::Group), None),
+                    'D' => (Some(SddlComponent::Dacl), Some(AclType::DACL)),
+                    _ => (Some(SddlComponent::Sacl), Some(AclType::SACL)),
+                };
+                ace_index = None;
+            }
+            _ => (),
+        }
+    }
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AclType, Error, SddlComponent, SecurityDescriptor};
+
+    use super::ParseErrorKind;
+
+    fn parse_error(sddl: &str) -> Box<super::SddlParseError> {
+        match SecurityDescriptor::from_sddl(sddl, None) {
+            Err(Error::ParseError(err)) => err,
+            Err(other) => panic!("unexpected error for '{sddl}': {other}"),
+            Ok(sd) => panic!("'{sddl}' has been parsed as '{sd}'"),
+        }
+    }
+
+    #[test]
+    fn test_components() {
+        assert_eq!(
+            parse_error("O:XYG:BA").component(),
+            Some(SddlComponent::Owner)
+        );
+        assert_eq!(
+            parse_error("O:BAG:??").component(),
+            Some(SddlComponent::Group)
+        );
+        assert_eq!(
+            parse_error("O:BAD:PXX").component(),
+            Some(SddlComponent::Dacl)
+        );
+        assert_eq!(
+            parse_error("D:(A;;FA;;;SY)S:(AU;FA;GA;;;WD)(AU;FA;GA;;WD)").component(),
+            Some(SddlComponent::Ace {
+                acl_type: AclType::SACL,
+                index: 1
+            })
+        );
+        assert_eq!(
+            parse_error(r#"D:(XA;;FA;;;SY;(@User.x == ")"))(A;;FA;;;QQ)"#).component(),
+            Some(SddlComponent::Ace {
+                acl_type: AclType::DACL,
+                index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_eof() {
+        let err = parse_error("D:(A;;FA;;;SY");
+        assert_eq!(err.kind(), ParseErrorKind::UnexpectedEof);
+        assert_eq!(err.span(), &(13..13));
+        assert!(err.unexpected().is_none());
+        assert!(err.expected().iter().any(|t| t == r#"")""#));
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let aces = "(A;;FA;;;SY)".repeat(10);
+        let err = parse_error(&format!("D:{aces}(A;;FA;;;QQ){aces}"));
+        assert_eq!(
+            err.component(),
+            Some(SddlComponent::Ace {
+                acl_type: AclType::DACL,
+                index: 10
+            })
+        );
+        let message = err.to_string();
+        let lines: Vec<_> = message.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("invalid token 'Q' in ACE #11 of the DACL at offset 131"));
+        assert_eq!(
+            lines[1],
+            "...A;;;SY)(A;;FA;;;SY)(A;;FA;;;SY)(A;;FA;;;QQ)(A;;FA;;;SY)(A;;FA;;;SY)(A;;FA;;;SY)(A..."
+        );
+        assert_eq!(lines[2].find('^'), Some(43));
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        for (sddl, span, component) in [
+            (
+                "D:(A;;FA;;;DA)",
+                11..13,
+                SddlComponent::Ace {
+                    acl_type: AclType::DACL,
+                    index: 0,
+                },
+            ),
+            (
+                "D:(A;;FA;;;WD)(A;;0xFFFFFFFFFF;;;WD)",
+                18..30,
+                SddlComponent::Ace {
+                    acl_type: AclType::DACL,
+                    index: 1,
+                },
+            ),
+            (
+                "D:(XA;;FA;;;WD;(@User.x == #abc))",
+                27..31,
+                SddlComponent::Ace {
+                    acl_type: AclType::DACL,
+                    index: 0,
+                },
+            ),
+            ("O:BAG:SYO:WD", 8..12, SddlComponent::Owner),
+        ] {
+            let err = parse_error(sddl);
+            assert_eq!(err.kind(), ParseErrorKind::InvalidValue, "{sddl}");
+            assert_eq!(err.span(), &span, "{sddl}");
+            assert_eq!(err.component(), Some(component), "{sddl}");
+            assert_eq!(err.unexpected().as_deref(), Some(&sddl[span]));
+            assert!(err.reason().is_some(), "{sddl}");
+        }
+
+        let err = parse_error("D:(A;;FA;;;DA)");
+        assert!(err.to_string().starts_with(
+            "invalid value 'DA' in ACE #1 of the DACL at offset 11: \
+             this SID alias cannot be parsed without a domain RID\n"
+        ));
+    }
+}
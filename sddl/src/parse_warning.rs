@@ -0,0 +1,157 @@
+use std::fmt::Display;
+
+use crate::{parse_error::acl_name, AclRevision, AclType, SddlComponent};
+
+/// A problem which has been found and skipped by
+/// [`crate::SecurityDescriptor::from_bytes_lenient`]. All offsets are byte
+/// offsets into the data which has been passed to the parser.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum ParseWarning {
+    /// the security descriptor does not start at the beginning of the data,
+    /// but after `length` bytes
+    SkippedPrefix { length: usize },
+
+    /// the security descriptor has an unknown revision
+    InvalidRevision { revision: u8 },
+
+    /// the offset of a component points outside of the data, so that this
+    /// component has been ignored
+    OffsetOutOfBounds {
+        component: SddlComponent,
+        offset: u32,
+    },
+
+    /// the offset of a component points into the header of the security
+    /// descriptor or into another component
+    OverlappingComponents {
+        component: SddlComponent,
+        other: Option<SddlComponent>,
+    },
+
+    /// a component could not be parsed and has been ignored
+    InvalidComponent {
+        component: SddlComponent,
+        offset: usize,
+        reason: String,
+    },
+
+    /// an ACL has an unknown revision
+    InvalidAclRevision { acl_type: AclType, revision: u8 },
+
+    /// an ACL contains ACEs which are not allowed in its revision. The
+    /// revision has been kept.
+    InconsistentAclRevision {
+        acl_type: AclType,
+        revision: AclRevision,
+        required: AclRevision,
+    },
+
+    /// the AclSize field of an ACL is smaller than the ACL header or larger
+    /// than the remaining data, so that all remaining data has been used
+    InvalidAclSize {
+        acl_type: AclType,
+        acl_size: u16,
+        available: usize,
+    },
+
+    /// an ACL ends before all of its ACEs have been read
+    MissingAces {
+        acl_type: AclType,
+        expected: u16,
+        found: u16,
+    },
+
+    /// an ACE could not be parsed and has been skipped
+    MalformedAce {
+        component: SddlComponent,
+        offset: usize,
+        reason: String,
+    },
+
+    /// an ACE has a type which is not supported, and has been kept as
+    /// [`crate::Ace::Unknown`]
+    UnknownAceType {
+        component: SddlComponent,
+        offset: usize,
+        ace_type: u8,
+    },
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SkippedPrefix { length } => {
+                write!(f, "skipped {length} bytes before the security descriptor")
+            }
+            Self::InvalidRevision { revision } => {
+                write!(f, "invalid revision {revision} of the security descriptor")
+            }
+            Self::OffsetOutOfBounds { component, offset } => {
+                write!(f, "the offset {offset} of the {component} is out of bounds")
+            }
+            Self::OverlappingComponents {
+                component,
+                other: Some(other),
+            } => write!(f, "the {component} overlaps with the {other}"),
+            Self::OverlappingComponents {
+                component,
+                other: None,
+            } => write!(f, "the {component} overlaps with the header"),
+            Self::InvalidComponent {
+                component,
+                offset,
+                reason,
+            } => write!(f, "invalid {component} at offset {offset}: {reason}"),
+            Self::InvalidAclRevision { acl_type, revision } => {
+                write!(
+                    f,
+                    "invalid revision {revision} of the {}",
+                    acl_name(*acl_type)
+                )
+            }
+            Self::InconsistentAclRevision {
+                acl_type,
+                revision,
+                required,
+            } => write!(
+                f,
+                "the {} has the revision {revision:?}, but its ACEs require {required:?}",
+                acl_name(*acl_type)
+            ),
+            Self::InvalidAclSize {
+                acl_type,
+                acl_size,
+                available,
+            } => write!(
+                f,
+                "invalid size {acl_size} of the {}, using the remaining {available} bytes",
+                acl_name(*acl_type)
+            ),
+            Self::MissingAces {
+                acl_type,
+                expected,
+                found,
+            } => write!(
+                f,
+                "the {} should contain {expected} ACEs, but only {found} have been found",
+                acl_name(*acl_type)
+            ),
+            Self::MalformedAce {
+                component,
+                offset,
+                reason,
+            } => write!(
+                f,
+                "skipped malformed {component} at offset {offset}: {reason}"
+            ),
+            Self::UnknownAceType {
+                component,
+                offset,
+                ace_type,
+            } => write!(
+                f,
+                "{component} at offset {offset} has the unknown type {ace_type:#04x}"
+            ),
+        }
+    }
+}
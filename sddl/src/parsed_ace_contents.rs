@@ -1,4 +1,7 @@
-use crate::{AccessMask, Ace, AceHeaderFlags, AceType, Guid, Sid};
+use crate::{
+    AccessMask, Ace, AceHeaderFlags, AceType, ClaimAttribute, ConditionalExpression, Error, Guid,
+    Sid,
+};
 
 pub(crate) struct ParsedAceContents {
     pub flags: AceHeaderFlags,
@@ -6,6 +9,7 @@ pub(crate) struct ParsedAceContents {
     pub sid: Sid,
     pub object_type: Option<Guid>,
     pub inherited_object_type: Option<Guid>,
+    pub application_data: Vec<u8>,
 }
 
 impl ParsedAceContents {
@@ -16,6 +20,7 @@ impl ParsedAceContents {
             sid,
             object_type: None,
             inherited_object_type: None,
+            application_data: Vec::new(),
         }
     }
 
@@ -25,18 +30,37 @@ impl ParsedAceContents {
         self
     }
 
-    pub(crate) fn into_ace(self, ace_type: AceType) -> Ace {
+    pub(crate) fn with_condition(
+        mut self,
+        condition: Option<ConditionalExpression>,
+    ) -> Result<Self, Error> {
+        self.application_data = match condition {
+            Some(condition) => condition.to_bytes()?,
+            None => Vec::new(),
+        };
+        Ok(self)
+    }
+
+    pub(crate) fn with_resource_attribute(mut self, attribute: ClaimAttribute) -> Self {
+        self.application_data = attribute.to_bytes();
+        self
+    }
+
+    /// creates an ACE of type `ace_type`, or returns an error if this ACE
+    /// type cannot be created from SDDL
+    pub(crate) fn into_ace(self, ace_type: AceType) -> Result<Ace, Error> {
         let flags = self.flags;
         let mask = self.mask;
         let sid = self.sid;
         let object_type = self.object_type;
         let inherited_object_type = self.inherited_object_type;
-        match ace_type {
+        let application_data = self.application_data;
+        Ok(match ace_type {
             AceType::ACCESS_ALLOWED_ACE_TYPE => Ace::access_allowed(flags, mask, sid),
             AceType::ACCESS_DENIED_ACE_TYPE => Ace::access_denied(flags, mask, sid),
             AceType::SYSTEM_AUDIT_ACE_TYPE => Ace::system_audit(flags, mask, sid),
-            AceType::SYSTEM_ALARM_ACE_TYPE => unimplemented!(),
-            AceType::ACCESS_ALLOWED_COMPOUND_ACE_TYPE => unimplemented!(),
+            AceType::SYSTEM_ALARM_ACE_TYPE => Ace::system_alarm(flags, mask, sid),
+            AceType::ACCESS_ALLOWED_COMPOUND_ACE_TYPE => return Err(unsupported(ace_type)),
             AceType::ACCESS_ALLOWED_OBJECT_ACE_TYPE => {
                 Ace::access_allowed_object(flags, mask, object_type, inherited_object_type, sid)
             }
@@ -50,23 +74,69 @@ impl ParsedAceContents {
                 inherited_object_type,
                 sid,
                 vec![],
-            ),
-            AceType::SYSTEM_ALARM_OBJECT_ACE_TYPE => unimplemented!(),
-            AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE => unimplemented!(),
-            AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE => unimplemented!(),
-            AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => unimplemented!(),
-            AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE => unimplemented!(),
-            AceType::SYSTEM_AUDIT_CALLBACK_ACE_TYPE => unimplemented!(),
-            AceType::SYSTEM_ALARM_CALLBACK_ACE_TYPE => unimplemented!(),
-            AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE => unimplemented!(),
-            AceType::SYSTEM_ALARM_CALLBACK_OBJECT_ACE_TYPE => unimplemented!(),
+            )?,
+            AceType::SYSTEM_ALARM_OBJECT_ACE_TYPE => Ace::system_alarm_object(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                vec![],
+            )?,
+            AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE => {
+                Ace::access_allowed_callback(flags, mask, sid, application_data)?
+            }
+            AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE => {
+                Ace::access_denied_callback(flags, mask, sid, application_data)?
+            }
+            AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => Ace::access_allowed_object_callback(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                application_data,
+            )?,
+            AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE => Ace::access_denied_object_callback(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                application_data,
+            )?,
+            AceType::SYSTEM_AUDIT_CALLBACK_ACE_TYPE => {
+                Ace::system_audit_callback(flags, mask, sid, application_data)?
+            }
+            AceType::SYSTEM_ALARM_CALLBACK_ACE_TYPE => return Err(unsupported(ace_type)),
+            AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE => Ace::system_audit_callback_object(
+                flags,
+                mask,
+                object_type,
+                inherited_object_type,
+                sid,
+                application_data,
+            )?,
+            AceType::SYSTEM_ALARM_CALLBACK_OBJECT_ACE_TYPE => return Err(unsupported(ace_type)),
             AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE => {
                 Ace::system_mandatory_label(flags, mask, sid)
             }
-            AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => unimplemented!(),
+            AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => {
+                Ace::system_resource_attribute(flags, mask, sid, application_data)?
+            }
             AceType::SYSTEM_SCOPED_POLICY_ID_ACE_TYPE => {
                 Ace::system_scoped_policy_id(flags, mask, sid)
             }
-        }
+            AceType::SYSTEM_PROCESS_TRUST_LABEL_ACE_TYPE => {
+                Ace::system_process_trust_label(flags, mask, sid)
+            }
+            AceType::SYSTEM_ACCESS_FILTER_ACE_TYPE => {
+                Ace::system_access_filter(flags, mask, sid, application_data)?
+            }
+        })
     }
 }
+
+fn unsupported(ace_type: AceType) -> Error {
+    Error::UnsupportedAceType(ace_type)
+}
@@ -1,16 +1,125 @@
-use lalrpop_util::{lexer::Token, ParseError};
+use lalrpop_util::ParseError;
 
-use crate::{Error, Sid};
+use crate::{
+    Acl, ClaimAttribute, ClaimAttributeFlags, ClaimValue, ClaimValueType, ControlFlags, Error,
+    SecurityDescriptor, Sid,
+};
 
-pub(crate) trait NewDomainSid<L, T> {
-    fn new_domain_sid(&self, rid: u32) -> Result<Sid, ParseError<usize, T, Error>>;
+/// an error which has been raised by a semantic action of the grammar,
+/// together with the start and the end offset of the rejected input
+pub(crate) type SpannedError = (usize, Error, usize);
+
+pub(crate) trait WithSpan<V> {
+    /// converts an error of a semantic action into a parse error which
+    /// points to the input between `start` and `end`
+    fn with_span<T>(
+        self,
+        start: usize,
+        end: usize,
+    ) -> Result<V, ParseError<usize, T, SpannedError>>;
+}
+
+impl<V> WithSpan<V> for Result<V, Error> {
+    fn with_span<T>(
+        self,
+        start: usize,
+        end: usize,
+    ) -> Result<V, ParseError<usize, T, SpannedError>> {
+        self.map_err(|error| ParseError::User {
+            error: (start, error, end),
+        })
+    }
+}
+
+pub(crate) trait NewDomainSid {
+    fn new_domain_sid(&self, rid: u32) -> Result<Sid, Error>;
 }
 
-impl<'input> NewDomainSid<usize, Token<'input>> for Option<&[u32]> {
-    fn new_domain_sid(&self, rid: u32) -> Result<Sid, ParseError<usize, Token<'input>, Error>> {
+impl NewDomainSid for Option<&[u32]> {
+    fn new_domain_sid(&self, rid: u32) -> Result<Sid, Error> {
         self.map(|domain| crate::Sid::new_with_domain(rid, domain))
-            .ok_or(ParseError::User {
-                error: crate::Error::MissingDomainInformation,
+            .ok_or(crate::Error::MissingDomainInformation)
+    }
+}
+
+/// parses the digits of a number in an SDDL string, which must fit into 32
+/// bits
+pub(crate) fn parse_number(literal: &str, digits: &str, radix: u32) -> Result<u32, Error> {
+    u32::from_str_radix(digits, radix).map_err(|why| Error::IllegalNumber(literal.to_owned(), why))
+}
+
+/// creates a resource attribute from the SDDL representation of its values
+pub(crate) fn claim_attribute(
+    name: String,
+    value_type: ClaimValueType,
+    flags: ClaimAttributeFlags,
+    values: Vec<ClaimValue>,
+) -> Result<ClaimAttribute, Error> {
+    ClaimAttribute::new(name, value_type, flags, values)
+}
+
+/// rejects an ACE which has been written as octet string, because it has no
+/// SDDL representation
+pub(crate) fn ace_without_sddl() -> Result<crate::Ace, Error> {
+    Err(Error::NoSddlRepresentation(
+        "the ACE has been written as octet string".to_owned(),
+    ))
+}
+
+pub(crate) fn parse_octet_string(hex: &str) -> Result<Vec<u8>, Error> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(Error::IllegalConditionalExpression(format!(
+            "octet string '#{hex}' has an odd number of digits"
+        )));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| {
+            u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| {
+                Error::IllegalConditionalExpression(format!("illegal octet string '#{hex}'"))
             })
+        })
+        .collect()
+}
+
+/// a single component of an SDDL string
+pub(crate) enum SecurityDescriptorComponent {
+    Owner(Sid),
+    Group(Sid),
+    Dacl(Acl),
+    Sacl(Acl),
+
+    /// `D:NO_ACCESS_CONTROL`, with the flags which precede it
+    NullDacl(ControlFlags),
+}
+
+/// creates a security descriptor from its components, each of which may
+/// appear at most once. Every component is given with its start and end
+/// offset, so that a duplicate component can be pointed out.
+pub(crate) fn security_descriptor_from<T>(
+    components: Vec<(usize, SecurityDescriptorComponent, usize)>,
+) -> Result<SecurityDescriptor, ParseError<usize, T, SpannedError>> {
+    fn set<T>(slot: &mut Option<T>, value: T, name: &'static str) -> Result<(), Error> {
+        match slot.replace(value) {
+            Some(_) => Err(Error::DuplicateComponent(name)),
+            None => Ok(()),
+        }
+    }
+
+    // a NULL DACL is stored as `Err` with its flags
+    let (mut owner, mut group, mut dacl, mut sacl) = (None, None, None, None);
+    for (start, component, end) in components {
+        match component {
+            SecurityDescriptorComponent::Owner(sid) => set(&mut owner, sid, "owner"),
+            SecurityDescriptorComponent::Group(sid) => set(&mut group, sid, "group"),
+            SecurityDescriptorComponent::Dacl(acl) => set(&mut dacl, Ok(acl), "DACL"),
+            SecurityDescriptorComponent::NullDacl(flags) => set(&mut dacl, Err(flags), "DACL"),
+            SecurityDescriptorComponent::Sacl(acl) => set(&mut sacl, acl, "SACL"),
+        }
+        .with_span(start, end)?;
     }
+    Ok(match dacl {
+        Some(Err(flags)) => SecurityDescriptor::new(owner, group, None, sacl).with_null_dacl(flags),
+        dacl => SecurityDescriptor::new(owner, group, dacl.and_then(Result::ok), sacl),
+    })
 }
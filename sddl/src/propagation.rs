@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+
+use crate::{
+    inheritance::ChildObject, Ace, AceHeaderFlags, Acl, AclType, ControlFlags, GenericMapping,
+    Guid, SecurityDescriptor,
+};
+
+/// The syntax of the paths in a [`SecurityDescriptorTree`], which determines
+/// how the parent of an object is found
+#[derive(Eq, PartialEq, Debug, Default, Clone, Copy)]
+pub enum PathStyle {
+    /// paths of files or registry keys, like `C:\Users\Public`, which are
+    /// separated by `\` or `/`
+    #[default]
+    Filesystem,
+
+    /// distinguished names of Active Directory objects, like
+    /// `CN=Users,DC=example,DC=com`
+    DistinguishedName,
+}
+
+impl PathStyle {
+    /// returns the path of the parent of `path`, or `None` if `path` has no
+    /// parent
+    ///
+    /// ```
+    /// use sddl::*;
+    /// assert_eq!(PathStyle::Filesystem.parent(r"C:\Users\Public"), Some(r"C:\Users"));
+    /// assert_eq!(PathStyle::Filesystem.parent(r"C:\Users"), Some(r"C:\"));
+    /// assert_eq!(PathStyle::Filesystem.parent(r"C:\"), None);
+    /// assert_eq!(
+    ///     PathStyle::DistinguishedName.parent(r"CN=Doe\, John,CN=Users,DC=example,DC=com"),
+    ///     Some("CN=Users,DC=example,DC=com")
+    /// );
+    /// ```
+    pub fn parent<'p>(&self, path: &'p str) -> Option<&'p str> {
+        match self {
+            Self::Filesystem => {
+                let path = path.trim_end_matches(['\\', '/']);
+                let pos = path.rfind(['\\', '/'])?;
+                let parent = &path[..pos];
+                if parent.is_empty() || parent.ends_with(':') {
+                    // keep the separator of root directories
+                    Some(&path[..=pos])
+                } else {
+                    Some(parent)
+                }
+            }
+            Self::DistinguishedName => {
+                let mut escaped = false;
+                for (pos, c) in path.char_indices() {
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        ',' if !escaped => return Some(path[pos + 1..].trim_start()),
+                        _ => escaped = false,
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// returns the key under which `path` is stored. Both Windows paths and
+    /// distinguished names are case insensitive.
+    fn key(&self, path: &str) -> String {
+        match self {
+            Self::Filesystem => path.trim_end_matches(['\\', '/']).to_lowercase(),
+            Self::DistinguishedName => path.trim().to_lowercase(),
+        }
+    }
+}
+
+/// A securable object in a [`SecurityDescriptorTree`]
+#[derive(Getters, CopyGetters)]
+pub struct SecuredObject {
+    #[getset(get = "pub")]
+    path: String,
+
+    #[getset(get = "pub")]
+    security_descriptor: SecurityDescriptor,
+
+    /// `true` for directories, registry keys and AD objects, which can have
+    /// children
+    #[getset(get_copy = "pub")]
+    is_container: bool,
+
+    /// the `schemaIDGUID` of the class of an AD object, which is matched
+    /// against the inherited object type of object ACEs
+    #[getset(get_copy = "pub")]
+    object_type: Option<Guid>,
+}
+
+impl SecuredObject {
+    pub fn new(
+        path: impl Into<String>,
+        security_descriptor: SecurityDescriptor,
+        is_container: bool,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            security_descriptor,
+            is_container,
+            object_type: None,
+        }
+    }
+
+    pub fn with_object_type(mut self, object_type: Guid) -> Self {
+        self.object_type = Some(object_type);
+        self
+    }
+}
+
+/// An ACL whose inherited ACEs differ from the ACEs which its object should
+/// inherit from its parent
+#[derive(Debug, Clone, Getters, CopyGetters, Serialize)]
+pub struct InheritanceDeviation {
+    #[getset(get = "pub")]
+    path: String,
+
+    #[getset(get_copy = "pub")]
+    acl_type: AclType,
+
+    /// ACEs which are marked as `INHERITED_ACE`, but which are not inherited
+    /// from the parent. Such ACEs have been added or modified manually,
+    /// possibly to hide them among the inherited ACEs.
+    #[getset(get = "pub")]
+    unexpected: Vec<Ace>,
+
+    /// ACEs which should have been inherited from the parent, but which are
+    /// missing
+    #[getset(get = "pub")]
+    missing: Vec<Ace>,
+}
+
+/// A hierarchy of securable objects, such as a directory tree or a dump of
+/// an Active Directory, which is used to verify the inherited ACEs of every
+/// object against the ACEs of its parent.
+///
+/// Only ACLs which are auto-inherited (`AI`) are checked against their
+/// parent, because Windows does not track the inherited ACEs of other ACLs.
+/// Protected ACLs (`P`) must not contain any inherited ACEs. Every object is
+/// compared to the actual security descriptor of its parent, so that a
+/// deviation is reported at the object where it has been introduced.
+///
+/// ```
+/// use sddl::*;
+/// let sd = |sddl| SecurityDescriptor::from_sddl(sddl, None).unwrap();
+/// let tree = SecurityDescriptorTree::new(PathStyle::Filesystem)
+///     .with_object(SecuredObject::new(r"C:\Data", sd("O:BAD:AI(A;OICI;FA;;;BA)"), true))
+///     .with_object(SecuredObject::new(
+///         r"C:\Data\report.txt",
+///         sd("O:BAD:AI(A;ID;FA;;;BA)(A;ID;FA;;;WD)"),
+///         false,
+///     ));
+///
+/// let deviations = tree.check_inheritance(&GenericMapping::FILE);
+/// assert_eq!(deviations.len(), 1);
+/// assert_eq!(deviations[0].path(), r"C:\Data\report.txt");
+/// assert_eq!(deviations[0].unexpected()[0].to_string(), "A;ID;FA;;;WD");
+/// assert!(deviations[0].missing().is_empty());
+/// ```
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct SecurityDescriptorTree {
+    style: PathStyle,
+
+    objects: Vec<SecuredObject>,
+
+    #[getset(skip)]
+    index: HashMap<String, usize>,
+}
+
+impl SecurityDescriptorTree {
+    pub fn new(style: PathStyle) -> Self {
+        Self {
+            style,
+            objects: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// adds an object to the tree. An object with the same path is replaced.
+    pub fn with_object(mut self, object: SecuredObject) -> Self {
+        self.insert(object);
+        self
+    }
+
+    /// adds an object to the tree. An object with the same path is replaced.
+    pub fn insert(&mut self, object: SecuredObject) {
+        let key = self.style.key(object.path());
+        match self.index.get(&key) {
+            Some(&idx) => self.objects[idx] = object,
+            None => {
+                self.index.insert(key, self.objects.len());
+                self.objects.push(object);
+            }
+        }
+    }
+
+    /// returns the object with the path `path`
+    pub fn get(&self, path: &str) -> Option<&SecuredObject> {
+        self.index
+            .get(&self.style.key(path))
+            .map(|&idx| &self.objects[idx])
+    }
+
+    /// returns the parent of `object`, if it is part of this tree
+    pub fn parent(&self, object: &SecuredObject) -> Option<&SecuredObject> {
+        self.style
+            .parent(object.path())
+            .and_then(|path| self.get(path))
+    }
+
+    /// returns the ACEs which `object` should inherit from its parent into
+    /// its DACL or SACL, or `None` if this cannot be determined because the
+    /// ACL is not auto-inherited, because the parent is unknown or because an
+    /// inherited ACE would be too large. If the parent has no such ACL, the
+    /// object should not inherit any ACEs.
+    pub fn expected_inherited_aces(
+        &self,
+        object: &SecuredObject,
+        acl_type: AclType,
+        mapping: &GenericMapping,
+    ) -> Option<Vec<Ace>> {
+        let sd = object.security_descriptor();
+        let (protected, auto_inherited) = match acl_type {
+            AclType::DACL => (
+                ControlFlags::DiscretionaryAclProtected,
+                ControlFlags::DiscretionaryAclAutoInherited,
+            ),
+            AclType::SACL => (
+                ControlFlags::SystemAclProtected,
+                ControlFlags::SystemAclAutoInherited,
+            ),
+        };
+        if sd.flags().contains(protected) {
+            return Some(Vec::new());
+        }
+        if !sd.flags().contains(auto_inherited) {
+            return None;
+        }
+
+        let parent = self.parent(object)?;
+        let child = ChildObject {
+            owner: sd.owner().as_ref(),
+            group: sd.group().as_ref(),
+            is_container: object.is_container(),
+            object_type: object.object_type(),
+            mapping,
+        };
+        match acl_of(parent.security_descriptor(), acl_type) {
+            Some(acl) => child.inherited_aces(acl).ok(),
+            None => Some(Vec::new()),
+        }
+    }
+
+    /// compares the inherited ACEs of every object with the ACEs which it
+    /// should inherit from its parent
+    pub fn check_inheritance(&self, mapping: &GenericMapping) -> Vec<InheritanceDeviation> {
+        let mut deviations = Vec::new();
+        for object in &self.objects {
+            for acl_type in [AclType::DACL, AclType::SACL] {
+                if let Some(deviation) = self.check_acl(object, acl_type, mapping) {
+                    deviations.push(deviation);
+                }
+            }
+        }
+        deviations
+    }
+
+    fn check_acl(
+        &self,
+        object: &SecuredObject,
+        acl_type: AclType,
+        mapping: &GenericMapping,
+    ) -> Option<InheritanceDeviation> {
+        let acl = acl_of(object.security_descriptor(), acl_type)?;
+        let mut missing = self.expected_inherited_aces(object, acl_type, mapping)?;
+
+        // ACEs are compared by their SDDL representation, which ignores the
+        // padding of binary ACEs. ACEs without one must be equal.
+        let mut unexpected = Vec::new();
+        for ace in acl
+            .ace_list()
+            .iter()
+            .filter(|ace| ace.header().ace_flags().contains(AceHeaderFlags::INHERITED_ACE))
+        {
+            let sddl = ace.to_sddl();
+            let matches = |expected: &Ace| match (&sddl, expected.to_sddl()) {
+                (Ok(sddl), Ok(expected)) => *sddl == expected,
+                _ => expected == ace,
+            };
+            match missing.iter().position(matches) {
+                Some(pos) => {
+                    missing.remove(pos);
+                }
+                None => unexpected.push(ace.clone()),
+            }
+        }
+
+        if unexpected.is_empty() && missing.is_empty() {
+            None
+        } else {
+            Some(InheritanceDeviation {
+                path: object.path().clone(),
+                acl_type,
+                unexpected,
+                missing,
+            })
+        }
+    }
+}
+
+fn acl_of(sd: &SecurityDescriptor, acl_type: AclType) -> Option<&Acl> {
+    match acl_type {
+        AclType::DACL => sd.dacl().as_ref(),
+        AclType::SACL => sd.sacl().as_ref(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(objects: &[(&str, &str, bool)]) -> SecurityDescriptorTree {
+        let mut tree = SecurityDescriptorTree::new(PathStyle::Filesystem);
+        for (path, sddl, is_container) in objects {
+            let sd = SecurityDescriptor::from_sddl(sddl, None).unwrap();
+            tree.insert(SecuredObject::new(*path, sd, *is_container));
+        }
+        tree
+    }
+
+    #[test]
+    fn test_filesystem_parent() {
+        let style = PathStyle::Filesystem;
+        assert_eq!(style.parent("/srv/data/"), Some("/srv"));
+        assert_eq!(style.parent("/srv"), Some("/"));
+        assert_eq!(style.parent("/"), None);
+        assert_eq!(style.parent("HKLM\\SOFTWARE"), Some("HKLM"));
+        assert_eq!(style.key("C:\\Data\\"), style.key("c:\\data"));
+    }
+
+    #[test]
+    fn test_consistent_tree() {
+        let tree = tree(&[
+            ("C:\\", "O:SYD:PAI(A;OICI;FA;;;SY)(A;OICIIO;GA;;;CO)", true),
+            (
+                "C:\\Data",
+                "O:BAD:AI(A;;FR;;;WD)(A;OICIID;FA;;;SY)(A;ID;FA;;;BA)(A;OICIIOID;GA;;;CO)",
+                true,
+            ),
+            ("C:\\Data\\a.txt", "O:BUD:AI(A;ID;FA;;;SY)(A;ID;FA;;;BU)", false),
+            ("C:\\Data\\b.txt", "O:BUD:P(A;;FA;;;BU)", false),
+        ]);
+        assert!(tree.check_inheritance(&GenericMapping::FILE).is_empty());
+    }
+
+    #[test]
+    fn test_missing_and_protected() {
+        let tree = tree(&[
+            ("C:\\Data", "O:BAD:AI(A;OICI;FA;;;SY)(A;OICI;FR;;;BU)", true),
+            ("C:\\Data\\a.txt", "O:BUD:AI(A;ID;FA;;;SY)", false),
+            ("C:\\Data\\b.txt", "O:BUD:PAI(A;ID;FA;;;SY)", false),
+            ("C:\\Data\\c.txt", "O:BUD:(A;ID;FA;;;WD)", false),
+        ]);
+        let deviations = tree.check_inheritance(&GenericMapping::FILE);
+        assert_eq!(deviations.len(), 2);
+
+        assert_eq!(deviations[0].path(), "C:\\Data\\a.txt");
+        assert!(deviations[0].unexpected().is_empty());
+        assert_eq!(deviations[0].missing()[0].to_string(), "A;ID;FR;;;BU");
+
+        assert_eq!(deviations[1].path(), "C:\\Data\\b.txt");
+        assert_eq!(deviations[1].unexpected()[0].to_string(), "A;ID;FA;;;SY");
+    }
+
+    #[test]
+    fn test_parent_without_acl() {
+        let tree = tree(&[
+            ("C:\\Data", "O:BAD:AI(A;OICI;FA;;;SY)", true),
+            ("C:\\Data\\a.txt", "O:BUD:AI(A;ID;FA;;;SY)S:AI(AU;IDSA;FA;;;WD)", false),
+        ]);
+        let deviations = tree.check_inheritance(&GenericMapping::FILE);
+        assert_eq!(deviations.len(), 1);
+        assert_eq!(deviations[0].path(), "C:\\Data\\a.txt");
+        assert_eq!(deviations[0].acl_type(), AclType::SACL);
+        assert!(deviations[0].missing().is_empty());
+        assert_eq!(deviations[0].unexpected()[0].to_string(), "AU;IDSA;FA;;;WD");
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::Display;
+
+use getset::CopyGetters;
+use serde::{Deserialize, Serialize};
+use strum::FromRepr;
+
+use crate::{constants::PROCESS_TRUST_AUTHORITY, AccessMask, Sid};
+
+/// Protection type of a process, which is encoded as the first RID of a SID
+/// in the `S-1-19` authority
+#[repr(u32)]
+#[derive(
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    FromRepr,
+)]
+pub enum ProtectionType {
+    #[default]
+    None = 0,
+
+    /// protected process light (PPL)
+    ProtectedLight = 512,
+
+    /// protected process (PP)
+    Protected = 1024,
+}
+
+impl ProtectionType {
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::ProtectedLight => "PPL",
+            Self::Protected => "PP",
+        }
+    }
+}
+
+/// Signer of a protected process, which is encoded as the second RID of a
+/// SID in the `S-1-19` authority
+#[repr(u32)]
+#[derive(
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    FromRepr,
+    strum::Display,
+)]
+pub enum ProtectionSigner {
+    #[default]
+    None = 0,
+    Authenticode = 1024,
+    AntiMalware = 1536,
+    App = 2048,
+    Windows = 4096,
+    WinTcb = 8192,
+}
+
+/// Protection level of a process or of a process trust label, which is
+/// encoded as a SID of the form `S-1-19-<type>-<signer>`
+///
+/// ```
+/// use sddl::*;
+/// let sid = Sid::try_from("S-1-19-512-4096").unwrap();
+/// let level = ProtectionLevel::from_sid(&sid).unwrap();
+/// assert_eq!(level.protection_type(), ProtectionType::ProtectedLight);
+/// assert_eq!(level.signer(), ProtectionSigner::Windows);
+/// assert_eq!(level.to_string(), "PPL Windows");
+/// ```
+#[derive(Eq, PartialEq, Debug, Default, Clone, Copy, Hash, CopyGetters, Serialize, Deserialize)]
+#[getset(get_copy = "pub")]
+pub struct ProtectionLevel {
+    protection_type: ProtectionType,
+    signer: ProtectionSigner,
+}
+
+impl ProtectionLevel {
+    pub fn new(protection_type: ProtectionType, signer: ProtectionSigner) -> Self {
+        Self {
+            protection_type,
+            signer,
+        }
+    }
+
+    /// returns the protection level which is represented by `sid`, or `None`
+    /// if `sid` is not a process trust SID
+    pub fn from_sid(sid: &Sid) -> Option<Self> {
+        match &sid.sub_authority()[..] {
+            [protection_type, signer] if *sid.identifier_authority() == PROCESS_TRUST_AUTHORITY => {
+                Some(Self::new(
+                    ProtectionType::from_repr(*protection_type)?,
+                    ProtectionSigner::from_repr(*signer)?,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn sid(&self) -> Sid {
+        Sid::new(
+            PROCESS_TRUST_AUTHORITY,
+            &[self.protection_type as u32, self.signer as u32],
+        )
+    }
+
+    /// returns `true` if a process with this protection level is not
+    /// restricted by a trust label of the protection level `other`, which is
+    /// the case if both its type and its signer are at least as high
+    pub fn dominates(&self, other: &Self) -> bool {
+        self.protection_type >= other.protection_type && self.signer >= other.signer
+    }
+}
+
+impl Display for ProtectionLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.protection_type {
+            ProtectionType::None => write!(f, "{}", self.protection_type.short_name()),
+            _ => write!(f, "{} {}", self.protection_type.short_name(), self.signer),
+        }
+    }
+}
+
+/// The process trust label of an object
+#[derive(Eq, PartialEq, Debug, Clone, Copy, CopyGetters, Serialize)]
+#[getset(get_copy = "pub")]
+pub struct ProcessTrustLabel {
+    level: ProtectionLevel,
+
+    /// the access rights which are granted to processes which do not
+    /// dominate `level`
+    mask: AccessMask,
+}
+
+impl ProcessTrustLabel {
+    pub fn new(level: ProtectionLevel, mask: AccessMask) -> Self {
+        Self { level, mask }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sid_roundtrip() {
+        for (sid, name) in [
+            ("S-1-19-512-8192", "PPL WinTcb"),
+            ("S-1-19-512-1536", "PPL AntiMalware"),
+            ("S-1-19-1024-4096", "PP Windows"),
+            ("S-1-19-1024-1024", "PP Authenticode"),
+            ("S-1-19-0-0", "None"),
+        ] {
+            let level = ProtectionLevel::from_sid(&Sid::try_from(sid).unwrap()).unwrap();
+            assert_eq!(level.to_string(), name);
+            assert_eq!(level.sid().to_string(), sid);
+        }
+    }
+
+    #[test]
+    fn test_invalid_sids() {
+        for sid in [
+            "S-1-19-512",
+            "S-1-19-512-1",
+            "S-1-19-3-4096",
+            "S-1-16-512-4096",
+        ] {
+            assert!(ProtectionLevel::from_sid(&Sid::try_from(sid).unwrap()).is_none());
+        }
+    }
+
+    #[test]
+    fn test_dominates() {
+        let ppl_windows =
+            ProtectionLevel::new(ProtectionType::ProtectedLight, ProtectionSigner::Windows);
+        let ppl_tcb =
+            ProtectionLevel::new(ProtectionType::ProtectedLight, ProtectionSigner::WinTcb);
+        let pp_app = ProtectionLevel::new(ProtectionType::Protected, ProtectionSigner::App);
+        assert!(ppl_tcb.dominates(&ppl_windows));
+        assert!(!ppl_windows.dominates(&ppl_tcb));
+        assert!(!pp_app.dominates(&ppl_windows));
+        assert!(ppl_windows.dominates(&ProtectionLevel::default()));
+    }
+}
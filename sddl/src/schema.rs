@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+
+use getset::Getters;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+use crate::Guid;
+
+/// The kind of schema object which is identified by a GUID in an object ACE
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum SchemaObjectKind {
+    /// a `classSchema` object, identified by its `schemaIDGUID`
+    Class,
+
+    /// an `attributeSchema` object, identified by its `schemaIDGUID`
+    Attribute,
+
+    /// a property set, identified by the `rightsGuid` of its
+    /// `controlAccessRight` object
+    PropertySet,
+
+    /// a validated write, identified by the `rightsGuid` of its
+    /// `controlAccessRight` object
+    ValidatedWrite,
+
+    /// an extended right, identified by the `rightsGuid` of its
+    /// `controlAccessRight` object
+    ExtendedRight,
+}
+
+/// The name of a GUID in the Active Directory schema
+#[derive(Eq, PartialEq, Debug, Clone, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct SchemaEntry {
+    guid: Guid,
+
+    /// the `lDAPDisplayName` of classes and attributes, or the `name` of
+    /// control access rights
+    name: String,
+
+    kind: SchemaObjectKind,
+}
+
+impl SchemaEntry {
+    pub fn new(guid: Guid, name: impl Into<String>, kind: SchemaObjectKind) -> Self {
+        Self {
+            guid,
+            name: name.into(),
+            kind,
+        }
+    }
+}
+
+/// Looks up the names of the object types in object ACEs. This is
+/// implemented for lists of [`SchemaEntry`]s and for
+/// [`ActiveDirectorySchema`], which contains the default schema and can be
+/// extended by the classes, attributes and rights of a forest.
+pub trait GuidResolver {
+    /// returns the schema object which is identified by `guid`, or `None` if
+    /// the GUID is unknown
+    fn resolve_guid(&self, guid: &Guid) -> Option<&SchemaEntry>;
+}
+
+impl GuidResolver for [SchemaEntry] {
+    fn resolve_guid(&self, guid: &Guid) -> Option<&SchemaEntry> {
+        self.iter().find(|entry| entry.guid() == guid)
+    }
+}
+
+impl GuidResolver for Vec<SchemaEntry> {
+    fn resolve_guid(&self, guid: &Guid) -> Option<&SchemaEntry> {
+        self.as_slice().resolve_guid(guid)
+    }
+}
+
+/// The GUIDs of an Active Directory schema. [`Default::default`] returns the
+/// classes, attributes, property sets, validated writes and extended rights
+/// of the default schema.
+///
+/// ```
+/// use sddl::*;
+/// let user = Guid::try_from("bf967aba-0de6-11d0-a285-00aa003049e2").unwrap();
+/// let laps = Guid::try_from("f3531ec6-6330-4f8e-8d39-7a671fbac605").unwrap();
+///
+/// let schema = ActiveDirectorySchema::default().with_entry(SchemaEntry::new(
+///     laps,
+///     "ms-Mcs-AdmPwd",
+///     SchemaObjectKind::Attribute,
+/// ));
+/// assert_eq!(schema.resolve_guid(&user).unwrap().name(), "user");
+/// assert_eq!(schema.resolve_guid(&laps).unwrap().kind(), &SchemaObjectKind::Attribute);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ActiveDirectorySchema {
+    entries: HashMap<Guid, SchemaEntry>,
+}
+
+impl ActiveDirectorySchema {
+    /// creates a schema without any entries
+    pub fn empty() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// adds or replaces the entry for a GUID
+    pub fn with_entry(mut self, entry: SchemaEntry) -> Self {
+        self.insert(entry);
+        self
+    }
+
+    /// adds or replaces the entry for a GUID
+    pub fn insert(&mut self, entry: SchemaEntry) {
+        self.entries.insert(*entry.guid(), entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// returns the default schema, which is used by the `Serialize`
+    /// implementation of [`Guid`]
+    pub fn builtin() -> &'static Self {
+        &DEFAULT_SCHEMA
+    }
+}
+
+impl Default for ActiveDirectorySchema {
+    fn default() -> Self {
+        let mut schema = Self::empty();
+        for (guid, name, kind) in DEFAULT_SCHEMA_ENTRIES {
+            let guid = Guid::try_from(*guid).expect("the default schema contains valid GUIDs");
+            schema.insert(SchemaEntry::new(guid, *name, *kind));
+        }
+        schema
+    }
+}
+
+impl GuidResolver for ActiveDirectorySchema {
+    fn resolve_guid(&self, guid: &Guid) -> Option<&SchemaEntry> {
+        self.entries.get(guid)
+    }
+}
+
+lazy_static! {
+    static ref DEFAULT_SCHEMA: ActiveDirectorySchema = ActiveDirectorySchema::default();
+}
+
+use SchemaObjectKind::*;
+
+/// GUIDs of the default schema. Some validated writes share their GUID with
+/// the attribute they validate, which is listed only once.
+#[rustfmt::skip]
+const DEFAULT_SCHEMA_ENTRIES: &[(&str, &str, SchemaObjectKind)] = &[
+    // classes
+    ("bf967a81-0de6-11d0-a285-00aa003049e2", "builtinDomain", Class),
+    ("3fdfee50-47f4-11d1-a9c3-0000f80367c1", "certificationAuthority", Class),
+    ("bf967a86-0de6-11d0-a285-00aa003049e2", "computer", Class),
+    ("5cb41ed0-0e4c-11d0-a286-00aa003049e2", "contact", Class),
+    ("bf967a8b-0de6-11d0-a285-00aa003049e2", "container", Class),
+    ("bf967a8d-0de6-11d0-a285-00aa003049e2", "crossRef", Class),
+    ("e0fa1e8c-9b45-11d0-afdd-00c04fd930c9", "dnsNode", Class),
+    ("e0fa1e8b-9b45-11d0-afdd-00c04fd930c9", "dnsZone", Class),
+    ("19195a5a-6da0-11d0-afd3-00c04fd930c9", "domain", Class),
+    ("19195a5b-6da0-11d0-afd3-00c04fd930c9", "domainDNS", Class),
+    ("bf967a99-0de6-11d0-a285-00aa003049e2", "domainPolicy", Class),
+    ("89e31c12-8530-11d0-afda-00c04fd930c9", "foreignSecurityPrincipal", Class),
+    ("bf967a9c-0de6-11d0-a285-00aa003049e2", "group", Class),
+    ("f30e3bc2-9ff0-11d1-b603-0000f80367c1", "groupPolicyContainer", Class),
+    ("4828cc14-1437-45bc-9b07-ad6f015e5f28", "inetOrgPerson", Class),
+    ("7b8b558a-93a5-4af7-adca-c017e67f1057", "msDS-GroupManagedServiceAccount", Class),
+    ("ce206244-5827-4a86-ba1c-1c0c386c1b64", "msDS-ManagedServiceAccount", Class),
+    ("3bcd9db8-f84b-451c-952f-6c52b81f9ec6", "msDS-PasswordSettings", Class),
+    ("ea715d30-8f53-40d0-bd1e-6109186d782c", "msFVE-RecoveryInformation", Class),
+    ("85045b6a-47a6-4243-a7cc-6890701f662c", "msTPM-InformationObject", Class),
+    ("f0f8ffab-1191-11d0-a060-00aa006c33ed", "nTDSDSA", Class),
+    ("bf967aa4-0de6-11d0-a285-00aa003049e2", "organizationalPerson", Class),
+    ("bf967aa5-0de6-11d0-a285-00aa003049e2", "organizationalUnit", Class),
+    ("bf967aa7-0de6-11d0-a285-00aa003049e2", "person", Class),
+    ("e5209ca2-3bba-11d2-90cc-00c04fd91ab1", "pKICertificateTemplate", Class),
+    ("bf967aa8-0de6-11d0-a285-00aa003049e2", "printQueue", Class),
+    ("6617188d-8f3c-11d0-afda-00c04fd930c9", "rIDManager", Class),
+    ("7bfdcb89-4807-11d1-a9c3-0000f80367c1", "rIDSet", Class),
+    ("bf967aad-0de6-11d0-a285-00aa003049e2", "samServer", Class),
+    ("bf967a92-0de6-11d0-a285-00aa003049e2", "server", Class),
+    ("28630ec1-41d5-11d1-a9c1-0000f80367c1", "serviceConnectionPoint", Class),
+    ("bf967ab3-0de6-11d0-a285-00aa003049e2", "site", Class),
+    ("d50c2cde-8951-11d1-aebc-0000f80367c1", "siteLink", Class),
+    ("b7b13124-b82e-11d0-afee-0000f80367c1", "subnet", Class),
+    ("bf967ab7-0de6-11d0-a285-00aa003049e2", "top", Class),
+    ("bf967ab8-0de6-11d0-a285-00aa003049e2", "trustedDomain", Class),
+    ("bf967aba-0de6-11d0-a285-00aa003049e2", "user", Class),
+    // attributes
+    ("bf967915-0de6-11d0-a285-00aa003049e2", "accountExpires", Attribute),
+    ("bf967918-0de6-11d0-a285-00aa003049e2", "adminCount", Attribute),
+    ("00fbf30c-91fe-11d1-aebc-0000f80367c1", "altSecurityIdentities", Attribute),
+    ("bf96792e-0de6-11d0-a285-00aa003049e2", "badPwdCount", Attribute),
+    ("bf96793f-0de6-11d0-a285-00aa003049e2", "cn", Attribute),
+    ("bf967950-0de6-11d0-a285-00aa003049e2", "description", Attribute),
+    ("bf967953-0de6-11d0-a285-00aa003049e2", "displayName", Attribute),
+    ("f30e3bc1-9ff0-11d1-b603-0000f80367c1", "gPCFileSysPath", Attribute),
+    ("f30e3bbe-9ff0-11d1-b603-0000f80367c1", "gPLink", Attribute),
+    ("f30e3bbf-9ff0-11d1-b603-0000f80367c1", "gPOptions", Attribute),
+    ("9a9a021e-4a5b-11d1-a9c3-0000f80367c1", "groupType", Attribute),
+    ("bf967985-0de6-11d0-a285-00aa003049e2", "homeDirectory", Attribute),
+    ("bf967997-0de6-11d0-a285-00aa003049e2", "lastLogon", Attribute),
+    ("c0e20a04-0e5a-4ff3-9482-5efeaecd7060", "lastLogonTimestamp", Attribute),
+    ("28630ebf-41d5-11d1-a9c1-0000f80367c1", "lockoutTime", Attribute),
+    ("bf9679ab-0de6-11d0-a285-00aa003049e2", "logonHours", Attribute),
+    ("bf967961-0de6-11d0-a285-00aa003049e2", "mail", Attribute),
+    ("0296c120-40da-11d1-a9c0-0000f80367c1", "managedBy", Attribute),
+    ("bf9679b5-0de6-11d0-a285-00aa003049e2", "manager", Attribute),
+    ("bf9679c0-0de6-11d0-a285-00aa003049e2", "member", Attribute),
+    ("bf967991-0de6-11d0-a285-00aa003049e2", "memberOf", Attribute),
+    ("d064fb68-1480-11d3-91c1-0000f87a57d4", "ms-DS-MachineAccountQuota", Attribute),
+    ("3f78c3e5-f79a-46bd-a0b8-9d18116ddc79", "msDS-AllowedToActOnBehalfOfOtherIdentity", Attribute),
+    ("800d94d7-b7a1-42a1-b14d-7cae1423d07f", "msDS-AllowedToDelegateTo", Attribute),
+    ("888eedd6-ce04-df40-b462-b8a50e41ba38", "msDS-GroupMSAMembership", Attribute),
+    ("80641043-15a2-40e1-92a2-8ca866f70776", "msDS-HostServiceAccount", Attribute),
+    ("5b47d60f-6090-40b2-9f37-2a4de88f3063", "msDS-KeyCredentialLink", Attribute),
+    ("e362ed86-b728-0842-b27d-2dea7a9df218", "msDS-ManagedPassword", Attribute),
+    ("15585999-fd49-4d66-b25d-eeb96aba8174", "msDS-NeverRevealGroup", Attribute),
+    ("b77ea093-88d0-4780-9a98-911f8e8b1dca", "msDS-ResultantPSO", Attribute),
+    ("303d9f4a-1dd6-4b38-8fc5-33afe8c988ad", "msDS-RevealOnDemandGroup", Attribute),
+    ("20119867-1d04-4ab7-9371-cfc3d5df0afd", "msDS-SupportedEncryptionTypes", Attribute),
+    ("2cc4b836-b63f-4940-8d23-ea7acf06af56", "msDS-User-Account-Control-Computed", Attribute),
+    ("ea1dddc4-60ff-416e-8cc0-17cee534bce7", "msPKI-Certificate-Name-Flag", Attribute),
+    ("d15ef7d8-f226-46db-ae79-b34e560bd12c", "msPKI-Enrollment-Flag", Attribute),
+    ("aa4e1a6d-550d-4e05-8c35-4afcb917a9fe", "msTPM-OwnerInformation", Attribute),
+    ("ea1b7b93-5e48-46d5-bc6c-4df4fda78a35", "msTPM-TpmInformationForComputer", Attribute),
+    ("bf967a0e-0de6-11d0-a285-00aa003049e2", "name", Attribute),
+    ("bf9679e3-0de6-11d0-a285-00aa003049e2", "nTSecurityDescriptor", Attribute),
+    ("bf9679e5-0de6-11d0-a285-00aa003049e2", "objectClass", Attribute),
+    ("bf9679e7-0de6-11d0-a285-00aa003049e2", "objectGUID", Attribute),
+    ("bf9679e8-0de6-11d0-a285-00aa003049e2", "objectSid", Attribute),
+    ("3e978925-8c01-11d0-afda-00c04fd930c9", "operatingSystem", Attribute),
+    ("bd951b3c-9c96-11d0-afdd-00c04fd930c9", "operatingSystemHotfix", Attribute),
+    ("3e978927-8c01-11d0-afda-00c04fd930c9", "operatingSystemServicePack", Attribute),
+    ("3e978926-8c01-11d0-afda-00c04fd930c9", "operatingSystemVersion", Attribute),
+    ("18976af6-3b9e-11d2-90cc-00c04fd91ab1", "pKIExtendedKeyUsage", Attribute),
+    ("bf967a00-0de6-11d0-a285-00aa003049e2", "primaryGroupID", Attribute),
+    ("bf967a05-0de6-11d0-a285-00aa003049e2", "profilePath", Attribute),
+    ("bf967a0a-0de6-11d0-a285-00aa003049e2", "pwdLastSet", Attribute),
+    ("bf967a0b-0de6-11d0-a285-00aa003049e2", "pwdProperties", Attribute),
+    ("3e0abfd0-126a-11d0-a060-00aa006c33ed", "sAMAccountName", Attribute),
+    ("6e7b626c-64f2-11d0-afd2-00c04fd930c9", "sAMAccountType", Attribute),
+    ("bf9679a8-0de6-11d0-a285-00aa003049e2", "scriptPath", Attribute),
+    ("f3a64788-5306-11d1-a9c5-0000f80367c1", "servicePrincipalName", Attribute),
+    ("17eb4278-d167-11d0-b002-0000f80367c1", "sIDHistory", Attribute),
+    ("bf967a49-0de6-11d0-a285-00aa003049e2", "telephoneNumber", Attribute),
+    ("b7c69e6d-2cc7-11d2-854e-00a0c983f608", "tokenGroups", Attribute),
+    ("46a9b11d-60ae-405a-b7e8-ff8a58d456d2", "tokenGroupsGlobalAndUniversal", Attribute),
+    ("040fc392-33df-11d2-98b2-0000f87a57d4", "tokenGroupsNoGCAcceptable", Attribute),
+    ("bf9679e1-0de6-11d0-a285-00aa003049e2", "unicodePwd", Attribute),
+    ("bf967a7f-0de6-11d0-a285-00aa003049e2", "userCertificate", Attribute),
+    ("bf967a68-0de6-11d0-a285-00aa003049e2", "userAccountControl", Attribute),
+    ("28630ebb-41d5-11d1-a9c1-0000f80367c1", "userPrincipalName", Attribute),
+    ("bf9679d7-0de6-11d0-a285-00aa003049e2", "userWorkstations", Attribute),
+    // property sets
+    ("b8119fd0-04f6-4762-ab7a-4986c76b3f9a", "Domain-Other-Parameters", PropertySet),
+    ("6db69a1c-9422-11d1-aebd-0000f80367c1", "Terminal-Server", PropertySet),
+    ("5805bc62-bdc9-4428-a5e2-856a0f4c185e", "Terminal-Server-License-Server", PropertySet),
+    ("4c164200-20c0-11d0-a768-00aa006e0529", "User-Account-Restrictions", PropertySet),
+    ("c7407360-20bf-11d0-a768-00aa006e0529", "Domain-Password", PropertySet),
+    ("e45795b2-9455-11d1-aebd-0000f80367c1", "Email-Information", PropertySet),
+    ("59ba2f42-79a2-11d0-9020-00c04fc2d3cf", "General-Information", PropertySet),
+    ("bc0ac240-79a9-11d0-9020-00c04fc2d4cf", "Membership", PropertySet),
+    ("77b5b886-944a-11d1-aebd-0000f80367c1", "Personal-Information", PropertySet),
+    ("91e647de-d96f-4b70-9557-d63ff4f3ccd8", "Private-Information", PropertySet),
+    ("e48d0154-bcf8-11d1-8702-00c04fb96050", "Public-Information", PropertySet),
+    ("037088f8-0ae1-11d2-b422-00a0c968f939", "RAS-Information", PropertySet),
+    ("5f202010-79a5-11d0-9020-00c04fc2d4cf", "User-Logon", PropertySet),
+    ("e45795b3-9455-11d1-aebd-0000f80367c1", "Web-Information", PropertySet),
+    // validated writes
+    ("9b026da6-0d3c-465c-8bee-5199d7165cba", "DS-Validated-Write-Computer", ValidatedWrite),
+    ("72e39547-7b18-11d1-adef-00c04fd8d5cd", "Validated-DNS-Host-Name", ValidatedWrite),
+    ("80863791-dbe9-4eb8-837e-7f0ab55d9ac7", "Validated-MS-DS-Additional-DNS-Host-Name", ValidatedWrite),
+    ("d31a8757-2447-4545-8081-3bb610cacbf2", "Validated-MS-DS-Behavior-Version", ValidatedWrite),
+    // extended rights
+    ("ee914b82-0a98-11d1-adbb-00c04fd8d5cd", "Abandon-Replication", ExtendedRight),
+    ("440820ad-65b4-11d1-a3da-0000f875ae0d", "Add-GUID", ExtendedRight),
+    ("1abd7cf8-0a99-11d1-adbb-00c04fd8d5cd", "Allocate-Rids", ExtendedRight),
+    ("68b1d179-0d15-4d4f-ab71-46152e79a7bc", "Allowed-To-Authenticate", ExtendedRight),
+    ("edacfd8f-ffb3-11d1-b41d-00a0c968f939", "Apply-Group-Policy", ExtendedRight),
+    ("a05b8cc2-17bc-4802-a710-e7c15ab866a2", "Certificate-AutoEnrollment", ExtendedRight),
+    ("0e10c968-78fb-11d2-90d4-00c04f79dc55", "Certificate-Enrollment", ExtendedRight),
+    ("014bf69c-7b3b-11d1-85f6-08002be74fab", "Change-Domain-Master", ExtendedRight),
+    ("cc17b1fb-33d9-11d2-97d4-00c04fd8d5cd", "Change-Infrastructure-Master", ExtendedRight),
+    ("bae50096-4752-11d1-9052-00c04fc2d4cf", "Change-PDC", ExtendedRight),
+    ("d58d5f36-0a98-11d1-adbb-00c04fd8d5cd", "Change-Rid-Master", ExtendedRight),
+    ("e12b56b6-0a95-11d1-adbb-00c04fd8d5cd", "Change-Schema-Master", ExtendedRight),
+    ("e2a36dc9-ae17-47c3-b58b-be34c55ba633", "Create-Inbound-Forest-Trust", ExtendedRight),
+    ("fec364e0-0a98-11d1-adbb-00c04fd8d5cd", "Do-Garbage-Collection", ExtendedRight),
+    ("ab721a52-1e2f-11d0-9819-00aa0040529b", "Domain-Administer-Server", ExtendedRight),
+    ("88a9933e-e5c8-4f2a-9dd7-2527416b8092", "DS-Bypass-Quota", ExtendedRight),
+    ("69ae6200-7f46-11d2-b9ad-00c04f79f805", "DS-Check-Stale-Phantoms", ExtendedRight),
+    ("3e0f7e18-2c7a-4c10-ba82-4d926db99a3e", "DS-Clone-Domain-Controller", ExtendedRight),
+    ("2f16c4a5-b98e-432c-952a-cb388ba33f2e", "DS-Execute-Intentions-Script", ExtendedRight),
+    ("9923a32a-3607-11d2-b9be-0000f87a36b2", "DS-Install-Replica", ExtendedRight),
+    ("4ecc03fe-ffc0-4947-b630-eb672a8a9dbc", "DS-Query-Self-Quota", ExtendedRight),
+    ("084c93a2-620d-4879-a836-f0ae47de0e89", "DS-Read-Partition-Secrets", ExtendedRight),
+    ("1131f6aa-9c07-11d1-f79f-00c04fc2dcd2", "DS-Replication-Get-Changes", ExtendedRight),
+    ("1131f6ad-9c07-11d1-f79f-00c04fc2dcd2", "DS-Replication-Get-Changes-All", ExtendedRight),
+    ("89e95b76-444d-4c62-991a-0facbeda640c", "DS-Replication-Get-Changes-In-Filtered-Set", ExtendedRight),
+    ("1131f6ac-9c07-11d1-f79f-00c04fc2dcd2", "DS-Replication-Manage-Topology", ExtendedRight),
+    ("f98340fb-7c5b-4cdb-a00b-2ebdfa115a96", "DS-Replication-Monitor-Topology", ExtendedRight),
+    ("1131f6ab-9c07-11d1-f79f-00c04fc2dcd2", "DS-Replication-Synchronize", ExtendedRight),
+    ("4125c71f-7fac-4ff0-bcb7-f09a41325286", "DS-Set-Owner", ExtendedRight),
+    ("94825a8d-b171-4116-8146-1e34d8f54401", "DS-Write-Partition-Secrets", ExtendedRight),
+    ("05c74c5e-4deb-43b4-bd9f-86664c2a7fd5", "Enable-Per-User-Reversibly-Encrypted-Password", ExtendedRight),
+    ("b7b1b3de-ab09-4242-9e30-9980e5d322f7", "Generate-RSoP-Logging", ExtendedRight),
+    ("b7b1b3dd-ab09-4242-9e30-9980e5d322f7", "Generate-RSoP-Planning", ExtendedRight),
+    ("7c0e2a7c-a419-48e4-a995-10180aad54dd", "Manage-Optional-Features", ExtendedRight),
+    ("ba33815a-4f93-4c76-87f3-57574bff8109", "Migrate-SID-History", ExtendedRight),
+    ("b4e60130-df3f-11d1-9c86-006008764d0e", "msmq-Open-Connector", ExtendedRight),
+    ("06bd3201-df3e-11d1-9c86-006008764d0e", "msmq-Peek", ExtendedRight),
+    ("4b6e08c3-df3c-11d1-9c86-006008764d0e", "msmq-Peek-computer-Journal", ExtendedRight),
+    ("4b6e08c1-df3c-11d1-9c86-006008764d0e", "msmq-Peek-Dead-Letter", ExtendedRight),
+    ("06bd3200-df3e-11d1-9c86-006008764d0e", "msmq-Receive", ExtendedRight),
+    ("4b6e08c2-df3c-11d1-9c86-006008764d0e", "msmq-Receive-computer-Journal", ExtendedRight),
+    ("4b6e08c0-df3c-11d1-9c86-006008764d0e", "msmq-Receive-Dead-Letter", ExtendedRight),
+    ("06bd3203-df3e-11d1-9c86-006008764d0e", "msmq-Receive-journal", ExtendedRight),
+    ("06bd3202-df3e-11d1-9c86-006008764d0e", "msmq-Send", ExtendedRight),
+    ("a1990816-4298-11d1-ade2-00c04fd8d5cd", "Open-Address-Book", ExtendedRight),
+    ("1131f6ae-9c07-11d1-f79f-00c04fc2dcd2", "Read-Only-Replication-Secret-Synchronization", ExtendedRight),
+    ("45ec5156-db7e-47bb-b53f-dbeb2d03c40f", "Reanimate-Tombstones", ExtendedRight),
+    ("0bc1554e-0a99-11d1-adbb-00c04fd8d5cd", "Recalculate-Hierarchy", ExtendedRight),
+    ("62dd28a8-7f46-11d2-b9ad-00c04f79f805", "Recalculate-Security-Inheritance", ExtendedRight),
+    ("ab721a56-1e2f-11d0-9819-00aa0040529b", "Receive-As", ExtendedRight),
+    ("9432c620-033c-4db7-8b58-14ef6d0bf477", "Refresh-Group-Cache", ExtendedRight),
+    ("1a60ea8d-58a6-4b20-bcdc-fb71eb8a9ff8", "Reload-SSL-Certificate", ExtendedRight),
+    ("7726b9d5-a4b4-4288-a6b2-dce952e80a7f", "Run-Protect-Admin-Groups-Task", ExtendedRight),
+    ("91d67418-0135-4acc-8d79-c08e857cfbec", "SAM-Enumerate-Entire-Domain", ExtendedRight),
+    ("ab721a54-1e2f-11d0-9819-00aa0040529b", "Send-As", ExtendedRight),
+    ("ab721a55-1e2f-11d0-9819-00aa0040529b", "Send-To", ExtendedRight),
+    ("ccc2dc7d-a6ad-4a7a-8846-c04e3cc53501", "Unexpire-Password", ExtendedRight),
+    ("280f369c-67c7-438e-ae98-1d46f3c6f541", "Update-Password-Not-Required-Bit", ExtendedRight),
+    ("be2bb760-7f46-11d2-b9ad-00c04f79f805", "Update-Schema-Cache", ExtendedRight),
+    ("ab721a53-1e2f-11d0-9819-00aa0040529b", "User-Change-Password", ExtendedRight),
+    ("00299570-246d-11d0-a768-00aa006e0529", "User-Force-Change-Password", ExtendedRight),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_schema_has_unique_guids() {
+        assert_eq!(
+            ActiveDirectorySchema::builtin().len(),
+            DEFAULT_SCHEMA_ENTRIES.len()
+        );
+    }
+
+    #[test]
+    fn test_resolve_extended_right() {
+        let guid = Guid::try_from("1131f6ad-9c07-11d1-f79f-00c04fc2dcd2").unwrap();
+        let entry = ActiveDirectorySchema::builtin()
+            .resolve_guid(&guid)
+            .unwrap();
+        assert_eq!(entry.name(), "DS-Replication-Get-Changes-All");
+        assert_eq!(*entry.kind(), SchemaObjectKind::ExtendedRight);
+
+        let custom = vec![SchemaEntry::new(
+            guid,
+            "custom",
+            SchemaObjectKind::Attribute,
+        )];
+        assert_eq!(custom.resolve_guid(&guid).unwrap().name(), "custom");
+        assert!(ActiveDirectorySchema::empty().resolve_guid(&guid).is_none());
+    }
+}
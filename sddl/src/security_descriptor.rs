@@ -1,21 +1,37 @@
-use std::{fmt::Display, io::Cursor};
+use std::{
+    fmt::Display,
+    io::{Cursor, SeekFrom},
+    ops::Range,
+};
 
-use binrw::{binread, FilePtr, BinReaderExt};
+use binrw::{binread, BinReaderExt, BinWrite, BinWriterExt};
 use getset::Getters;
 use serde::Serialize;
 
-use crate::{sddl_h::*, Acl, AclType, ControlFlags, Offset, Sid};
+use crate::acl::display_string;
+use crate::{
+    sddl_h::*, Ace, AceHeaderFlags, Acl, AclType, ClaimAttribute, ControlFlags, IntegrityLabel,
+    IntegrityLevel, ObjectKind, Offset, ParseWarning, ProcessTrustLabel, ProtectionLevel, RawSize,
+    SddlComponent, Sid,
+};
+
+/// size of the fixed part of a self-relative security descriptor
+pub const SECURITY_DESCRIPTOR_HEADER_SIZE: u32 = 1 + 1 + 2 + 4 + 4 + 4 + 4;
+
+/// maximum number of bytes which [`SecurityDescriptor::from_bytes_lenient`]
+/// skips while searching for the beginning of a security descriptor
+const MAX_PREFIX_SIZE: usize = 16;
 
 /// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/2918391b-75b9-4eeb-83f0-7fdc04a5c6c9>
 #[binread]
 #[derive(Eq, PartialEq, Getters, Serialize)]
 #[getset(get = "pub")]
+#[br(little)]
 pub struct SecurityDescriptor {
     #[serde(skip)]
     sd_offset: Offset,
 
     #[br(assert(revision == 1))]
-    #[bw(assert(*revision == 1))]
     revision: u8,
 
     #[getset(skip)]
@@ -25,54 +41,38 @@ pub struct SecurityDescriptor {
 
     flags: ControlFlags,
 
-    #[serde(skip)]
-    #[br(little,temp,
-        offset=sd_offset.0,
-        if(! flags.contains(ControlFlags::OwnerDefaulted)))
-        ]
-    owner_ref: Option<FilePtr<u32, Sid>>,
-
-    #[serde(skip)]
-    #[br(little,temp,
-        offset=sd_offset.0,
-        if(! flags.contains(ControlFlags::GroupDefaulted)))
-        ]
-    group_ref: Option<FilePtr<u32, Sid>>,
-
-    #[serde(skip)]
-    #[br(little,temp,
-        offset=sd_offset.0,
-        if(flags.contains(ControlFlags::SystemAclPresent)),
-        args{inner: (flags, AclType::SACL)})
-        ]
-    sacl_ref: Option<FilePtr<u32, Acl>>,
+    #[br(temp)]
+    owner_offset: u32,
 
-    #[serde(skip)]
-    #[br(temp, if(! flags.contains(ControlFlags::SystemAclPresent), 0))]
-    _sacl_ref: u32,
+    #[br(temp)]
+    group_offset: u32,
 
-    #[serde(skip)]
-    #[br(little,temp,
-        offset=sd_offset.0,
-        if(flags.contains(ControlFlags::DiscretionaryAclPresent)),
-        args{inner: (flags, AclType::DACL)})
-        ]
-    dacl_ref: Option<FilePtr<u32, Acl>>,
+    #[br(temp)]
+    sacl_offset: u32,
 
-    #[serde(skip)]
-    #[br(temp, if(! flags.contains(ControlFlags::DiscretionaryAclPresent), 0))]
-    _dacl_ref: u32,
+    #[br(temp)]
+    dacl_offset: u32,
 
-    #[br(calc=owner_ref.as_ref().map(|d| d.value.clone()))]
+    #[br(if(owner_offset != 0),
+        seek_before=SeekFrom::Start(sd_offset.0 + u64::from(owner_offset)),
+        restore_position)]
     owner: Option<Sid>,
 
-    #[br(calc=group_ref.as_ref().map(|d| d.value.clone()))]
+    #[br(if(group_offset != 0),
+        seek_before=SeekFrom::Start(sd_offset.0 + u64::from(group_offset)),
+        restore_position)]
     group: Option<Sid>,
 
-    #[br(calc=dacl_ref.as_ref().map(|d| d.value.clone()))]
+    #[br(if(flags.contains(ControlFlags::DiscretionaryAclPresent) && dacl_offset != 0),
+        seek_before=SeekFrom::Start(sd_offset.0 + u64::from(dacl_offset)),
+        restore_position,
+        args(flags, AclType::DACL))]
     dacl: Option<Acl>,
 
-    #[br(calc=sacl_ref.as_ref().map(|d| d.value.clone()))]
+    #[br(if(flags.contains(ControlFlags::SystemAclPresent) && sacl_offset != 0),
+        seek_before=SeekFrom::Start(sd_offset.0 + u64::from(sacl_offset)),
+        restore_position,
+        args(flags, AclType::SACL))]
     sacl: Option<Acl>,
 }
 
@@ -105,19 +105,134 @@ impl SecurityDescriptor {
         Ok(cursor.read_le()?)
     }
 
+    /// parses a binary security descriptor which might be damaged, e.g.
+    /// because it has been carved from a disk image. Instead of failing, this
+    /// function skips everything which cannot be parsed and reports it as a
+    /// [`ParseWarning`]:
+    ///
+    ///  - up to 16 bytes before the security descriptor are skipped,
+    ///  - owners, groups and ACLs with invalid offsets are ignored,
+    ///  - malformed ACEs are skipped using their AceSize,
+    ///  - ACEs of unknown types are kept as [`Ace::Unknown`], and
+    ///  - ACL revisions which do not allow the ACEs of the ACL are kept.
+    ///
+    /// An error is only returned if the header of the security descriptor is
+    /// incomplete.
+    ///
+    /// ```rust
+    /// use sddl::{ParseWarning, SecurityDescriptor};
+    ///
+    /// let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:(A;;FA;;;SY)(A;;FR;;;WD)", None).unwrap();
+    /// let mut bytes = sd.to_bytes().unwrap();
+    /// // replace the type of the first ACE by an unknown type
+    /// bytes[28] = 0x7f;
+    /// assert!(SecurityDescriptor::from_bytes(&bytes).is_err());
+    ///
+    /// let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    /// assert_eq!(sd.dacl().as_ref().unwrap().ace_list().len(), 2);
+    /// assert!(matches!(warnings[..], [ParseWarning::UnknownAceType { ace_type: 0x7f, .. }]));
+    /// ```
+    pub fn from_bytes_lenient(bytes: &[u8]) -> Result<(Self, Vec<ParseWarning>), crate::Error> {
+        let mut warnings = Vec::new();
+        let base = match find_header(bytes) {
+            Some(0) => 0,
+            Some(length) => {
+                warnings.push(ParseWarning::SkippedPrefix { length });
+                length
+            }
+            None => {
+                let revision = bytes.first().copied().unwrap_or_default();
+                warnings.push(ParseWarning::InvalidRevision { revision });
+                0
+            }
+        };
+        let data = &bytes[base..];
+
+        let mut cursor = Cursor::new(data);
+        let revision: u8 = cursor.read_le()?;
+        let _reserved1: u8 = cursor.read_le()?;
+        let flags: ControlFlags = cursor.read_le()?;
+        let owner_offset: u32 = cursor.read_le()?;
+        let group_offset: u32 = cursor.read_le()?;
+        let sacl_offset: u32 = cursor.read_le()?;
+        let dacl_offset: u32 = cursor.read_le()?;
+
+        let mut reader = LenientReader {
+            data,
+            base,
+            warnings,
+            components: Vec::new(),
+        };
+        let owner = reader.sid(SddlComponent::Owner, owner_offset);
+        let group = reader.sid(SddlComponent::Group, group_offset);
+        let sacl = flags
+            .contains(ControlFlags::SystemAclPresent)
+            .then(|| reader.acl(AclType::SACL, sacl_offset, flags))
+            .flatten();
+        let dacl = flags
+            .contains(ControlFlags::DiscretionaryAclPresent)
+            .then(|| reader.acl(AclType::DACL, dacl_offset, flags))
+            .flatten();
+
+        let sd = Self {
+            sd_offset: Offset(0),
+            revision,
+            flags,
+            owner,
+            group,
+            dacl,
+            sacl,
+        };
+        Ok((sd, reader.warnings))
+    }
+
+    /// creates the binary representation of this security descriptor in
+    /// self-relative format
+    ///
+    /// ```rust
+    /// use sddl::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_sddl("O:BAG:BAD:P(A;CIOI;GA;;;SY)S:P(AU;FA;GR;;;WD)", None).unwrap();
+    /// let bytes = sd.to_bytes().unwrap();
+    /// let sd2 = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    /// assert_eq!(sd.to_string(), sd2.to_string());
+    /// assert_eq!(bytes, sd2.to_bytes().unwrap());
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, crate::Error> {
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le(self)?;
+        Ok(cursor.into_inner())
+    }
+
+    /// creates a security descriptor. The `*AclPresent` flags are set for
+    /// each ACL which is given, and the protection and inheritance flags
+    /// are taken from the ACLs.
+    ///
+    /// ```rust
+    /// use sddl::{ControlFlags, SecurityDescriptor};
+    ///
+    /// let sd = SecurityDescriptor::from_sddl("D:P(A;;FA;;;SY)", None).unwrap();
+    /// assert!(sd.owner().is_none());
+    /// assert_eq!(
+    ///     *sd.flags(),
+    ///     ControlFlags::DiscretionaryAclPresent | ControlFlags::DiscretionaryAclProtected
+    /// );
+    /// ```
     pub fn new(owner: Option<Sid>, group: Option<Sid>, dacl: Option<Acl>, sacl: Option<Acl>) -> Self {
         let mut flags = ControlFlags::empty();
-        if owner.is_some() {
-            flags |= ControlFlags::OwnerDefaulted
-        }
-        if group.is_some() {
-            flags |= ControlFlags::GroupDefaulted
-        }
         if let Some(dacl) = &dacl {
-            flags |= *dacl.control_flags() | ControlFlags::DiscretionaryAclPresent;
+            flags |= (*dacl.control_flags()
+                & (ControlFlags::DiscretionaryAclProtected
+                    | ControlFlags::DiscretionaryAclAutoInheritRequired
+                    | ControlFlags::DiscretionaryAclAutoInherited))
+                | ControlFlags::DiscretionaryAclPresent;
         }
         if let Some(sacl) = &sacl {
-            flags |= *sacl.control_flags() | ControlFlags::SystemAclPresent;
+            flags |= (*sacl.control_flags()
+                & (ControlFlags::SystemAclProtected
+                    | ControlFlags::SystemAclAutoInheritRequired
+                    | ControlFlags::SystemAclAutoInherited))
+                | ControlFlags::SystemAclPresent;
         }
         Self {
             sd_offset: Offset(0),
@@ -130,73 +245,442 @@ impl SecurityDescriptor {
         }
     }
 
+    /// replaces the DACL by a NULL DACL, which grants full access to
+    /// everyone. Unlike a missing DACL, a NULL DACL is marked as present by
+    /// the `DiscretionaryAclPresent` flag. The protection and inheritance
+    /// flags of the DACL are taken from `flags`.
+    ///
+    /// ```rust
+    /// use sddl::{ControlFlags, SecurityDescriptor};
+    ///
+    /// let sd = SecurityDescriptor::from_sddl("D:NO_ACCESS_CONTROL", None).unwrap();
+    /// assert!(sd.has_null_dacl());
+    /// assert!(sd.dacl().is_none());
+    /// assert_eq!(*sd.flags(), ControlFlags::DiscretionaryAclPresent);
+    /// assert_eq!(sd.to_string(), "D:NO_ACCESS_CONTROL");
+    /// ```
+    pub fn with_null_dacl(mut self, flags: ControlFlags) -> Self {
+        self.dacl = None;
+        self.flags -= ControlFlags::DiscretionaryAclProtected
+            | ControlFlags::DiscretionaryAclAutoInheritRequired
+            | ControlFlags::DiscretionaryAclAutoInherited;
+        self.flags |= (flags
+            & (ControlFlags::DiscretionaryAclProtected
+                | ControlFlags::DiscretionaryAclAutoInheritRequired
+                | ControlFlags::DiscretionaryAclAutoInherited))
+            | ControlFlags::DiscretionaryAclPresent;
+        self
+    }
+
+    /// returns `true` if the DACL is present, but NULL
+    pub fn has_null_dacl(&self) -> bool {
+        self.dacl.is_none() && self.flags.contains(ControlFlags::DiscretionaryAclPresent)
+    }
+
+    /// returns the SDDL representation of the SACL, or `None` if there is no
+    /// SACL. ACEs which have no SDDL representation are written as described
+    /// in the `Display` implementation of [`Ace`].
     pub fn sacl_as_sddl_string(&self) -> Option<String> {
-        self.sacl().as_ref().map(|sacl| {
-            let mut flags = String::with_capacity(5);
-            if self.flags().contains(ControlFlags::SystemAclProtected) {
-                flags.push_str(SDDL_PROTECTED);
-            }
-            if self
-                .flags()
-                .contains(ControlFlags::SystemAclAutoInheritRequired)
-            {
-                flags.push_str(SDDL_AUTO_INHERIT_REQ);
-            }
-            if self.flags().contains(ControlFlags::SystemAclAutoInherited) {
-                flags.push_str(SDDL_AUTO_INHERITED);
-            }
-            format!("{SDDL_SACL}{SDDL_DELIMINATOR}{flags}{sacl}")
-        })
+        let Ok(sddl) = self.sacl_sddl_string_with(&display_string(None));
+        sddl
     }
 
+    /// returns the SDDL representation of the SACL, or `Ok(None)` if there
+    /// is no SACL. Fails if an ACE has no SDDL representation, see
+    /// [`Ace::to_sddl`].
+    pub fn try_sacl_as_sddl_string(&self) -> Result<Option<String>, crate::Error> {
+        self.sacl_sddl_string_with(&|ace| ace.sddl_string_with(None))
+    }
+
+    /// returns the SDDL representation of the DACL, or `None` if there is no
+    /// DACL. A NULL DACL is written as `D:NO_ACCESS_CONTROL`. ACEs which have
+    /// no SDDL representation are written as described in the `Display`
+    /// implementation of [`Ace`].
     pub fn dacl_as_sddl_string(&self) -> Option<String> {
-        self.dacl().as_ref().map(|dacl| {
-            let mut flags = String::with_capacity(5);
-            if self
-                .flags()
-                .contains(ControlFlags::DiscretionaryAclProtected)
-            {
-                flags.push_str(SDDL_PROTECTED);
-            }
-            if self
-                .flags()
-                .contains(ControlFlags::DiscretionaryAclAutoInheritRequired)
-            {
-                flags.push_str(SDDL_AUTO_INHERIT_REQ);
-            }
-            if self
-                .flags()
-                .contains(ControlFlags::DiscretionaryAclAutoInherited)
-            {
-                flags.push_str(SDDL_AUTO_INHERITED);
-            }
-            format!("{SDDL_DACL}{SDDL_DELIMINATOR}{flags}{dacl}")
-        })
+        let Ok(sddl) = self.dacl_sddl_string_with(&display_string(None));
+        sddl
+    }
+
+    /// returns the SDDL representation of the DACL, or `Ok(None)` if there
+    /// is no DACL. A NULL DACL is written as `D:NO_ACCESS_CONTROL`. Fails if
+    /// an ACE has no SDDL representation, see [`Ace::to_sddl`].
+    pub fn try_dacl_as_sddl_string(&self) -> Result<Option<String>, crate::Error> {
+        self.dacl_sddl_string_with(&|ace| ace.sddl_string_with(None))
+    }
+
+    /// returns the SDDL representation of this security descriptor, or
+    /// [`crate::Error::NoSddlRepresentation`] if it contains an ACE which
+    /// cannot be written as SDDL without losing information (see
+    /// [`Ace::to_sddl`]). Unlike the `Display` implementation, this never
+    /// writes ACEs which cannot be parsed as SDDL.
+    pub fn to_sddl(&self) -> Result<String, crate::Error> {
+        self.sddl_string_with(&|ace| ace.sddl_string_with(None))
+    }
+
+    /// returns the SDDL representation of this security descriptor, using the
+    /// access right shorthands which are appropriate for an object of type
+    /// `kind`
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AI(A;;KR;;;BU)S:AI", None).unwrap();
+    /// let dacl = sd.sddl_string_for(ObjectKind::RegistryKey).unwrap();
+    /// assert!(dacl.ends_with("D:AI(A;;KR;;;BU)"), "{dacl}");
+    /// let dacl = sd.sddl_string_for(ObjectKind::Service).unwrap();
+    /// assert!(dacl.ends_with("D:AI(A;;CCSWRPRC;;;BU)"), "{dacl}");
+    /// ```
+    pub fn sddl_string_for(&self, kind: ObjectKind) -> Result<String, crate::Error> {
+        self.sddl_string_with(&|ace| ace.sddl_string_with(Some(kind)))
+    }
+
+    fn sddl_string_with<E>(
+        &self,
+        ace_string: &impl Fn(&Ace) -> Result<String, E>,
+    ) -> Result<String, E> {
+        let mut sddl = String::new();
+        if let Some(owner) = self.owner() {
+            sddl.push_str(&format!("{SDDL_OWNER}{SDDL_DELIMINATOR}{owner}"));
+        }
+        if let Some(group) = self.group() {
+            sddl.push_str(&format!("{SDDL_GROUP}{SDDL_DELIMINATOR}{group}"));
+        }
+        if let Some(sacl) = self.sacl_sddl_string_with(ace_string)? {
+            sddl.push_str(&sacl);
+        }
+        if let Some(dacl) = self.dacl_sddl_string_with(ace_string)? {
+            sddl.push_str(&dacl);
+        }
+        Ok(sddl)
+    }
+
+    fn sacl_sddl_string_with<E>(
+        &self,
+        ace_string: &impl Fn(&Ace) -> Result<String, E>,
+    ) -> Result<Option<String>, E> {
+        let Some(sacl) = self.sacl() else {
+            return Ok(None);
+        };
+        let mut flags = String::with_capacity(5);
+        if self.flags().contains(ControlFlags::SystemAclProtected) {
+            flags.push_str(SDDL_PROTECTED);
+        }
+        if self
+            .flags()
+            .contains(ControlFlags::SystemAclAutoInheritRequired)
+        {
+            flags.push_str(SDDL_AUTO_INHERIT_REQ);
+        }
+        if self.flags().contains(ControlFlags::SystemAclAutoInherited) {
+            flags.push_str(SDDL_AUTO_INHERITED);
+        }
+        let mut sddl = format!("{SDDL_SACL}{SDDL_DELIMINATOR}{flags}");
+        sacl.write_aces(&mut sddl, ace_string)?;
+        Ok(Some(sddl))
+    }
+
+    fn dacl_sddl_string_with<E>(
+        &self,
+        ace_string: &impl Fn(&Ace) -> Result<String, E>,
+    ) -> Result<Option<String>, E> {
+        if self.dacl().is_none() && !self.has_null_dacl() {
+            return Ok(None);
+        }
+        let mut flags = String::with_capacity(5);
+        if self
+            .flags()
+            .contains(ControlFlags::DiscretionaryAclProtected)
+        {
+            flags.push_str(SDDL_PROTECTED);
+        }
+        if self
+            .flags()
+            .contains(ControlFlags::DiscretionaryAclAutoInheritRequired)
+        {
+            flags.push_str(SDDL_AUTO_INHERIT_REQ);
+        }
+        if self
+            .flags()
+            .contains(ControlFlags::DiscretionaryAclAutoInherited)
+        {
+            flags.push_str(SDDL_AUTO_INHERITED);
+        }
+        let mut sddl = format!("{SDDL_DACL}{SDDL_DELIMINATOR}{flags}");
+        match self.dacl() {
+            Some(dacl) => dacl.write_aces(&mut sddl, ace_string)?,
+            None => sddl.push_str(SDDL_NULL_ACL),
+        }
+        Ok(Some(sddl))
+    }
+
+    /// returns the mandatory integrity label which is stored in the SACL, or
+    /// `None` if the object has no label
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let sd = SecurityDescriptor::from_sddl("O:BAG:SYD:AIS:AI(ML;;NWNR;;;LW)", None).unwrap();
+    /// let label = sd.integrity_label().unwrap();
+    /// assert_eq!(label.level(), IntegrityLevel::Low);
+    /// assert_eq!(label.policy(), MandatoryPolicy::NO_WRITE_UP | MandatoryPolicy::NO_READ_UP);
+    /// ```
+    pub fn integrity_label(&self) -> Option<IntegrityLabel> {
+        self.sacl()
+            .as_ref()?
+            .ace_list()
+            .iter()
+            .filter(|ace| {
+                !ace.header()
+                    .ace_flags()
+                    .contains(AceHeaderFlags::INHERIT_ONLY_ACE)
+            })
+            .find_map(|ace| match ace {
+                Ace::SYSTEM_MANDATORY_LABEL_ACE { header, sid, .. } => IntegrityLevel::from_sid(sid)
+                    .map(|level| IntegrityLabel::new(level, (*header.mask()).into())),
+                _ => None,
+            })
+    }
+
+    /// returns the process trust label which is stored in the SACL, or
+    /// `None` if the object has no trust label
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let sd = SecurityDescriptor::from_sddl("S:(TL;;0x1200a9;;;S-1-19-512-4096)", None).unwrap();
+    /// let label = sd.process_trust_label().unwrap();
+    /// assert_eq!(label.level().to_string(), "PPL Windows");
+    /// assert_eq!(label.mask(), AccessMask::from(0x1200a9));
+    /// ```
+    pub fn process_trust_label(&self) -> Option<ProcessTrustLabel> {
+        self.sacl()
+            .as_ref()?
+            .ace_list()
+            .iter()
+            .filter(|ace| {
+                !ace.header()
+                    .ace_flags()
+                    .contains(AceHeaderFlags::INHERIT_ONLY_ACE)
+            })
+            .find_map(|ace| match ace {
+                Ace::SYSTEM_PROCESS_TRUST_LABEL_ACE { header, sid, .. } => {
+                    ProtectionLevel::from_sid(sid)
+                        .map(|level| ProcessTrustLabel::new(level, *header.mask()))
+                }
+                _ => None,
+            })
+    }
+
+    /// returns the resource attributes which are stored in the SACL.
+    /// Resource attribute ACEs which cannot be decoded are ignored.
+    ///
+    /// ```
+    /// use sddl::*;
+    /// let sd = SecurityDescriptor::from_sddl(r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#, None).unwrap();
+    /// let attributes = sd.resource_attributes();
+    /// assert_eq!(attributes[0].name(), "Secrecy");
+    /// assert_eq!(attributes[0].values(), &[ClaimValue::UInt64(3)]);
+    /// ```
+    pub fn resource_attributes(&self) -> Vec<ClaimAttribute> {
+        self.sacl()
+            .iter()
+            .flat_map(|sacl| sacl.ace_list())
+            .filter(|ace| {
+                !ace.header()
+                    .ace_flags()
+                    .contains(AceHeaderFlags::INHERIT_ONLY_ACE)
+            })
+            .filter_map(|ace| ace.resource_attribute().ok().flatten())
+            .collect()
     }
 
     pub fn from_sddl(value: &str, domain_rid: Option<&[u32]>) -> Result<Self, crate::Error> {
-        Ok(crate::parser::SecurityDescriptorParser::new().parse(domain_rid, value)?)
+        crate::parser::SecurityDescriptorParser::new()
+            .parse(domain_rid, value)
+            .map_err(|e| crate::SddlParseError::from_lalrpop(value, e))
     }
 }
 
+/// ACEs which have no SDDL representation are written as described in the
+/// `Display` implementation of [`Ace`]. Use [`SecurityDescriptor::to_sddl`]
+/// to obtain a string which can be parsed as SDDL.
 impl Display for SecurityDescriptor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(owner) = self.owner() {
-            write!(f, "{SDDL_OWNER}{SDDL_DELIMINATOR}{owner}")?;
+        let Ok(sddl) = self.sddl_string_with(&display_string(None));
+        f.write_str(&sddl)
+    }
+}
+
+impl BinWrite for SecurityDescriptor {
+    type Args<'a> = ();
+
+    /// writes the security descriptor in self-relative format. The SACL,
+    /// the DACL, the owner and the group are stored in this order after the
+    /// header, each of them aligned to a 4-byte boundary.
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let mut flags = self.flags | ControlFlags::SelfRelative;
+        flags.set(ControlFlags::SystemAclPresent, self.sacl.is_some());
+        // a NULL DACL is present, but has no offset
+        flags.set(
+            ControlFlags::DiscretionaryAclPresent,
+            self.dacl.is_some() || self.has_null_dacl(),
+        );
+
+        let mut next_offset = SECURITY_DESCRIPTOR_HEADER_SIZE;
+        let mut offset_of = |size: Option<u16>| match size {
+            Some(size) => {
+                let offset = next_offset;
+                next_offset = align4(offset + u32::from(size));
+                offset
+            }
+            None => 0,
+        };
+        let sacl_offset = offset_of(self.sacl.as_ref().map(RawSize::raw_size));
+        let dacl_offset = offset_of(self.dacl.as_ref().map(RawSize::raw_size));
+        let owner_offset = offset_of(self.owner.as_ref().map(RawSize::raw_size));
+        let group_offset = offset_of(self.group.as_ref().map(RawSize::raw_size));
+
+        writer.write_type(&self.revision, endian)?;
+        writer.write_type(&0u8, endian)?;
+        writer.write_type(&flags, endian)?;
+        writer.write_type(&owner_offset, endian)?;
+        writer.write_type(&group_offset, endian)?;
+        writer.write_type(&sacl_offset, endian)?;
+        writer.write_type(&dacl_offset, endian)?;
+
+        if let Some(sacl) = &self.sacl {
+            sacl.write_options(writer, endian, (flags, AclType::SACL))?;
+            write_padding(writer, sacl.raw_size())?;
         }
-        if let Some(group) = self.group() {
-            write!(f, "{SDDL_GROUP}{SDDL_DELIMINATOR}{group}")?;
+        if let Some(dacl) = &self.dacl {
+            dacl.write_options(writer, endian, (flags, AclType::DACL))?;
+            write_padding(writer, dacl.raw_size())?;
         }
-        if let Some(sacl) = self.sacl_as_sddl_string() {
-            write!(f, "{sacl}")?;
+        if let Some(owner) = &self.owner {
+            owner.write_options(writer, endian, ())?;
+            write_padding(writer, owner.raw_size())?;
         }
-        if let Some(dacl) = self.dacl_as_sddl_string() {
-            write!(f, "{dacl}")?;
+        if let Some(group) = &self.group {
+            group.write_options(writer, endian, ())?;
+            write_padding(writer, group.raw_size())?;
         }
         Ok(())
     }
 }
 
+fn align4(offset: u32) -> u32 {
+    (offset + 3) & !3
+}
+
+fn write_padding<W: std::io::Write>(writer: &mut W, size: u16) -> binrw::BinResult<()> {
+    let padding = align4(u32::from(size)) - u32::from(size);
+    writer.write_all(&[0u8; 3][..padding as usize])?;
+    Ok(())
+}
+
+/// returns the offset of the first position in `bytes` which looks like the
+/// beginning of a self-relative security descriptor, if any
+fn find_header(bytes: &[u8]) -> Option<usize> {
+    if bytes.first() == Some(&1) {
+        return Some(0);
+    }
+    (0..=MAX_PREFIX_SIZE).step_by(4).find(|offset| {
+        // the revision must be 1, followed by a zero byte, and the
+        // SelfRelative flag must be set
+        matches!(bytes.get(*offset..*offset + 4), Some([1, 0, _, high]) if high & 0x80 != 0)
+    })
+}
+
+/// keeps track of the components which have been read by
+/// [`SecurityDescriptor::from_bytes_lenient`]
+struct LenientReader<'d> {
+    /// the data of the security descriptor, without any prefix
+    data: &'d [u8],
+
+    /// the length of the prefix which has been skipped
+    base: usize,
+
+    warnings: Vec<ParseWarning>,
+
+    /// the components which have been read, and the bytes they occupy
+    components: Vec<(SddlComponent, Range<usize>)>,
+}
+
+impl<'d> LenientReader<'d> {
+    /// checks that `offset` can be the beginning of `component` and returns
+    /// the data which starts at this offset
+    fn data_at(&mut self, component: SddlComponent, offset: u32) -> Option<&'d [u8]> {
+        let start = usize::try_from(offset).ok()?;
+        if offset == 0 {
+            return None;
+        }
+        if start >= self.data.len() {
+            self.warnings
+                .push(ParseWarning::OffsetOutOfBounds { component, offset });
+            return None;
+        }
+        if start < SECURITY_DESCRIPTOR_HEADER_SIZE as usize {
+            self.warnings.push(ParseWarning::OverlappingComponents {
+                component,
+                other: None,
+            });
+        }
+        Some(&self.data[start..])
+    }
+
+    /// remembers that `component` occupies the bytes in `range` and warns if
+    /// another component occupies some of these bytes
+    fn occupy(&mut self, component: SddlComponent, range: Range<usize>) {
+        for (other, other_range) in &self.components {
+            if range.start < other_range.end && other_range.start < range.end {
+                self.warnings.push(ParseWarning::OverlappingComponents {
+                    component,
+                    other: Some(*other),
+                });
+            }
+        }
+        self.components.push((component, range));
+    }
+
+    fn sid(&mut self, component: SddlComponent, offset: u32) -> Option<Sid> {
+        let data = self.data_at(component, offset)?;
+        let start = offset as usize;
+        match Cursor::new(data).read_le::<Sid>() {
+            Ok(sid) => {
+                self.occupy(component, start..start + usize::from(sid.raw_size()));
+                Some(sid)
+            }
+            Err(why) => {
+                self.warnings.push(ParseWarning::InvalidComponent {
+                    component,
+                    offset: self.base + start,
+                    reason: why.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn acl(&mut self, acl_type: AclType, offset: u32, flags: ControlFlags) -> Option<Acl> {
+        let component = SddlComponent::from(acl_type);
+        let data = self.data_at(component, offset)?;
+        let start = offset as usize;
+        let acl = Acl::from_bytes_lenient(
+            data,
+            self.base + start,
+            acl_type,
+            flags,
+            &mut self.warnings,
+        )?;
+        let acl_size = u16::from_le_bytes([data[2], data[3]]);
+        let end = (start + usize::from(acl_size)).min(self.data.len());
+        self.occupy(component, start..end);
+        Some(acl)
+    }
+}
+
 impl TryFrom<&[u8]> for SecurityDescriptor {
     type Error = crate::Error;
 
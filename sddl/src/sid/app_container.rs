@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use sha2::{Digest, Sha256};
+
+use super::Sid;
+use crate::constants::APPLICATION_PACKAGE_AUTHORITY;
+
+/// the first sub authority of the SIDs of AppContainers
+const APP_CONTAINER_BASE_RID: u32 = 2;
+
+/// the first sub authority of the SIDs of capabilities
+const CAPABILITY_BASE_RID: u32 = 3;
+
+/// the second sub authority of capability SIDs which are derived from the
+/// name of the capability
+const CAPABILITY_APP_RID: u32 = 1024;
+
+/// capabilities which have a fixed RID (`S-1-15-3-<rid>`), in the order of
+/// their RIDs
+const LEGACY_CAPABILITIES: [&str; 12] = [
+    "internetClient",
+    "internetClientServer",
+    "privateNetworkClientServer",
+    "picturesLibrary",
+    "videosLibrary",
+    "musicLibrary",
+    "documentsLibrary",
+    "enterpriseAuthentication",
+    "sharedUserCertificates",
+    "removableStorage",
+    "appointments",
+    "contacts",
+];
+
+/// names of capabilities which are declared in the manifests of apps or used
+/// by Windows components
+///
+/// <https://learn.microsoft.com/en-us/windows/uwp/packaging/app-capability-declarations>
+#[rustfmt::skip]
+const KNOWN_CAPABILITIES: &[&str] = &[
+    "accessoryManager", "activity", "allAppMods", "allJoyn", "allowElevation",
+    "appBroadcastServices", "appCaptureServices", "appCaptureSettings", "appDiagnostics",
+    "appLicensing", "appointments", "appointmentsSystem", "audioDeviceConfiguration",
+    "backgroundMediaPlayback", "backgroundMediaRecording", "backgroundSpatialPerception",
+    "backgroundVoIP", "blockedChatMessages", "bluetooth", "broadFileSystemAccess",
+    "cameraProcessingExtension", "cellularDeviceControl", "cellularDeviceIdentity",
+    "cellularMessaging", "chat", "chatSystem", "codeGeneration", "confirmAppClose",
+    "contacts", "contactsSystem", "cortanaPermissions", "cortanaSettings",
+    "customInstallActions", "deviceManagementAdministrator", "deviceManagementDmAccount",
+    "deviceManagementEmailAccount", "deviceManagementFoundation",
+    "deviceManagementRegistration", "deviceManagementWapSecurityPolicies",
+    "devicePortalProvider", "deviceUnlock", "documentsLibrary", "dualSimTiles", "email",
+    "emailSystem", "enterpriseAuthentication", "enterpriseCloudSSO",
+    "enterpriseDataPolicy", "enterpriseDeviceLockdown", "expandedResources",
+    "extendedBackgroundTaskTime", "extendedExecutionBackgroundAudio",
+    "extendedExecutionCritical", "extendedExecutionUnconstrained", "eyeTracker",
+    "firstSignInSettings", "gameBarServices", "gameList", "gameMonitor", "gazeInput",
+    "globalMediaControl", "graphicsCapture", "graphicsCaptureProgrammatic",
+    "graphicsCaptureWithoutBorder", "humanInterfaceDevice", "inputForegroundObservation",
+    "inputInjectionBrokered", "inputObservation", "inputSuppression", "internetClient",
+    "internetClientServer", "interopServices", "localSystemServices", "location",
+    "locationHistory", "locationSystem", "lowLevel", "lowLevelDevices",
+    "lpacAppExperience", "lpacAppointments", "lpacClipboard", "lpacCom",
+    "lpacContacts", "lpacCryptoServices", "lpacDeviceAccess",
+    "lpacEnterprisePolicyChangeNotifications",
+    "lpacIdentityServices", "lpacInstrumentation", "lpacMedia",
+    "lpacPackageManagerOperation", "lpacPayments", "lpacPnPNotifications", "lpacPrinting",
+    "lpacServicesManagement", "lpacSessionManagement", "lpacWebPlatform", "microphone",
+    "modifiableApp", "musicLibrary", "networkConnectionManagerProvisioning",
+    "networkDataPlanProvisioning", "networkingVpnProvider", "objects3D", "oemDeployment",
+    "oemPublicDirectory", "optical", "packageManagement", "packagePolicySystem",
+    "packageQuery", "packageWriteRedirectionCompatibilityShim", "phoneCall",
+    "phoneCallHistory", "phoneCallHistoryPublic", "phoneCallHistorySystem",
+    "picturesLibrary", "pointOfService", "previewInkWorkspace", "previewPenWorkspace",
+    "previewStore", "previewUiComposition", "privateNetworkClientServer", "protectedApp",
+    "proximity", "radios", "recordedCallsFolder", "registryRead", "remoteSystem",
+    "removableStorage", "runFullTrust", "screenDuplication", "secondaryAuthenticationFactor",
+    "sharedUserCertificates", "slapiQueryLicenseValue", "smbios", "smsSend",
+    "spatialPerception", "startScreenManagement", "storeLicenseManagement",
+    "systemManagement", "targetedContent", "teamEditionDeviceCredential",
+    "teamEditionExperience", "teamEditionView", "uiAutomation", "unvirtualizedResources",
+    "userAccountInformation", "userDataAccountsProvider", "userDataSystem", "userDataTasks",
+    "userNotificationListener", "userPrincipalName", "userSigninSupport", "usb",
+    "videosLibrary", "voipCall", "walletSystem", "webcam", "wiFiControl",
+    "xboxAccessoryManagement",
+];
+
+lazy_static! {
+    /// maps the sub authorities of known capability SIDs to their names
+    static ref CAPABILITY_NAMES: HashMap<Vec<u32>, String> = {
+        let mut names: HashMap<Vec<u32>, String> = KNOWN_CAPABILITIES
+            .iter()
+            .map(|name| (capability_rids(name), format!(r"NAMED CAPABILITIES\{name}")))
+            .collect();
+        for (rid, name) in (1..).zip(LEGACY_CAPABILITIES) {
+            names.insert(
+                vec![CAPABILITY_BASE_RID, rid],
+                format!(r"NAMED CAPABILITIES\{name}"),
+            );
+        }
+        names
+    };
+}
+
+/// hashes the UTF-16LE encoding of `name` and splits the hash into sub
+/// authorities
+fn hash_rids(name: &str) -> Vec<u32> {
+    let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    Sha256::digest(name)
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+fn capability_rids(name: &str) -> Vec<u32> {
+    let mut rids = vec![CAPABILITY_BASE_RID, CAPABILITY_APP_RID];
+    rids.extend(hash_rids(&name.to_uppercase()));
+    rids
+}
+
+impl Sid {
+    /// derives the SID of an AppContainer from the package family name
+    /// (e.g. `Microsoft.WindowsCalculator_8wekyb3d8bbwe`), like
+    /// `DeriveAppContainerSidFromAppContainerName` does
+    ///
+    /// ```
+    /// use sddl::Sid;
+    /// let sid = Sid::app_container_sid("Microsoft.WindowsCalculator_8wekyb3d8bbwe");
+    /// assert_eq!(
+    ///     sid.to_string(),
+    ///     "S-1-15-2-466767348-3739614953-2700836392-1801644223-4227750657-1087833535-2488631167"
+    /// );
+    /// assert!(sid.is_app_container());
+    /// ```
+    pub fn app_container_sid(package_family_name: &str) -> Self {
+        let mut rids = vec![APP_CONTAINER_BASE_RID];
+        rids.extend(&hash_rids(&package_family_name.to_lowercase())[..7]);
+        Self::new(APPLICATION_PACKAGE_AUTHORITY, &rids)
+    }
+
+    /// derives the SID of a capability from its name (`S-1-15-3-1024-*`),
+    /// like `DeriveCapabilitySidsFromName` does
+    pub fn capability_sid(name: &str) -> Self {
+        Self::new(APPLICATION_PACKAGE_AUTHORITY, &capability_rids(name))
+    }
+
+    /// returns `true` if this is the SID of an AppContainer or of a child of
+    /// an AppContainer
+    pub fn is_app_container(&self) -> bool {
+        *self.identifier_authority() == APPLICATION_PACKAGE_AUTHORITY
+            && self.sub_authority().first() == Some(&APP_CONTAINER_BASE_RID)
+            && matches!(self.sub_authority().len(), 8 | 12)
+    }
+
+    /// returns `true` if this is the SID of a capability
+    pub fn is_capability(&self) -> bool {
+        *self.identifier_authority() == APPLICATION_PACKAGE_AUTHORITY
+            && self.sub_authority().first() == Some(&CAPABILITY_BASE_RID)
+            && self.sub_authority().len() > 1
+    }
+
+    /// returns the name of a capability SID, if it is one of the capabilities
+    /// which are known to this crate
+    ///
+    /// ```
+    /// use sddl::Sid;
+    /// assert_eq!(
+    ///     Sid::capability_sid("webcam").capability_name(),
+    ///     Some(r"NAMED CAPABILITIES\webcam")
+    /// );
+    /// ```
+    pub fn capability_name(&self) -> Option<&'static str> {
+        if self.is_capability() {
+            Self::known_capability_name(self.sub_authority())
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn known_capability_name(sub_authority: &[u32]) -> Option<&'static str> {
+        CAPABILITY_NAMES.get(sub_authority).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sid;
+
+    #[test]
+    fn test_capability_sids() {
+        let sid = Sid::capability_sid("internetClient");
+        assert_eq!(&sid.sub_authority()[..2], &[3, 1024]);
+        assert_eq!(sid.sub_authority().len(), 10);
+        assert_eq!(sid, Sid::capability_sid("INTERNETCLIENT"));
+        assert!(sid.is_capability());
+        assert!(!sid.is_app_container());
+        assert_eq!(
+            sid.capability_name(),
+            Some(r"NAMED CAPABILITIES\internetClient")
+        );
+        assert_eq!(*sid.well_known_name(), sid.capability_name());
+
+        let legacy = Sid::try_from("S-1-15-3-1").unwrap();
+        assert!(legacy.is_capability());
+        assert_eq!(
+            legacy.capability_name(),
+            Some(r"NAMED CAPABILITIES\internetClient")
+        );
+
+        assert_eq!(
+            Sid::capability_sid("someUnknownCapability").capability_name(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_classification() {
+        let all_app_packages = Sid::try_from("S-1-15-2-1").unwrap();
+        assert!(!all_app_packages.is_app_container());
+        assert!(!all_app_packages.is_capability());
+
+        let child = Sid::try_from("S-1-15-2-1-2-3-4-5-6-7-8-9-10-11").unwrap();
+        assert!(child.is_app_container());
+
+        let builtin = Sid::try_from("S-1-5-32-544").unwrap();
+        assert!(!builtin.is_app_container());
+        assert!(!builtin.is_capability());
+        assert_eq!(builtin.capability_name(), None);
+    }
+}
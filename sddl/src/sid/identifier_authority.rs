@@ -52,6 +52,7 @@ pub mod constants {
     predefined_authority!(APPLICATION_PACKAGE_AUTHORITY, 15);
     predefined_authority!(MANDATORY_LABEL_AUTHORITY, 16);
     predefined_authority!(AUTHENTICATION_AUTHORITY, 18);
+    predefined_authority!(PROCESS_TRUST_AUTHORITY, 19);
 }
 
 impl IdentifierAuthority {
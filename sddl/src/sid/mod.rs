@@ -5,19 +5,24 @@ use std::str::FromStr;
 use binrw::binrw;
 use getset::Getters;
 
+mod app_container;
 mod identifier_authority;
 mod sid_alias;
 pub use sid_alias::*;
 
 mod sid_name_use;
+mod virtual_account;
+mod well_known_sid;
+pub use virtual_account::*;
 
 pub use identifier_authority::constants::*;
 pub use identifier_authority::*;
 use lazy_regex::regex_captures;
+use serde::de::{self, MapAccess, Visitor};
 use serde::ser::SerializeStruct;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{sddl_h::*, RawSize};
+use crate::{sddl_h::*, NameContext, RawSize};
 
 pub const MAX_SUB_AUTHORITIES: u8 = 15;
 
@@ -49,7 +54,7 @@ pub struct Sid {
     alias: Option<SidAlias>,
 
     #[bw(ignore)]
-    #[br(calc=alias.map(|a| a.long_name()))]
+    #[br(calc=Self::well_known_name_of(&identifier_authority, &sub_authority, alias))]
     well_known_name: Option<&'static str>,
 }
 
@@ -58,9 +63,11 @@ impl Display for Sid {
         let revision = self.revision();
         let identifier_authority = self.identifier_authority();
 
-        let mut sub_authorities = Vec::new();
         let mut iter = self.sub_authority().iter();
-        sub_authorities.push(iter.next().unwrap().to_string());
+        let Some(first) = iter.next() else {
+            return write!(f, "S-{revision}-{identifier_authority}");
+        };
+        let mut sub_authorities = vec![first.to_string()];
 
         // the first and last sub authority will have no leading 0s
         if let Some(mut current) = iter.next() {
@@ -81,7 +88,12 @@ impl Serialize for Sid {
     where
         S: serde::Serializer,
     {
-        let mut ser = serializer.serialize_struct("SID", 3)?;
+        let name = NameContext::with_active(|context| {
+            context
+                .and_then(|context| context.sid_name(self))
+                .map(str::to_owned)
+        });
+        let mut ser = serializer.serialize_struct("SID", 3 + usize::from(name.is_some()))?;
         ser.serialize_field("sid", &serde_json::Value::String(self.to_string()))?;
         ser.serialize_field("alias", &self.alias())?;
         ser.serialize_field(
@@ -91,10 +103,57 @@ impl Serialize for Sid {
                 .map(|a| a.into())
                 .unwrap_or(serde_json::Value::Null),
         )?;
+        if let Some(name) = name {
+            ser.serialize_field("name", &name)?;
+        }
         ser.end()
     }
 }
 
+/// accepts either a plain SID string (`"S-1-5-32-544"`) or the structure
+/// which is created by the `Serialize` implementation
+impl<'de> Deserialize<'de> for Sid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SidVisitor;
+
+        impl<'de> Visitor<'de> for SidVisitor {
+            type Value = Sid;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a SID string or a SID structure")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Sid::try_from(v).map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut sid = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "sid" {
+                        let value: String = map.next_value()?;
+                        sid = Some(Sid::try_from(&value[..]).map_err(de::Error::custom)?);
+                    } else {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+                sid.ok_or_else(|| de::Error::missing_field("sid"))
+            }
+        }
+
+        deserializer.deserialize_any(SidVisitor)
+    }
+}
+
 impl RawSize for Sid {
     fn raw_size(&self) -> u16 {
         self.len().try_into().unwrap()
@@ -104,7 +163,7 @@ impl RawSize for Sid {
 impl Sid {
     pub fn new(identifier_authority: IdentifierAuthority, sub_authority: &[u32]) -> Self {
         let alias = Self::sddl_alias(&identifier_authority, sub_authority);
-        let well_known_name = alias.map(|a| a.long_name());
+        let well_known_name = Self::well_known_name_of(&identifier_authority, sub_authority, alias);
         Self {
             revision: 1,
             sub_authority_count: sub_authority.len() as u8,
@@ -197,6 +256,18 @@ impl Sid {
         }
     }
 
+    /// prefers the long name of the SDDL alias over the name from the table
+    /// of well-known SIDs
+    fn well_known_name_of(
+        identifier_authority: &IdentifierAuthority,
+        sub_authority: &[u32],
+        alias: Option<SidAlias>,
+    ) -> Option<&'static str> {
+        alias
+            .map(|a| a.long_name())
+            .or_else(|| Self::well_known_sid_name(identifier_authority, sub_authority))
+    }
+
     fn sddl_domain_alias(sub_authority: &[u32]) -> Option<SidAlias> {
         assert_eq!(*sub_authority.first().unwrap(), 21);
         if let Some(last) = sub_authority.last() {
@@ -226,7 +297,10 @@ impl Sid {
     }
 
     fn sddl_builtin_alias(sub_authority: &[u32]) -> Option<SidAlias> {
-        assert_eq!(sub_authority.len(), 2);
+        // only S-1-5-32-<rid> refers to a builtin group
+        if sub_authority.len() != 2 {
+            return None;
+        }
         assert_eq!(*sub_authority.first().unwrap(), 32);
 
         if let Some(last) = sub_authority.last() {
@@ -280,11 +354,17 @@ impl TryFrom<&str> for Sid {
                 Self::Error::IllegalSidFormat(value.into(), "illegal revision number")
             })?;
 
-            let authority: [u8; 8] = u64::from_str(authority)
-                .map_err(|_| {
-                    Self::Error::IllegalSidFormat(value.into(), "invalid authority format")
-                })?
-                .to_be_bytes();
+            let authority = u64::from_str(authority).map_err(|_| {
+                Self::Error::IllegalSidFormat(value.into(), "invalid authority format")
+            })?;
+            // the identifier authority is a 48 bit value
+            if authority > 0xFFFF_FFFF_FFFF {
+                return Err(Self::Error::IllegalSidFormat(
+                    value.into(),
+                    "identifier authority exceeds 48 bits",
+                ));
+            }
+            let authority = authority.to_be_bytes();
             let identifier_authority = [
                 authority[2],
                 authority[3],
@@ -321,7 +401,8 @@ impl TryFrom<&str> for Sid {
                 Self::Error::IllegalSidFormat(value.into(), "illegal number of sub authorities")
             })?;
             let alias = Self::sddl_alias(&identifier_authority, &sub_authority);
-            let well_known_name = alias.map(|a| a.long_name());
+            let well_known_name =
+                Self::well_known_name_of(&identifier_authority, &sub_authority, alias);
             Ok(Self {
                 revision,
                 identifier_authority,
@@ -356,6 +437,15 @@ mod tests {
         assert_eq!(my_sid, Sid::try_from(my_sid).unwrap().to_string());
     }
 
+    #[test]
+    fn test_identifier_authority_range() {
+        let sid = Sid::try_from("S-1-281474976710655-1").unwrap();
+        assert_eq!(sid.identifier_authority().to_string(), "281474976710655");
+        for sid in ["S-1-281474976710656-1", "S-1-99999999999999999-1"] {
+            assert!(Sid::try_from(sid).is_err(), "{sid}");
+        }
+    }
+
     #[test]
     fn test_domain_sid() {
         let my_sid = "S-1-5-21-2623811015-3361044348-030300820-1013";
@@ -5,7 +5,7 @@ use strum_macros::EnumString;
 #[derive(Clone, Copy, Debug, Eq, PartialEq, EnumString, EnumMessage, IntoStaticStr, Hash)]
 #[strum(use_phf, serialize_all = "SCREAMING_SNAKE_CASE")]
 pub enum SidAlias {
-    #[strum(message=r"BUILTIN\Access Control Assistence Operators")]
+    #[strum(message=r"BUILTIN\Access Control Assistance Operators")]
     AA,
 
     #[strum(message=r"APPLICATION PACKAGE AUTHORITY\ALL APPLICATION PACKAGES")]
@@ -29,7 +29,7 @@ pub enum SidAlias {
     #[strum(message=r"BUILTIN\Administrators")]
     BA,
 
-    #[strum(message=r"UILTIN\Guest")]
+    #[strum(message=r"BUILTIN\Guests")]
     BG,
 
     #[strum(message=r"BUILTIN\Backup Operators")]
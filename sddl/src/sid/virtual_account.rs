@@ -0,0 +1,195 @@
+use sha1::{Digest, Sha1};
+use strum::Display;
+
+use super::Sid;
+use crate::constants::SECURITY_NT_AUTHORITY;
+use crate::Guid;
+
+/// accounts whose SIDs are derived from their names, such as the SIDs which
+/// `sc showsid` displays
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Display)]
+pub enum VirtualAccountKind {
+    /// `NT SERVICE\<name>` (`S-1-5-80-*`)
+    #[strum(serialize = "NT SERVICE")]
+    Service,
+
+    /// `IIS APPPOOL\<name>` (`S-1-5-82-*`)
+    #[strum(serialize = "IIS APPPOOL")]
+    AppPool,
+}
+
+impl VirtualAccountKind {
+    /// the first sub authority of the SIDs of this kind
+    pub fn rid(&self) -> u32 {
+        match self {
+            Self::Service => 80,
+            Self::AppPool => 82,
+        }
+    }
+
+    /// returns the kind of accounts which `sid` belongs to, if `sid` has
+    /// been derived from a name
+    pub fn of(sid: &Sid) -> Option<Self> {
+        if *sid.identifier_authority() != SECURITY_NT_AUTHORITY {
+            return None;
+        }
+        match sid.sub_authority()[..] {
+            [80, _, _, _, _, _] => Some(Self::Service),
+            [82, _, _, _, _, _] => Some(Self::AppPool),
+            _ => None,
+        }
+    }
+
+    /// Windows hashes the uppercase names of services, but the lowercase
+    /// names of application pools
+    fn normalize(&self, name: &str) -> String {
+        match self {
+            Self::Service => name.to_uppercase(),
+            Self::AppPool => name.to_lowercase(),
+        }
+    }
+}
+
+impl Sid {
+    /// derives the SID of a virtual account from the SHA-1 hash of its name,
+    /// encoded as UTF-16LE
+    pub fn virtual_account_sid(kind: VirtualAccountKind, name: &str) -> Self {
+        let name: Vec<u8> = kind
+            .normalize(name)
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let hash = Sha1::digest(name);
+
+        let mut sub_authority = vec![kind.rid()];
+        sub_authority.extend(
+            hash.chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())),
+        );
+        Self::new(SECURITY_NT_AUTHORITY, &sub_authority)
+    }
+
+    /// returns the SID of `NT SERVICE\<name>`
+    ///
+    /// ```
+    /// use sddl::Sid;
+    /// assert_eq!(
+    ///     Sid::service_sid("TrustedInstaller").to_string(),
+    ///     "S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464"
+    /// );
+    /// ```
+    pub fn service_sid(name: &str) -> Self {
+        Self::virtual_account_sid(VirtualAccountKind::Service, name)
+    }
+
+    /// returns the SID of `IIS APPPOOL\<name>`
+    pub fn app_pool_sid(name: &str) -> Self {
+        Self::virtual_account_sid(VirtualAccountKind::AppPool, name)
+    }
+
+    /// returns the SID of the Hyper-V virtual machine with the id `vm_id`
+    /// (`S-1-5-83-1-*`), which is not a hash, but the binary representation
+    /// of the id
+    pub fn virtual_machine_sid(vm_id: &Guid) -> Self {
+        let mut sub_authority = vec![83, 1];
+        sub_authority.extend(
+            vm_id
+                .to_bytes()
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())),
+        );
+        Self::new(SECURITY_NT_AUTHORITY, &sub_authority)
+    }
+
+    /// returns the id of the virtual machine, if this is the SID of a
+    /// Hyper-V virtual machine
+    pub fn virtual_machine_id(&self) -> Option<Guid> {
+        if *self.identifier_authority() != SECURITY_NT_AUTHORITY {
+            return None;
+        }
+        match self.sub_authority()[..] {
+            [83, 1, a, b, c, d] => {
+                let mut bytes = [0; 16];
+                for (chunk, value) in bytes.chunks_exact_mut(4).zip([a, b, c, d]) {
+                    chunk.copy_from_slice(&value.to_le_bytes());
+                }
+                Some(Guid::from_bytes(bytes))
+            }
+            _ => None,
+        }
+    }
+
+    /// searches `candidates` for the name from which this SID has been
+    /// derived, and returns the full account name (e.g.
+    /// `NT SERVICE\TrustedInstaller`)
+    ///
+    /// ```
+    /// use sddl::Sid;
+    /// let sid = Sid::try_from("S-1-5-80-1913148863-3492339771-4165695881-2087618961-4109116736").unwrap();
+    /// assert_eq!(
+    ///     sid.find_virtual_account_name(["Spooler", "WinDefend"]).as_deref(),
+    ///     Some(r"NT SERVICE\WinDefend")
+    /// );
+    /// ```
+    pub fn find_virtual_account_name<'n>(
+        &self,
+        candidates: impl IntoIterator<Item = &'n str>,
+    ) -> Option<String> {
+        let kind = VirtualAccountKind::of(self)?;
+        candidates
+            .into_iter()
+            .find(|name| Self::virtual_account_sid(kind, name) == *self)
+            .map(|name| format!(r"{kind}\{name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Guid, Sid, VirtualAccountKind};
+
+    #[test]
+    fn test_app_pool_sid() {
+        assert_eq!(
+            Sid::app_pool_sid("DefaultAppPool").to_string(),
+            "S-1-5-82-3006700770-424185619-1745488364-794895919-4004696415"
+        );
+    }
+
+    #[test]
+    fn test_service_names_are_case_insensitive() {
+        assert_eq!(
+            Sid::service_sid("trustedinstaller"),
+            Sid::service_sid("TRUSTEDINSTALLER")
+        );
+        assert_eq!(
+            VirtualAccountKind::of(&Sid::service_sid("Spooler")),
+            Some(VirtualAccountKind::Service)
+        );
+    }
+
+    #[test]
+    fn test_virtual_machine_sid() {
+        let vm_id = Guid::try_from("6f0f4bb1-32d5-4a4f-9b64-0ab2c1d3e4f5").unwrap();
+        let sid = Sid::virtual_machine_sid(&vm_id);
+        assert_eq!(&sid.sub_authority()[..2], &[83, 1]);
+        assert_eq!(sid.virtual_machine_id(), Some(vm_id));
+        assert_eq!(Sid::service_sid("vmms").virtual_machine_id(), None);
+    }
+
+    #[test]
+    fn test_reverse_lookup() {
+        let sid = Sid::app_pool_sid("Intranet");
+        assert_eq!(
+            sid.find_virtual_account_name(["DefaultAppPool", "intranet"])
+                .as_deref(),
+            Some(r"IIS APPPOOL\intranet")
+        );
+        assert_eq!(sid.find_virtual_account_name(["DefaultAppPool"]), None);
+        assert_eq!(
+            Sid::try_from("S-1-5-32-544")
+                .unwrap()
+                .find_virtual_account_name(["Administrators"]),
+            None
+        );
+    }
+}
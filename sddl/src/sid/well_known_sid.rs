@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use super::{IdentifierAuthority, Sid};
+use crate::constants::*;
+
+/// `S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464`
+const TRUSTED_INSTALLER: [u32; 6] = [
+    80, 956008885, 3418522649, 1831038044, 1853292631, 2271478464,
+];
+
+lazy_static! {
+    /// the names of the per-session accounts, which are created on demand.
+    /// Every name is only allocated once, so that it can be stored in
+    /// [`Sid::well_known_name`].
+    static ref SESSION_NAMES: Mutex<HashMap<(&'static str, u32), &'static str>> =
+        Mutex::new(HashMap::new());
+}
+
+/// returns the name of the account `<prefix><session>`, which exists for
+/// every logon session
+fn session_name(prefix: &'static str, session: u32) -> &'static str {
+    let mut names = SESSION_NAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    names
+        .entry((prefix, session))
+        .or_insert_with(|| Box::leak(format!("{prefix}{session}").into_boxed_str()))
+}
+
+impl Sid {
+    /// returns the name of a well-known SID, as listed in
+    /// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/81d92bba-d22b-4a8c-908a-554ab29148ab>
+    /// and <https://learn.microsoft.com/en-us/windows/win32/secauthz/well-known-sids>,
+    /// regardless of whether there is an SDDL alias for it
+    pub fn well_known_sid_name(
+        identifier_authority: &IdentifierAuthority,
+        sub_authority: &[u32],
+    ) -> Option<&'static str> {
+        match *identifier_authority {
+            // S-1-0-*
+            SECURITY_NULL_SID_AUTHORITY if sub_authority == [0] => Some("NULL SID"),
+
+            // S-1-1-*
+            SECURITY_WORLD_SID_AUTHORITY if sub_authority == [0] => Some("Everyone"),
+
+            // S-1-2-*
+            SECURITY_LOCAL_SID_AUTHORITY => match sub_authority {
+                [0] => Some("LOCAL"),
+                [1] => Some("CONSOLE LOGON"),
+                _ => None,
+            },
+
+            // S-1-3-*
+            SECURITY_CREATOR_SID_AUTHORITY => match sub_authority {
+                [0] => Some("CREATOR OWNER"),
+                [1] => Some("CREATOR GROUP"),
+                [2] => Some("CREATOR OWNER SERVER"),
+                [3] => Some("CREATOR GROUP SERVER"),
+                [4] => Some("OWNER RIGHTS"),
+                _ => None,
+            },
+
+            // S-1-5-*
+            SECURITY_NT_AUTHORITY => Self::nt_authority_name(sub_authority),
+
+            // S-1-15-*
+            APPLICATION_PACKAGE_AUTHORITY => match sub_authority {
+                [2, 1] => Some(r"APPLICATION PACKAGE AUTHORITY\ALL APPLICATION PACKAGES"),
+                [2, 2] => {
+                    Some(r"APPLICATION PACKAGE AUTHORITY\ALL RESTRICTED APPLICATION PACKAGES")
+                }
+                [3, ..] => Self::known_capability_name(sub_authority),
+                _ => None,
+            },
+
+            // S-1-16-*
+            MANDATORY_LABEL_AUTHORITY => match sub_authority {
+                [0] => Some(r"Mandatory Label\Untrusted Mandatory Level"),
+                [4096] => Some(r"Mandatory Label\Low Mandatory Level"),
+                [8192] => Some(r"Mandatory Label\Medium Mandatory Level"),
+                [8448] => Some(r"Mandatory Label\Medium Plus Mandatory Level"),
+                [12288] => Some(r"Mandatory Label\High Mandatory Level"),
+                [16384] => Some(r"Mandatory Label\System Mandatory Level"),
+                [20480] => Some(r"Mandatory Label\Protected Process Mandatory Level"),
+                [28672] => Some(r"Mandatory Label\Secure Process Mandatory Level"),
+                _ => None,
+            },
+
+            // S-1-18-*
+            AUTHENTICATION_AUTHORITY => match sub_authority {
+                [1] => Some("Authentication authority asserted identity"),
+                [2] => Some("Service asserted identity"),
+                [3] => Some("Fresh public key identity"),
+                [4] => Some("Key trust identity"),
+                [5] => Some("Key property MFA"),
+                [6] => Some("Key property attestation"),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn nt_authority_name(sub_authority: &[u32]) -> Option<&'static str> {
+        match sub_authority {
+            [1] => Some(r"NT AUTHORITY\DIALUP"),
+            [2] => Some(r"NT AUTHORITY\NETWORK"),
+            [3] => Some(r"NT AUTHORITY\BATCH"),
+            [4] => Some(r"NT AUTHORITY\INTERACTIVE"),
+            [5, _, _] => Some(r"NT AUTHORITY\LogonSessionId"),
+            [6] => Some(r"NT AUTHORITY\SERVICE"),
+            [7] => Some(r"NT AUTHORITY\ANONYMOUS LOGON"),
+            [8] => Some(r"NT AUTHORITY\PROXY"),
+            [9] => Some(r"NT AUTHORITY\ENTERPRISE DOMAIN CONTROLLERS"),
+            [10] => Some(r"NT AUTHORITY\SELF"),
+            [11] => Some(r"NT AUTHORITY\Authenticated Users"),
+            [12] => Some(r"NT AUTHORITY\RESTRICTED"),
+            [13] => Some(r"NT AUTHORITY\TERMINAL SERVER USER"),
+            [14] => Some(r"NT AUTHORITY\REMOTE INTERACTIVE LOGON"),
+            [15] => Some(r"NT AUTHORITY\This Organization"),
+            [17] => Some(r"NT AUTHORITY\IUSR"),
+            [18] => Some(r"NT AUTHORITY\SYSTEM"),
+            [19] => Some(r"NT AUTHORITY\LOCAL SERVICE"),
+            [20] => Some(r"NT AUTHORITY\NETWORK SERVICE"),
+            [22] => Some(r"NT AUTHORITY\ENTERPRISE READ-ONLY DOMAIN CONTROLLERS BETA"),
+            [33] => Some(r"NT AUTHORITY\WRITE RESTRICTED"),
+            [32] => Some("BUILTIN"),
+            [32, rid] => Self::builtin_name(*rid),
+            [64, 10] => Some(r"NT AUTHORITY\NTLM Authentication"),
+            [64, 14] => Some(r"NT AUTHORITY\SChannel Authentication"),
+            [64, 21] => Some(r"NT AUTHORITY\Digest Authentication"),
+            [65, 1] => Some(r"NT AUTHORITY\This Organization Certificate"),
+            [80, 0] => Some(r"NT SERVICE\ALL SERVICES"),
+            sub_authority if sub_authority == TRUSTED_INSTALLER => {
+                Some(r"NT SERVICE\TrustedInstaller")
+            }
+            [83, 0] => Some(r"NT VIRTUAL MACHINE\Virtual Machines"),
+            [84, 0, 0, 0, 0, 0] => Some(r"NT AUTHORITY\USER MODE DRIVERS"),
+            [90, 0] => Some(r"Window Manager\Window Manager Group"),
+            [90, 0, session] => Some(session_name(r"Window Manager\DWM-", *session)),
+            [96, 0, session] => Some(session_name(r"Font Driver Host\UMFD-", *session)),
+            [113] => Some(r"NT AUTHORITY\Local account"),
+            [114] => Some(r"NT AUTHORITY\Local account and member of Administrators group"),
+            [1000] => Some(r"NT AUTHORITY\Other Organization"),
+            _ => None,
+        }
+    }
+
+    /// names of the groups `S-1-5-32-<rid>`
+    fn builtin_name(rid: u32) -> Option<&'static str> {
+        match rid {
+            544 => Some(r"BUILTIN\Administrators"),
+            545 => Some(r"BUILTIN\Users"),
+            546 => Some(r"BUILTIN\Guests"),
+            547 => Some(r"BUILTIN\Power Users"),
+            548 => Some(r"BUILTIN\Account Operators"),
+            549 => Some(r"BUILTIN\Server Operators"),
+            550 => Some(r"BUILTIN\Print Operators"),
+            551 => Some(r"BUILTIN\Backup Operators"),
+            552 => Some(r"BUILTIN\Replicator"),
+            554 => Some(r"BUILTIN\Pre-Windows 2000 Compatible Access"),
+            555 => Some(r"BUILTIN\Remote Desktop Users"),
+            556 => Some(r"BUILTIN\Network Configuration Operators"),
+            557 => Some(r"BUILTIN\Incoming Forest Trust Builders"),
+            558 => Some(r"BUILTIN\Performance Monitor Users"),
+            559 => Some(r"BUILTIN\Performance Log Users"),
+            560 => Some(r"BUILTIN\Windows Authorization Access Group"),
+            561 => Some(r"BUILTIN\Terminal Server License Servers"),
+            562 => Some(r"BUILTIN\Distributed COM Users"),
+            568 => Some(r"BUILTIN\IIS_IUSRS"),
+            569 => Some(r"BUILTIN\Cryptographic Operators"),
+            573 => Some(r"BUILTIN\Event Log Readers"),
+            574 => Some(r"BUILTIN\Certificate Service DCOM Access"),
+            575 => Some(r"BUILTIN\RDS Remote Access Servers"),
+            576 => Some(r"BUILTIN\RDS Endpoint Servers"),
+            577 => Some(r"BUILTIN\RDS Management Servers"),
+            578 => Some(r"BUILTIN\Hyper-V Administrators"),
+            579 => Some(r"BUILTIN\Access Control Assistance Operators"),
+            580 => Some(r"BUILTIN\Remote Management Users"),
+            582 => Some(r"BUILTIN\Storage Replica Administrators"),
+            583 => Some(r"BUILTIN\Device Owners"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sid;
+
+    fn name(sid: &str) -> Option<&'static str> {
+        *Sid::try_from(sid).unwrap().well_known_name()
+    }
+
+    #[test]
+    fn test_sids_without_alias() {
+        for (sid, expected) in [
+            (
+                "S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464",
+                r"NT SERVICE\TrustedInstaller",
+            ),
+            ("S-1-5-113", r"NT AUTHORITY\Local account"),
+            ("S-1-16-0", r"Mandatory Label\Untrusted Mandatory Level"),
+            ("S-1-5-64-10", r"NT AUTHORITY\NTLM Authentication"),
+            ("S-1-5-90-0", r"Window Manager\Window Manager Group"),
+            ("S-1-5-90-0-3", r"Window Manager\DWM-3"),
+            ("S-1-5-96-0-3", r"Font Driver Host\UMFD-3"),
+            ("S-1-2-0", "LOCAL"),
+            ("S-1-5-32-562", r"BUILTIN\Distributed COM Users"),
+        ] {
+            assert_eq!(name(sid), Some(expected), "{sid}");
+            assert!(Sid::try_from(sid).unwrap().alias().is_none(), "{sid}");
+        }
+    }
+
+    #[test]
+    fn test_alias_names_have_priority() {
+        assert_eq!(name("S-1-5-21-1-2-3-512"), Some(r"<DOMAIN>\Domain Admins"));
+        assert_eq!(name("S-1-5-32-546"), Some(r"BUILTIN\Guests"));
+        assert_eq!(name("S-1-5-32-562"), Some(r"BUILTIN\Distributed COM Users"));
+    }
+
+    #[test]
+    fn test_unknown_sids() {
+        assert_eq!(name("S-1-5-80-1-2-3-4-5"), None);
+        assert_eq!(name("S-1-5-21-1-2-3-1104"), None);
+        assert_eq!(name("S-1-5-32-544-1"), None);
+
+        // the names of domain accounts depend on the domain
+        assert_eq!(name("S-1-5-21-1-2-3-502"), None);
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use bitflags::bitflags;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::{Acl, ClaimValue, EvaluationContext, IntegrityLevel, Sid};
+
+bitflags! {
+    /// <https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-sid_and_attributes>
+    #[derive(Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+    pub struct GroupAttributes: u32 {
+        /// The group cannot be disabled
+        const MANDATORY = 0x00000001;
+
+        /// The group is enabled by default
+        const ENABLED_BY_DEFAULT = 0x00000002;
+
+        /// The group is enabled for access checks
+        const ENABLED = 0x00000004;
+
+        /// The group can be assigned as owner of new objects
+        const OWNER = 0x00000008;
+
+        /// The group is only used to check against access-denied ACEs
+        const USE_FOR_DENY_ONLY = 0x00000010;
+
+        /// The SID is a mandatory integrity SID
+        const INTEGRITY = 0x00000020;
+        const INTEGRITY_ENABLED = 0x00000040;
+
+        /// The group is a domain-local group
+        const RESOURCE = 0x20000000;
+
+        /// The SID is a logon session SID
+        const LOGON_ID = 0xC0000000;
+
+        const _ = !0;
+    }
+}
+
+/// A SID in a token, together with its attributes
+#[derive(Eq, PartialEq, Debug, Clone, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct TokenGroup {
+    sid: Sid,
+
+    #[serde(default = "default_group_attributes")]
+    attributes: GroupAttributes,
+}
+
+fn default_group_attributes() -> GroupAttributes {
+    GroupAttributes::MANDATORY | GroupAttributes::ENABLED_BY_DEFAULT | GroupAttributes::ENABLED
+}
+
+impl TokenGroup {
+    pub fn new(sid: Sid, attributes: GroupAttributes) -> Self {
+        Self { sid, attributes }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.attributes.contains(GroupAttributes::ENABLED)
+    }
+
+    pub fn is_deny_only(&self) -> bool {
+        self.attributes.contains(GroupAttributes::USE_FOR_DENY_ONLY)
+    }
+}
+
+/// Privileges which change the result of an access check
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Serialize, Deserialize, strum::Display)]
+pub enum Privilege {
+    /// allows to read every object and its SACL, regardless of the DACL
+    /// (generic read access, `READ_CONTROL` and `ACCESS_SYSTEM_SECURITY`)
+    #[serde(rename = "SeBackupPrivilege")]
+    #[strum(serialize = "SeBackupPrivilege")]
+    Backup,
+
+    /// allows to write every object and its SACL, regardless of the DACL
+    /// (generic write access, `WRITE_DACL`, `WRITE_OWNER`, `DELETE` and
+    /// `ACCESS_SYSTEM_SECURITY`)
+    #[serde(rename = "SeRestorePrivilege")]
+    #[strum(serialize = "SeRestorePrivilege")]
+    Restore,
+
+    /// allows to take ownership of every object (`WRITE_OWNER`)
+    #[serde(rename = "SeTakeOwnershipPrivilege")]
+    #[strum(serialize = "SeTakeOwnershipPrivilege")]
+    TakeOwnership,
+
+    /// allows to read and change the SACL (`ACCESS_SYSTEM_SECURITY`)
+    #[serde(rename = "SeSecurityPrivilege")]
+    #[strum(serialize = "SeSecurityPrivilege")]
+    Security,
+}
+
+/// The security context of a principal, which is checked against a
+/// [`crate::SecurityDescriptor`]
+///
+/// Tokens can be created using the builder methods or deserialized from JSON:
+///
+/// ```
+/// use sddl::*;
+/// let token: Token = serde_json::from_str(r#"{
+///     "user": "S-1-5-21-1-2-3-1013",
+///     "groups": [
+///         { "sid": "S-1-5-32-545" },
+///         { "sid": "S-1-5-32-544", "attributes": "USE_FOR_DENY_ONLY" }
+///     ],
+///     "privileges": ["SeBackupPrivilege"],
+///     "integrity_level": "High",
+///     "user_claims": { "department": [{ "String": "HR" }] }
+/// }"#).unwrap();
+///
+/// assert!(token.has_privilege(Privilege::Backup));
+/// assert!(token.contains_sid(&Sid::try_from("S-1-5-32-545").unwrap(), false));
+/// assert!(!token.contains_sid(&Sid::try_from("S-1-5-32-544").unwrap(), false));
+/// assert!(token.contains_sid(&Sid::try_from("S-1-5-32-544").unwrap(), true));
+/// ```
+#[derive(Eq, PartialEq, Debug, Clone, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct Token {
+    user: Sid,
+
+    #[serde(default)]
+    groups: Vec<TokenGroup>,
+
+    /// if this list is not empty, the token is a restricted token, and every
+    /// access must also be granted to one of these SIDs
+    #[serde(default)]
+    restricted_sids: Vec<TokenGroup>,
+
+    #[serde(default)]
+    privileges: Vec<Privilege>,
+
+    #[serde(default)]
+    integrity_level: IntegrityLevel,
+
+    #[serde(default)]
+    user_claims: HashMap<String, Vec<ClaimValue>>,
+
+    #[serde(default)]
+    device_claims: HashMap<String, Vec<ClaimValue>>,
+
+    /// the groups of the device, which are used by `Device_Member_of`
+    #[serde(default)]
+    device_groups: Vec<Sid>,
+
+    /// the owner of objects created with this token. If this is not set,
+    /// the user is the owner.
+    #[serde(default)]
+    owner: Option<Sid>,
+
+    /// the primary group, which becomes the group of new objects
+    #[serde(default)]
+    primary_group: Option<Sid>,
+
+    /// the DACL of new objects which neither inherit ACEs nor have an
+    /// explicit DACL
+    #[serde(skip)]
+    default_dacl: Option<Acl>,
+}
+
+impl Token {
+    pub fn new(user: Sid) -> Self {
+        Self {
+            user,
+            groups: Vec::new(),
+            restricted_sids: Vec::new(),
+            privileges: Vec::new(),
+            integrity_level: IntegrityLevel::default(),
+            user_claims: HashMap::new(),
+            device_claims: HashMap::new(),
+            device_groups: Vec::new(),
+            owner: None,
+            primary_group: None,
+            default_dacl: None,
+        }
+    }
+
+    /// adds an enabled group
+    pub fn with_group(self, sid: Sid) -> Self {
+        self.with_group_attributes(sid, default_group_attributes())
+    }
+
+    pub fn with_group_attributes(mut self, sid: Sid, attributes: GroupAttributes) -> Self {
+        self.groups.push(TokenGroup::new(sid, attributes));
+        self
+    }
+
+    pub fn with_restricted_sid(mut self, sid: Sid) -> Self {
+        self.restricted_sids
+            .push(TokenGroup::new(sid, default_group_attributes()));
+        self
+    }
+
+    pub fn with_privilege(mut self, privilege: Privilege) -> Self {
+        if !self.privileges.contains(&privilege) {
+            self.privileges.push(privilege);
+        }
+        self
+    }
+
+    pub fn with_integrity_level(mut self, integrity_level: IntegrityLevel) -> Self {
+        self.integrity_level = integrity_level;
+        self
+    }
+
+    pub fn with_user_claim(mut self, name: &str, values: Vec<ClaimValue>) -> Self {
+        self.user_claims.insert(name.to_owned(), values);
+        self
+    }
+
+    pub fn with_device_claim(mut self, name: &str, values: Vec<ClaimValue>) -> Self {
+        self.device_claims.insert(name.to_owned(), values);
+        self
+    }
+
+    pub fn with_device_group(mut self, sid: Sid) -> Self {
+        self.device_groups.push(sid);
+        self
+    }
+
+    pub fn with_owner(mut self, owner: Sid) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn with_primary_group(mut self, primary_group: Sid) -> Self {
+        self.primary_group = Some(primary_group);
+        self
+    }
+
+    pub fn with_default_dacl(mut self, default_dacl: Acl) -> Self {
+        self.default_dacl = Some(default_dacl);
+        self
+    }
+
+    /// returns the owner of objects created with this token
+    pub fn default_owner(&self) -> &Sid {
+        self.owner.as_ref().unwrap_or(&self.user)
+    }
+
+    pub fn has_privilege(&self, privilege: Privilege) -> bool {
+        self.privileges.contains(&privilege)
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        !self.restricted_sids.is_empty()
+    }
+
+    /// returns `true` if `sid` is the user or one of the enabled groups of
+    /// this token. Deny-only groups are only considered if `for_deny_ace` is
+    /// set.
+    pub fn contains_sid(&self, sid: &Sid, for_deny_ace: bool) -> bool {
+        self.user == *sid || Self::contains_group(&self.groups, sid, for_deny_ace)
+    }
+
+    /// returns `true` if `sid` is one of the restricted SIDs of this token
+    pub fn contains_restricted_sid(&self, sid: &Sid, for_deny_ace: bool) -> bool {
+        Self::contains_group(&self.restricted_sids, sid, for_deny_ace)
+    }
+
+    fn contains_group(groups: &[TokenGroup], sid: &Sid, for_deny_ace: bool) -> bool {
+        groups.iter().any(|group| {
+            group.sid == *sid && (group.is_enabled() || (for_deny_ace && group.is_deny_only()))
+        })
+    }
+
+    /// creates the context used to evaluate conditional ACEs
+    pub fn evaluation_context(&self) -> EvaluationContext {
+        let mut ctx = EvaluationContext::default().with_sid(self.user.clone());
+        for group in self.groups.iter().filter(|g| g.is_enabled()) {
+            ctx = ctx.with_sid(group.sid.clone());
+        }
+        for sid in &self.device_groups {
+            ctx = ctx.with_device_sid(sid.clone());
+        }
+        for (name, values) in &self.user_claims {
+            ctx = ctx.with_user_claim(name, values.clone());
+        }
+        for (name, values) in &self.device_claims {
+            ctx = ctx.with_device_claim(name, values.clone());
+        }
+        ctx
+    }
+}
@@ -0,0 +1,115 @@
+use sddl::{Acl, GenericMapping, Guid, SecurityDescriptor, Sid, Token};
+
+fn token() -> Token {
+    Token::new(Sid::try_from("S-1-5-32-545").unwrap())
+        .with_primary_group(Sid::try_from("S-1-5-32-546").unwrap())
+}
+
+fn create_child(parent: &str, creator: Option<&str>, is_container: bool) -> SecurityDescriptor {
+    let parent = SecurityDescriptor::from_sddl(parent, None).unwrap();
+    let creator = creator.map(|sddl| SecurityDescriptor::from_sddl(sddl, None).unwrap());
+    SecurityDescriptor::create_child(
+        &parent,
+        creator.as_ref(),
+        is_container,
+        None,
+        &token(),
+        &GenericMapping::FILE,
+    )
+    .unwrap()
+}
+
+#[test]
+fn explicit_aces_precede_inherited_aces() {
+    let sd = create_child(
+        "D:AI(A;OICI;FA;;;SY)",
+        Some("D:(A;;FR;;;WD)(A;ID;FA;;;BA)"),
+        false,
+    );
+    assert_eq!(
+        sd.dacl_as_sddl_string().unwrap(),
+        "D:AI(A;;FR;;;WD)(A;ID;FA;;;SY)"
+    );
+}
+
+#[test]
+fn protected_creator_acl() {
+    let sd = create_child("D:AI(A;OICI;FA;;;SY)", Some("D:P(A;;FR;;;WD)"), false);
+    assert_eq!(sd.dacl_as_sddl_string().unwrap(), "D:P(A;;FR;;;WD)");
+}
+
+#[test]
+fn parent_without_auto_inheritance() {
+    let sd = create_child("D:(A;OICI;FA;;;SY)", Some("D:(A;;FR;;;WD)"), false);
+    assert_eq!(sd.dacl_as_sddl_string().unwrap(), "D:(A;;FR;;;WD)");
+
+    let sd = create_child("D:(A;OICI;FA;;;SY)", None, false);
+    assert_eq!(sd.dacl_as_sddl_string().unwrap(), "D:(A;ID;FA;;;SY)");
+}
+
+#[test]
+fn owner_and_group() {
+    let sd = create_child("D:AI(A;OICIIO;FA;;;CO)", Some("O:BAG:SY"), false);
+    assert_eq!(sd.owner().as_ref().unwrap(), &Sid::try_from("S-1-5-32-544").unwrap());
+    assert_eq!(sd.group().as_ref().unwrap(), &Sid::try_from("S-1-5-18").unwrap());
+    assert_eq!(sd.dacl_as_sddl_string().unwrap(), "D:AI(A;ID;FA;;;BA)");
+
+    let sd = create_child("D:AI(A;OICIIO;FA;;;CO)", None, false);
+    assert_eq!(sd.owner().as_ref().unwrap(), token().user());
+    assert_eq!(sd.group(), token().primary_group());
+}
+
+#[test]
+fn default_dacl() {
+    let parent = SecurityDescriptor::from_sddl("D:AI(A;CI;FA;;;SY)", None).unwrap();
+    let default_dacl = Acl::from_sddl("D:(A;;GA;;;SY)(A;;GA;;;BU)", None).unwrap();
+    let token = token().with_default_dacl(default_dacl.clone());
+    let sd = SecurityDescriptor::create_child(
+        &parent,
+        None,
+        false,
+        None,
+        &token,
+        &GenericMapping::FILE,
+    )
+    .unwrap();
+    assert_eq!(sd.dacl().as_ref(), Some(&default_dacl));
+}
+
+#[test]
+fn audit_aces_are_inherited() {
+    let sd = create_child("S:AI(AU;OICISAFA;FA;;;WD)(ML;OICINP;NW;;;HI)", None, true);
+    assert_eq!(
+        sd.sacl_as_sddl_string().unwrap(),
+        "S:AI(AU;OICIIDSAFA;FA;;;WD)(ML;ID;NW;;;HI)"
+    );
+}
+
+#[test]
+fn active_directory_object() {
+    // allows authenticated users to read the properties of user objects
+    // and to write the member attribute of group objects
+    let parent = SecurityDescriptor::from_sddl(
+        "D:AI(OA;CIIO;RP;;bf967aba-0de6-11d0-a285-00aa003049e2;AU)\
+         (OA;CI;WP;bf9679c0-0de6-11d0-a285-00aa003049e2;bf967a9c-0de6-11d0-a285-00aa003049e2;AU)\
+         (A;CI;GR;;;ED)",
+        None,
+    )
+    .unwrap();
+    let user_class = Guid::try_from("bf967aba-0de6-11d0-a285-00aa003049e2").unwrap();
+    let user = SecurityDescriptor::create_child(
+        &parent,
+        None,
+        true,
+        Some(user_class),
+        &token(),
+        &GenericMapping::DS_OBJECT,
+    )
+    .unwrap();
+    assert_eq!(
+        user.dacl_as_sddl_string().unwrap(),
+        "D:AI(OA;CIID;RP;;bf967aba-0de6-11d0-a285-00aa003049e2;AU)\
+         (OA;CIIOID;WP;bf9679c0-0de6-11d0-a285-00aa003049e2;bf967a9c-0de6-11d0-a285-00aa003049e2;AU)\
+         (A;ID;LCRPLORC;;;ED)(A;CIIOID;GR;;;ED)"
+    );
+}
@@ -0,0 +1,237 @@
+//! Feeds the seeds in `tests/corpus` and deterministic mutations of them into
+//! the SDDL and the binary parsers. Every input must either be parsed or be
+//! rejected with an error, but it must never cause a panic.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use base64::prelude::*;
+use sddl::{
+    AccessMask, Ace, AceHeaderFlags, Acl, AclRevision, AclType, ControlFlags, Error,
+    SecurityDescriptor, Sid,
+};
+
+const SDDL_CORPUS: &str = include_str!("corpus/sddl.txt");
+const BINARY_CORPUS: &str = include_str!("corpus/binary.txt");
+
+/// number of mutations which are created from every seed
+const MUTATIONS: usize = 100;
+
+/// fragments which are inserted into SDDL strings to create inputs which
+/// get further into the grammar than random bytes
+const SDDL_TOKENS: &[&str] = &[
+    "(", ")", ";", ":", "O:", "G:", "D:", "S:", "P", "AI", "(A;", "(OA;", "(XA;", "(ML;", "(RA;",
+    "(AL;", "CI", "OI", "FA", "GA", "RP", "0x", "0xffffffff1", "077777777777", "99999999999",
+    "S-1-", "-99999999999999999999", "bf967aba-0de6-11d0-a285-00aa003049e2", "{", "}", "\"",
+    "@User.", "@Resource.", "#", "#0", "==", "&&", "||", "!", "SID(", "Member_of",
+    "Exists ", "-", "9223372036854775808", "\u{e4}",
+];
+
+/// seeds from `corpus/sddl.txt` which are not valid SDDL. It is not enough
+/// that they do not cause a panic, they must also be rejected.
+const INVALID_SDDL_SEEDS: &[&str] = &[
+    "O:S-1-99999999999999999-1",
+    "O:S-1-5-21-4294967296-1",
+    "D:(A;;0x1FFFFFFFF;;;SY)",
+    "D:(A;;077777777777;;;SY)",
+    "D:(A;;99999999999;;;SY)",
+    "D:(OA;;CR;bf967aba-0de6-11d0-a285-00aa003049e2;;S-1-281474976710656-1)",
+    "D:(XA;;FR;;;WD;(@User.x == 99999999999999999999999))",
+    "D:(XA;;FR;;;WD;(@User.x == #abc))",
+    "O:BAO:BA",
+];
+
+/// a xorshift generator, which makes the mutations reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            (self.next() % n as u64) as usize
+        }
+    }
+}
+
+fn seeds(corpus: &str) -> impl Iterator<Item = &str> {
+    corpus
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn mutate_bytes(rng: &mut Rng, data: &mut Vec<u8>) {
+    for _ in 0..=rng.below(4) {
+        let pos = rng.below(data.len());
+        match rng.below(7) {
+            0 if !data.is_empty() => data[pos] ^= 1 << rng.below(8),
+            1 if !data.is_empty() => data[pos] = rng.next() as u8,
+            2 if !data.is_empty() => data[pos] = [0x00, 0x01, 0x7f, 0x80, 0xff][rng.below(5)],
+            3 => data.insert(pos, rng.next() as u8),
+            4 if !data.is_empty() => {
+                data.remove(pos);
+            }
+            5 => data.truncate(pos),
+            _ => {
+                let end = (pos + rng.below(16)).min(data.len());
+                let chunk = data[pos..end].to_vec();
+                let target = rng.below(data.len());
+                data.splice(target..target, chunk);
+            }
+        }
+    }
+}
+
+fn mutate_sddl(rng: &mut Rng, sddl: &str) -> String {
+    let mut bytes = sddl.as_bytes().to_vec();
+    if rng.below(2) == 0 {
+        mutate_bytes(rng, &mut bytes);
+    } else {
+        for _ in 0..=rng.below(3) {
+            let token = SDDL_TOKENS[rng.below(SDDL_TOKENS.len())].as_bytes();
+            let pos = rng.below(bytes.len());
+            let end = (pos + rng.below(4)).min(bytes.len());
+            bytes.splice(pos..end, token.iter().copied());
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// parses `sddl` and, if this succeeds, also formats and encodes the result
+fn check_sddl(sddl: &str) {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        for domain_rid in [None, Some(&[1, 2, 3][..])] {
+            if let Ok(sd) = SecurityDescriptor::from_sddl(sddl, domain_rid) {
+                let _ = SecurityDescriptor::from_sddl(&sd.to_string(), domain_rid);
+                if let Ok(bytes) = sd.to_bytes() {
+                    let _ = SecurityDescriptor::from_bytes(&bytes);
+                }
+            }
+        }
+    }));
+    assert!(result.is_ok(), "parsing the SDDL string {sddl:?} panicked");
+}
+
+/// parses `bytes` and, if this succeeds, also formats and encodes the result
+fn check_binary(bytes: &[u8]) {
+    let result = catch_unwind(|| {
+        if let Ok(sd) = SecurityDescriptor::from_bytes(bytes) {
+            let _ = sd.to_sddl();
+            let _ = sd.to_string();
+            let _ = sd.to_bytes();
+        }
+        if let Ok((sd, warnings)) = SecurityDescriptor::from_bytes_lenient(bytes) {
+            let _ = sd.to_sddl();
+            let _ = sd.to_string();
+            let _ = sd.to_bytes();
+            let _ = warnings.iter().map(ToString::to_string).count();
+        }
+    });
+    assert!(
+        result.is_ok(),
+        "parsing the binary security descriptor {} panicked",
+        BASE64_STANDARD.encode(bytes)
+    );
+}
+
+#[test]
+fn fuzz_sddl() {
+    let mut rng = Rng(0x5dd1_5eed);
+    for seed in seeds(SDDL_CORPUS) {
+        check_sddl(seed);
+        for _ in 0..MUTATIONS {
+            check_sddl(&mutate_sddl(&mut rng, seed));
+        }
+    }
+}
+
+#[test]
+fn invalid_seeds_are_rejected() {
+    for seed in INVALID_SDDL_SEEDS {
+        assert!(seeds(SDDL_CORPUS).any(|s| s == *seed), "{seed} is not in the corpus");
+        for domain_rid in [None, Some(&[1, 2, 3][..])] {
+            assert!(
+                SecurityDescriptor::from_sddl(seed, domain_rid).is_err(),
+                "{seed} should be rejected"
+            );
+        }
+    }
+}
+
+#[test]
+fn fuzz_binary() {
+    let mut rng = Rng(0xb1_4a27);
+    let binary_seeds = seeds(BINARY_CORPUS)
+        .map(|seed| BASE64_STANDARD.decode(seed).unwrap())
+        .chain(
+            seeds(SDDL_CORPUS)
+                .filter_map(|seed| SecurityDescriptor::from_sddl(seed, Some(&[1, 2, 3])).ok())
+                .filter_map(|sd| sd.to_bytes().ok()),
+        );
+    for seed in binary_seeds {
+        check_binary(&seed);
+        for _ in 0..MUTATIONS {
+            let mut bytes = seed.clone();
+            mutate_bytes(&mut rng, &mut bytes);
+            check_binary(&bytes);
+        }
+    }
+}
+
+#[test]
+fn oversized_aces_are_rejected() {
+    let sddl = format!("D:(XA;;FA;;;WD;(@User.x == \"{}\"))", "a".repeat(40000));
+    assert!(SecurityDescriptor::from_sddl(&sddl, None).is_err());
+
+    let everyone = Sid::try_from("S-1-1-0").unwrap();
+    let ace = Ace::access_allowed_callback(
+        AceHeaderFlags::empty(),
+        AccessMask::empty(),
+        everyone.clone(),
+        vec![0; 65532 - 8 - 12],
+    )
+    .unwrap();
+    assert_eq!(*ace.header().ace_size(), 65532);
+    assert!(matches!(
+        Ace::access_allowed_callback(
+            AceHeaderFlags::empty(),
+            AccessMask::empty(),
+            everyone,
+            vec![0; 65532 - 8 - 11]
+        ),
+        Err(Error::AceTooLarge(65536))
+    ));
+}
+
+#[test]
+fn deeply_nested_condition() {
+    // an attribute followed by 20,000 NOT tokens
+    let mut condition = b"artx\xf9\x02\x00\x00\x00x\x00".to_vec();
+    condition.resize(condition.len() + 20_000, 0xa2);
+    let ace = Ace::access_allowed_callback(
+        AceHeaderFlags::empty(),
+        AccessMask::empty(),
+        Sid::try_from("S-1-1-0").unwrap(),
+        condition,
+    )
+    .unwrap();
+    let dacl = Acl::new(
+        AclRevision::ACL_REVISION,
+        AclType::DACL,
+        ControlFlags::empty(),
+        vec![ace],
+    );
+    let bytes = SecurityDescriptor::new(None, None, Some(dacl), None)
+        .to_bytes()
+        .unwrap();
+    check_binary(&bytes);
+    let sd = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    assert!(sd.to_sddl().is_err());
+}
@@ -0,0 +1,191 @@
+use sddl::{Ace, AclRevision, AclType, Error, ParseWarning, SddlComponent, SecurityDescriptor};
+
+const SDDL: &str = "O:BAG:SYD:(A;;FA;;;SY)(A;;FR;;;WD)(A;;FA;;;BA)";
+
+/// offset of the DACL in the binary representation of `SDDL`
+const DACL: usize = 20;
+
+/// offset of the first ACE in the binary representation of `SDDL`
+const FIRST_ACE: usize = DACL + 8;
+
+/// size of an ACCESS_ALLOWED_ACE with a SID of one sub-authority
+const ACE_SIZE: usize = 20;
+
+fn encoded() -> Vec<u8> {
+    SecurityDescriptor::from_sddl(SDDL, None)
+        .unwrap()
+        .to_bytes()
+        .unwrap()
+}
+
+fn dacl_aces(sd: &SecurityDescriptor) -> Vec<String> {
+    sd.dacl()
+        .as_ref()
+        .unwrap()
+        .ace_list()
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn ace(index: usize) -> SddlComponent {
+    SddlComponent::Ace {
+        acl_type: AclType::DACL,
+        index,
+    }
+}
+
+#[test]
+fn intact_descriptor() {
+    let bytes = encoded();
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(sd.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn malformed_ace_is_skipped() {
+    let mut bytes = encoded();
+    // the SID of the second ACE has an invalid revision
+    bytes[FIRST_ACE + ACE_SIZE + 8] = 7;
+    assert!(SecurityDescriptor::from_bytes(&bytes).is_err());
+
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    assert_eq!(dacl_aces(&sd), ["A;;FA;;;SY", "A;;FA;;;BA"]);
+    assert!(matches!(
+        &warnings[..],
+        [ParseWarning::MalformedAce { component, offset, .. }]
+            if *component == ace(1) && *offset == FIRST_ACE + ACE_SIZE
+    ));
+}
+
+#[test]
+fn unknown_ace_type_is_kept() {
+    let mut bytes = encoded();
+    bytes[FIRST_ACE + ACE_SIZE] = 0x42;
+
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    assert_eq!(
+        warnings,
+        [ParseWarning::UnknownAceType {
+            component: ace(1),
+            offset: FIRST_ACE + ACE_SIZE,
+            ace_type: 0x42
+        }]
+    );
+    let dacl = sd.dacl().as_ref().unwrap();
+    let Ace::Unknown { ace_type, raw, .. } = &dacl.ace_list()[1] else {
+        panic!("the second ACE should be unknown");
+    };
+    assert_eq!(*ace_type, 0x42);
+    assert_eq!(raw.len(), ACE_SIZE - 8);
+    assert!(dacl.ace_list()[1].trustee().is_none());
+    assert_eq!(dacl.ace_list()[1].sid().to_string(), "S-1-0-0");
+    assert_eq!(dacl.ace_list()[0].trustee().unwrap().to_string(), "S-1-5-18");
+
+    // unknown ACEs are written back unchanged, but they have no SDDL
+    // representation
+    assert_eq!(sd.to_bytes().unwrap(), bytes);
+    assert!(matches!(sd.to_sddl(), Err(Error::NoSddlRepresentation(_))));
+}
+
+#[test]
+fn truncated_acl() {
+    let bytes = encoded();
+    let truncated = &bytes[..FIRST_ACE + ACE_SIZE + 10];
+
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(truncated).unwrap();
+    assert_eq!(dacl_aces(&sd), ["A;;FA;;;SY"]);
+    assert!(sd.owner().is_none());
+    assert!(sd.group().is_none());
+    assert!(warnings.contains(&ParseWarning::MissingAces {
+        acl_type: AclType::DACL,
+        expected: 3,
+        found: 2
+    }));
+    assert!(warnings.iter().any(
+        |w| matches!(w, ParseWarning::MalformedAce { component, .. } if *component == ace(1))
+    ));
+    assert!(warnings.iter().any(|w| matches!(
+        w,
+        ParseWarning::OffsetOutOfBounds {
+            component: SddlComponent::Owner,
+            ..
+        }
+    )));
+}
+
+#[test]
+fn inconsistent_ace_size() {
+    let mut bytes = encoded();
+    // the first ACE claims to be larger than the whole ACL
+    bytes[FIRST_ACE + 2] = 0xfc;
+
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    assert!(dacl_aces(&sd).is_empty());
+    assert_eq!(sd.owner().as_ref().unwrap().to_string(), "S-1-5-32-544");
+    assert!(warnings.contains(&ParseWarning::MissingAces {
+        acl_type: AclType::DACL,
+        expected: 3,
+        found: 1
+    }));
+}
+
+#[test]
+fn inconsistent_acl_revision_is_kept() {
+    let sddl = "O:BAG:SYD:(OA;;RP;bf967aba-0de6-11d0-a285-00aa003049e2;;WD)";
+    let mut bytes = SecurityDescriptor::from_sddl(sddl, None)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    assert_eq!(bytes[DACL], 0x04);
+    bytes[DACL] = 0x02;
+
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    assert_eq!(
+        *sd.dacl().as_ref().unwrap().acl_revision(),
+        AclRevision::ACL_REVISION
+    );
+    assert_eq!(
+        warnings,
+        [ParseWarning::InconsistentAclRevision {
+            acl_type: AclType::DACL,
+            revision: AclRevision::ACL_REVISION,
+            required: AclRevision::ACL_REVISION_DS
+        }]
+    );
+    assert_eq!(sd.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn oversized_and_overlapping_offsets() {
+    let mut bytes = encoded();
+    let owner_offset = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+
+    // the group points to the owner, the DACL points far beyond the data
+    bytes[8..12].copy_from_slice(&owner_offset.to_le_bytes());
+    bytes[16..20].copy_from_slice(&0x0100_0000u32.to_le_bytes());
+
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    assert_eq!(sd.owner(), sd.group());
+    assert!(sd.dacl().is_none());
+    assert_eq!(
+        warnings,
+        [
+            ParseWarning::OverlappingComponents {
+                component: SddlComponent::Group,
+                other: Some(SddlComponent::Owner)
+            },
+            ParseWarning::OffsetOutOfBounds {
+                component: SddlComponent::Dacl,
+                offset: 0x0100_0000
+            }
+        ]
+    );
+}
+
+#[test]
+fn incomplete_header() {
+    let bytes = encoded();
+    assert!(SecurityDescriptor::from_bytes_lenient(&bytes[..12]).is_err());
+}
@@ -1,4 +1,4 @@
-use sddl::SecurityDescriptor;
+use sddl::{ControlFlags, SecurityDescriptor};
 
 /// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/2918391b-75b9-4eeb-83f0-7fdc04a5c6c9>
 #[test]
@@ -14,3 +14,116 @@ fn testcase1_reversed() {
     let domain_rid = [1,2,3];
     let _ = SecurityDescriptor::from_sddl(sddl_string, Some(&domain_rid)).unwrap();
 }
+
+/// <https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-definition-language-for-conditional-aces->
+#[test]
+fn conditional_aces() {
+    let sddl_string = r#"O:BAG:BAD:P(XA;;FX;;;S-1-1-0;(@User.Title=="PM" && (@User.Division=="Finance" || @User.Division ==" Sales")))(ZA;;RP;bf967aba-0de6-11d0-a285-00aa003049e2;;AU;(Member_of_Any {SID(BA), SID(BO)}))S:P(XU;FA;GR;;;WD;(@Resource.Secrecy >= 2))"#;
+    let sd = SecurityDescriptor::from_sddl(sddl_string, None).unwrap();
+    assert_eq!(sd.dacl().as_ref().unwrap().ace_list().len(), 2);
+    assert_eq!(sd.sacl().as_ref().unwrap().ace_list().len(), 1);
+}
+
+#[test]
+fn conditional_aces_display() {
+    let sddl_string = r#"O:BAG:BAD:P(XA;;;;;WD;(@User.Title == "PM"))(ZA;;;bf967aba-0de6-11d0-a285-00aa003049e2;;AU;(Member_of_Any {SID(BA), SID(BO)}))S:P(XU;FA;GR;;;WD;(@Resource.Secrecy >= 2))"#;
+    let sd = SecurityDescriptor::from_sddl(sddl_string, None).unwrap();
+    let sd = SecurityDescriptor::from_bytes(&sd.to_bytes().unwrap()).unwrap();
+    let dacl = sd.dacl_as_sddl_string().unwrap();
+    let sacl = sd.sacl_as_sddl_string().unwrap();
+    assert!(sddl_string.contains(&dacl), "{dacl}");
+    assert!(sddl_string.contains(&sacl), "{sacl}");
+}
+
+/// output of `sc sdshow`, which has neither owner nor group
+#[test]
+fn service_without_owner() {
+    let sddl_string = "D:(A;;CCLCSWRPWPDTLOCRRC;;;SY)(A;;CCDCLCSWRPWPDTLOCRSDRCWDWO;;;BA)(A;;CCLCSWLOCRRC;;;IU)(A;;CCLCSWLOCRRC;;;SU)S:(AU;FA;CCDCLCSWRPWPDTLOCRSDRCWDWO;;;WD)";
+    let sd = SecurityDescriptor::from_sddl(sddl_string, None).unwrap();
+    assert!(sd.owner().is_none());
+    assert!(sd.group().is_none());
+    assert_eq!(sd.dacl().as_ref().unwrap().ace_list().len(), 4);
+    assert_eq!(sd.sacl().as_ref().unwrap().ace_list().len(), 1);
+    assert_eq!(
+        *sd.flags(),
+        ControlFlags::DiscretionaryAclPresent | ControlFlags::SystemAclPresent
+    );
+}
+
+#[test]
+fn optional_components() {
+    for sddl_string in [
+        "",
+        "O:SY",
+        "G:BA",
+        "D:(A;;FA;;;SY)",
+        "O:SYD:(A;;FA;;;SY)",
+        "G:BAD:P(A;;FA;;;SY)",
+        "D:AIS:AI(ML;;NW;;;LW)G:SYO:BA",
+    ] {
+        assert!(
+            SecurityDescriptor::from_sddl(sddl_string, None).is_ok(),
+            "{sddl_string}"
+        );
+    }
+
+    let sd = SecurityDescriptor::from_sddl("S:PAI(AU;FA;GA;;;WD)O:BA", None).unwrap();
+    assert!(sd.dacl().is_none());
+    assert_eq!(
+        *sd.flags(),
+        ControlFlags::SystemAclPresent
+            | ControlFlags::SystemAclProtected
+            | ControlFlags::SystemAclAutoInherited
+    );
+    assert_eq!(sd.to_string(), "O:S-1-5-32-544S:PAI(AU;FA;GA;;;WD)");
+}
+
+#[test]
+fn duplicate_components() {
+    for sddl_string in ["O:BAO:SY", "G:BAD:(A;;FA;;;SY)G:SY", "D:PD:AI", "S:S:"] {
+        assert!(
+            SecurityDescriptor::from_sddl(sddl_string, None).is_err(),
+            "{sddl_string}"
+        );
+    }
+}
+
+/// a NULL DACL, which grants full access to everyone, as opposed to a
+/// missing DACL
+#[test]
+fn null_dacl_roundtrip() {
+    for sddl_string in [
+        "D:NO_ACCESS_CONTROL",
+        "O:SYD:PNO_ACCESS_CONTROLS:(AU;FA;GA;;;WD)",
+    ] {
+        let sd = SecurityDescriptor::from_sddl(sddl_string, None).unwrap();
+        assert!(sd.has_null_dacl(), "{sddl_string}");
+        assert!(sd.dacl().is_none(), "{sddl_string}");
+        assert!(sd.flags().contains(ControlFlags::DiscretionaryAclPresent));
+
+        let sd2 = SecurityDescriptor::from_bytes(&sd.to_bytes().unwrap()).unwrap();
+        assert!(sd2.has_null_dacl(), "{sddl_string}");
+        assert_eq!(sd.to_string(), sd2.to_string());
+        assert_eq!(
+            SecurityDescriptor::from_sddl(&sd.to_string(), None)
+                .unwrap()
+                .to_string(),
+            sd.to_string()
+        );
+    }
+
+    let sd = SecurityDescriptor::from_sddl("O:SYD:PNO_ACCESS_CONTROL", None).unwrap();
+    assert_eq!(
+        *sd.flags(),
+        ControlFlags::DiscretionaryAclPresent | ControlFlags::DiscretionaryAclProtected
+    );
+    assert_eq!(
+        sd.dacl_as_sddl_string().unwrap(),
+        "D:PNO_ACCESS_CONTROL"
+    );
+
+    let sd = SecurityDescriptor::from_sddl("O:SY", None).unwrap();
+    assert!(!sd.has_null_dacl());
+    assert!(sd.dacl_as_sddl_string().is_none());
+    assert!(SecurityDescriptor::from_sddl("D:NO_ACCESS_CONTROLD:(A;;FA;;;SY)", None).is_err());
+}
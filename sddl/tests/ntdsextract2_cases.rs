@@ -1,19 +1,103 @@
 use base64::prelude::*;
-use sddl::SecurityDescriptor;
+use sddl::{
+    AccessMask, ActiveDirectorySchema, GenericMapping, Guid, GuidResolver, ObjectTypeList,
+    ParseWarning, SecurityDescriptor, Sid, Token,
+};
+
+const TESTCASE1: &str = "AQAEgDAAAAA8AAAAAAAAABQAAAACABwAAQAAAAADFAD/////AQEAAAAAAAEAAAAAAQEAAAAAAAEAAAAAAQEAAAAAAAEAAAAA";
+const TESTCASE2: &str = "AQAUhEwKAABcCgAAFAAAANwAAAAEAMgABQAAAAdCOAAgAAAAAwAAAL47DvPwn9ERtgMAAPgDZ8Glepa/5g3QEaKFAKoAMEniAQEAAAAAAAEAAAAAB0I4ACAAAAADAAAAvzsO8/Cf0RG2AwAA+ANnwaV6lr/mDdARooUAqgAwSeIBAQAAAAAAAQAAAAACQCQAAAEAAAEFAAAAAAAFFQAAAJrdeVep3u2iOCGG0gECAAACQBgAAAEAAAECAAAAAAAFIAAAACACAAACQBQAIAAMAAEBAAAAAAABAAAAAAQAcAk1AAAABQo8ABAAAAADAAAAAEIWTMAg0BGnaACqAG4FKRTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAAEIWTMAg0BGnaACqAG4FKbp6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAECAgX6V50BGQIADAT8LUzxTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAECAgX6V50BGQIADAT8LUz7p6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQMIKvKl50BGQIADAT8LUzxTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQMIKvKl50BGQIADAT8LUz7p6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQi+6WaJ50BGQIADAT8LTzxTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQi+6WaJ50BGQIADAT8LTz7p6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAA+IhwA+EK0hG0IgCgyWj5ORTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAA+IhwA+EK0hG0IgCgyWj5Obp6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQA4AAABAAABAAAAGH4PPnosEEy6gk2SbbmaPgEFAAAAAAAFFQAAAJrdeVep3u2iOCGG0goCAAAFADgAAAEAAAEAAACq9jERB5zREfefAMBPwtzSAQUAAAAAAAUVAAAAmt15V6ne7aI4IYbS8gEAAAUAOAAAAQAAAQAAAK32MREHnNER958AwE/C3NIBBQAAAAAABRUAAACa3XlXqd7tojghhtIEAgAABQI4ADAAAAABAAAAD9ZHW5BgskCfNypN6I8wYwEFAAAAAAAFFQAAAJrdeVep3u2iOCGG0g4CAAAFAjgAMAAAAAEAAAAP1kdbkGCyQJ83Kk3ojzBjAQUAAAAAAAUVAAAAmt15V6ne7aI4IYbSDwIAAAUKOAAIAAAAAwAAAKZtAps8DVxGi+5RmdcWXLqGepa/5g3QEaKFAKoAMEniAQEAAAAAAAMAAAAABQo4AAgAAAADAAAApm0CmzwNXEaL7lGZ1xZcuoZ6lr/mDdARooUAqgAwSeIBAQAAAAAABQoAAAAFCjgAEAAAAAMAAABtnsa3xyzSEYVOAKDJg/YIhnqWv+YN0BGihQCqADBJ4gEBAAAAAAAFCQAAAAUKOAAQAAAAAwAAAG2exrfHLNIRhU4AoMmD9gicepa/5g3QEaKFAKoAMEniAQEAAAAAAAUJAAAABQo4ABAAAAADAAAAbZ7Gt8cs0hGFTgCgyYP2CLp6lr/mDdARooUAqgAwSeIBAQAAAAAABQkAAAAFCjgAIAAAAAMAAACTexvqSF7VRrxsTfT9p4o1hnqWv+YN0BGihQCqADBJ4gEBAAAAAAAFCgAAAAUALAAAAQAAAQAAAHZb6YlNRGJMmRoPrL7aZAwBAgAAAAAABSAAAAAgAgAABQAsAAABAAABAAAAqvYxEQec0RH3nwDAT8Lc0gECAAAAAAAFIAAAACACAAAFACwAAAEAAAEAAACr9jERB5zREfefAMBPwtzSAQIAAAAAAAUgAAAAIAIAAAUALAAAAQAAAQAAAKz2MREHnNER958AwE/C3NIBAgAAAAAABSAAAAAgAgAABQAsAAABAAABAAAArfYxEQec0RH3nwDAT8Lc0gECAAAAAAAFIAAAACACAAAFACwAAAEAAAEAAACu9jERB5zREfefAMBPwtzSAQIAAAAAAAUgAAAAIAIAAAUALAAAAQAAAQAAAMlto+IXrsNHtYu+NMVbpjMBAgAAAAAABSAAAAAtAgAABQAsABAAAAABAAAAYHNAx78g0BGnaACqAG4FKQECAAAAAAAFIAAAACoCAAAFACwAEAAAAAEAAADQnxG49gRiR6t6SYbHaz+aAQIAAAAAAAUgAAAAKgIAAAUKLACUAAIAAgAAABTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQosAJQAAgACAAAAnHqWv+YN0BGihQCqADBJ4gECAAAAAAAFIAAAACoCAAAFCiwAlAACAAIAAAC6epa/5g3QEaKFAKoAMEniAQIAAAAAAAUgAAAAKgIAAAUAKAAAAQAAAQAAAF5MxwXrTbRDvZ+GZkwqf9UBAQAAAAAABQsAAAAFACgAAAEAAAEAAAB2W+mJTURiTJkaD6y+2mQMAQEAAAAAAAUJAAAABQAoAAABAAABAAAAfdzCzK2mekqIRsBOPMU1AQEBAAAAAAAFCwAAAAUAKAAAAQAAAQAAAJw2DyjHZ45DrpgdRvPG9UEBAQAAAAAABQsAAAAFACgAAAEAAAEAAACq9jERB5zREfefAMBPwtzSAQEAAAAAAAUJAAAABQAoAAABAAABAAAAq/YxEQec0RH3nwDAT8Lc0gEBAAAAAAAFCQAAAAUAKAAAAQAAAQAAAKz2MREHnNER958AwE/C3NIBAQAAAAAABQkAAAAFACgAAAEAAAEAAACu9jERB5zREfefAMBPwtzSAQEAAAAAAAUJAAAABQAoABAAAAABAAAA0J8RuPYEYkerekmGx2s/mgEBAAAAAAAFCwAAAAUDKAAwAAAAAQAAAOXDeD+a971GoLidGBFt3HkBAQAAAAAABQoAAAAFCigAMAEAAAEAAADeR+aRb9lwS5VX1j/088zYAQEAAAAAAAUKAAAAAAAkAL0BDgABBQAAAAAABRUAAACa3XlXqd7tojghhtIAAgAAAAIkAP8BDwABBQAAAAAABRUAAACa3XlXqd7tojghhtIHAgAAAAAYABAAAgABAgAAAAAABSAAAAAqAgAAAAIYAAQAAAABAgAAAAAABSAAAAAqAgAAAAIYAL0BDwABAgAAAAAABSAAAAAgAgAAAAAUABAAAAABAQAAAAAAAQAAAAAAABQAlAACAAEBAAAAAAAFCQAAAAAAFACUAAIAAQEAAAAAAAULAAAAAAAUAP8BDwABAQAAAAAABRIAAAABAgAAAAAABSAAAAAgAgAAAQIAAAAAAAUgAAAAIAIAAA==";
 
 #[test]
 fn testcase1() {
-    let _sd = SecurityDescriptor::from_bytes(&BASE64_STANDARD.decode("AQAEgDAAAAA8AAAAAAAAABQAAAACABwAAQAAAAADFAD/////AQEAAAAAAAEAAAAAAQEAAAAAAAEAAAAAAQEAAAAAAAEAAAAA").unwrap()).unwrap();
+    let _sd = SecurityDescriptor::from_bytes(&BASE64_STANDARD.decode(TESTCASE1).unwrap()).unwrap();
 }
 
-
 #[test]
 fn testcase2() {
-    let _sd = SecurityDescriptor::from_bytes(&BASE64_STANDARD.decode("AQAUhEwKAABcCgAAFAAAANwAAAAEAMgABQAAAAdCOAAgAAAAAwAAAL47DvPwn9ERtgMAAPgDZ8Glepa/5g3QEaKFAKoAMEniAQEAAAAAAAEAAAAAB0I4ACAAAAADAAAAvzsO8/Cf0RG2AwAA+ANnwaV6lr/mDdARooUAqgAwSeIBAQAAAAAAAQAAAAACQCQAAAEAAAEFAAAAAAAFFQAAAJrdeVep3u2iOCGG0gECAAACQBgAAAEAAAECAAAAAAAFIAAAACACAAACQBQAIAAMAAEBAAAAAAABAAAAAAQAcAk1AAAABQo8ABAAAAADAAAAAEIWTMAg0BGnaACqAG4FKRTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAAEIWTMAg0BGnaACqAG4FKbp6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAECAgX6V50BGQIADAT8LUzxTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAECAgX6V50BGQIADAT8LUz7p6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQMIKvKl50BGQIADAT8LUzxTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQMIKvKl50BGQIADAT8LUz7p6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQi+6WaJ50BGQIADAT8LTzxTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAAQi+6WaJ50BGQIADAT8LTz7p6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAA+IhwA+EK0hG0IgCgyWj5ORTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQo8ABAAAAADAAAA+IhwA+EK0hG0IgCgyWj5Obp6lr/mDdARooUAqgAwSeIBAgAAAAAABSAAAAAqAgAABQA4AAABAAABAAAAGH4PPnosEEy6gk2SbbmaPgEFAAAAAAAFFQAAAJrdeVep3u2iOCGG0goCAAAFADgAAAEAAAEAAACq9jERB5zREfefAMBPwtzSAQUAAAAAAAUVAAAAmt15V6ne7aI4IYbS8gEAAAUAOAAAAQAAAQAAAK32MREHnNER958AwE/C3NIBBQAAAAAABRUAAACa3XlXqd7tojghhtIEAgAABQI4ADAAAAABAAAAD9ZHW5BgskCfNypN6I8wYwEFAAAAAAAFFQAAAJrdeVep3u2iOCGG0g4CAAAFAjgAMAAAAAEAAAAP1kdbkGCyQJ83Kk3ojzBjAQUAAAAAAAUVAAAAmt15V6ne7aI4IYbSDwIAAAUKOAAIAAAAAwAAAKZtAps8DVxGi+5RmdcWXLqGepa/5g3QEaKFAKoAMEniAQEAAAAAAAMAAAAABQo4AAgAAAADAAAApm0CmzwNXEaL7lGZ1xZcuoZ6lr/mDdARooUAqgAwSeIBAQAAAAAABQoAAAAFCjgAEAAAAAMAAABtnsa3xyzSEYVOAKDJg/YIhnqWv+YN0BGihQCqADBJ4gEBAAAAAAAFCQAAAAUKOAAQAAAAAwAAAG2exrfHLNIRhU4AoMmD9gicepa/5g3QEaKFAKoAMEniAQEAAAAAAAUJAAAABQo4ABAAAAADAAAAbZ7Gt8cs0hGFTgCgyYP2CLp6lr/mDdARooUAqgAwSeIBAQAAAAAABQkAAAAFCjgAIAAAAAMAAACTexvqSF7VRrxsTfT9p4o1hnqWv+YN0BGihQCqADBJ4gEBAAAAAAAFCgAAAAUALAAAAQAAAQAAAHZb6YlNRGJMmRoPrL7aZAwBAgAAAAAABSAAAAAgAgAABQAsAAABAAABAAAAqvYxEQec0RH3nwDAT8Lc0gECAAAAAAAFIAAAACACAAAFACwAAAEAAAEAAACr9jERB5zREfefAMBPwtzSAQIAAAAAAAUgAAAAIAIAAAUALAAAAQAAAQAAAKz2MREHnNER958AwE/C3NIBAgAAAAAABSAAAAAgAgAABQAsAAABAAABAAAArfYxEQec0RH3nwDAT8Lc0gECAAAAAAAFIAAAACACAAAFACwAAAEAAAEAAACu9jERB5zREfefAMBPwtzSAQIAAAAAAAUgAAAAIAIAAAUALAAAAQAAAQAAAMlto+IXrsNHtYu+NMVbpjMBAgAAAAAABSAAAAAtAgAABQAsABAAAAABAAAAYHNAx78g0BGnaACqAG4FKQECAAAAAAAFIAAAACoCAAAFACwAEAAAAAEAAADQnxG49gRiR6t6SYbHaz+aAQIAAAAAAAUgAAAAKgIAAAUKLACUAAIAAgAAABTMKEg3FLxFmwetbwFeXygBAgAAAAAABSAAAAAqAgAABQosAJQAAgACAAAAnHqWv+YN0BGihQCqADBJ4gECAAAAAAAFIAAAACoCAAAFCiwAlAACAAIAAAC6epa/5g3QEaKFAKoAMEniAQIAAAAAAAUgAAAAKgIAAAUAKAAAAQAAAQAAAF5MxwXrTbRDvZ+GZkwqf9UBAQAAAAAABQsAAAAFACgAAAEAAAEAAAB2W+mJTURiTJkaD6y+2mQMAQEAAAAAAAUJAAAABQAoAAABAAABAAAAfdzCzK2mekqIRsBOPMU1AQEBAAAAAAAFCwAAAAUAKAAAAQAAAQAAAJw2DyjHZ45DrpgdRvPG9UEBAQAAAAAABQsAAAAFACgAAAEAAAEAAACq9jERB5zREfefAMBPwtzSAQEAAAAAAAUJAAAABQAoAAABAAABAAAAq/YxEQec0RH3nwDAT8Lc0gEBAAAAAAAFCQAAAAUAKAAAAQAAAQAAAKz2MREHnNER958AwE/C3NIBAQAAAAAABQkAAAAFACgAAAEAAAEAAACu9jERB5zREfefAMBPwtzSAQEAAAAAAAUJAAAABQAoABAAAAABAAAA0J8RuPYEYkerekmGx2s/mgEBAAAAAAAFCwAAAAUDKAAwAAAAAQAAAOXDeD+a971GoLidGBFt3HkBAQAAAAAABQoAAAAFCigAMAEAAAEAAADeR+aRb9lwS5VX1j/088zYAQEAAAAAAAUKAAAAAAAkAL0BDgABBQAAAAAABRUAAACa3XlXqd7tojghhtIAAgAAAAIkAP8BDwABBQAAAAAABRUAAACa3XlXqd7tojghhtIHAgAAAAAYABAAAgABAgAAAAAABSAAAAAqAgAAAAIYAAQAAAABAgAAAAAABSAAAAAqAgAAAAIYAL0BDwABAgAAAAAABSAAAAAgAgAAAAAUABAAAAABAQAAAAAAAQAAAAAAABQAlAACAAEBAAAAAAAFCQAAAAAAFACUAAIAAQEAAAAAAAULAAAAAAAUAP8BDwABAQAAAAAABRIAAAABAgAAAAAABSAAAAAgAgAAAQIAAAAAAAUgAAAAIAIAAA==").unwrap()).unwrap();
+    let _sd = SecurityDescriptor::from_bytes(&BASE64_STANDARD.decode(TESTCASE2).unwrap()).unwrap();
 }
 
-#[ignore]
+/// TESTCASE1, preceded by four additional bytes
+const TESTCASE3: &str = "DwAAAAEABIAwAAAAPAAAAAAAAAAUAAAAAgAcAAEAAAAAAxQA/////wEBAAAAAAABAAAAAAEBAAAAAAABAAAAAAEBAAAAAAABAAAAAA==";
+
 #[test]
 fn testcase3() {
-    let _sd = SecurityDescriptor::from_bytes(&BASE64_STANDARD.decode("DwAAAAEABIAwAAAAPAAAAAAAAAAUAAAAAgAcAAEAAAAAAxQA/////wEBAAAAAAABAAAAAAEBAAAAAAABAAAAAAEBAAAAAAABAAAAAA==").unwrap()).unwrap();
-}
\ No newline at end of file
+    let bytes = BASE64_STANDARD.decode(TESTCASE3).unwrap();
+    assert!(SecurityDescriptor::from_bytes(&bytes).is_err());
+
+    let (sd, warnings) = SecurityDescriptor::from_bytes_lenient(&bytes).unwrap();
+    assert_eq!(warnings, vec![ParseWarning::SkippedPrefix { length: 4 }]);
+    assert_eq!(sd.to_bytes().unwrap(), BASE64_STANDARD.decode(TESTCASE1).unwrap());
+}
+
+fn assert_roundtrip(encoded: &str) {
+    let bytes = BASE64_STANDARD.decode(encoded).unwrap();
+    let sd = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    assert_eq!(sd.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn testcase1_roundtrip() {
+    assert_roundtrip(TESTCASE1);
+}
+
+#[test]
+fn testcase2_roundtrip() {
+    assert_roundtrip(TESTCASE2);
+}
+
+#[test]
+fn testcase2_replication_rights() {
+    let sd = SecurityDescriptor::from_bytes(&BASE64_STANDARD.decode(TESTCASE2).unwrap()).unwrap();
+    let domain_dns = Guid::try_from("19195a5b-6da0-11d0-afd3-00c04fd930c9").unwrap();
+    let get_changes_all = Guid::try_from("1131f6ad-9c07-11d1-f79f-00c04fc2dcd2").unwrap();
+    let list = ObjectTypeList::new(domain_dns).with_property_set(get_changes_all);
+
+    let domain_controller =
+        Token::new(Sid::try_from("S-1-5-21-1467604378-2733498025-3532005688-1000").unwrap())
+            .with_group(Sid::try_from("S-1-5-21-1467604378-2733498025-3532005688-516").unwrap())
+            .with_group(Sid::try_from("S-1-5-11").unwrap());
+    let results = sd.access_check_by_type_result_list(
+        &domain_controller,
+        AccessMask::CONTROL_ACCESS,
+        &list,
+        &GenericMapping::DS_OBJECT,
+    );
+    assert!(results[1].access_allowed());
+
+    let user = Token::new(Sid::try_from("S-1-5-21-1467604378-2733498025-3532005688-1001").unwrap())
+        .with_group(Sid::try_from("S-1-5-21-1467604378-2733498025-3532005688-513").unwrap())
+        .with_group(Sid::try_from("S-1-5-11").unwrap());
+    let results = sd.access_check_by_type_result_list(
+        &user,
+        AccessMask::CONTROL_ACCESS,
+        &list,
+        &GenericMapping::DS_OBJECT,
+    );
+    assert!(!results[1].access_allowed());
+}
+
+#[test]
+fn testcase2_object_types_have_names() {
+    let sd = SecurityDescriptor::from_bytes(&BASE64_STANDARD.decode(TESTCASE2).unwrap()).unwrap();
+    let schema = ActiveDirectorySchema::builtin();
+    let aces = [sd.dacl(), sd.sacl()]
+        .into_iter()
+        .flatten()
+        .flat_map(|acl| acl.ace_list());
+    for ace in aces {
+        for guid in [ace.object_type(), ace.inherited_object_type()]
+            .into_iter()
+            .flatten()
+        {
+            assert!(schema.resolve_guid(guid).is_some(), "{guid}");
+        }
+    }
+}
+
+#[test]
+fn oversized_acl_cannot_be_written() {
+    let sddl = format!("D:{}", "(A;;FA;;;WD)".repeat(4000));
+    let sd = SecurityDescriptor::from_sddl(&sddl, None).unwrap();
+    assert!(sd.to_bytes().is_err());
+}
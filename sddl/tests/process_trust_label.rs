@@ -0,0 +1,68 @@
+use sddl::{
+    Ace, AceHeaderFlags, AclRevision, ProtectionLevel, ProtectionSigner, ProtectionType,
+    SecurityDescriptor,
+};
+
+fn assert_roundtrip(sddl: &str) {
+    let sd = SecurityDescriptor::from_sddl(sddl, None).unwrap();
+    assert_eq!(sd.to_string(), sddl);
+
+    let bytes = sd.to_bytes().unwrap();
+    let decoded = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_string(), sddl);
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn trust_label_ace() {
+    assert_roundtrip("S:(TL;;0x200;;;S-1-19-512-8192)");
+    assert_roundtrip("S:(TL;CIIO;0x1200a9;;;S-1-19-1024-4096)");
+
+    let sd = SecurityDescriptor::from_sddl("S:(TL;;0x200;;;S-1-19-512-8192)", None).unwrap();
+    let sacl = sd.sacl().as_ref().unwrap();
+    assert_eq!(*sacl.acl_revision(), AclRevision::ACL_REVISION);
+    assert!(matches!(
+        &sacl.ace_list()[0],
+        Ace::SYSTEM_PROCESS_TRUST_LABEL_ACE { .. }
+    ));
+
+    let label = sd.process_trust_label().unwrap();
+    assert_eq!(
+        label.level(),
+        ProtectionLevel::new(ProtectionType::ProtectedLight, ProtectionSigner::WinTcb)
+    );
+    assert_eq!(label.level().to_string(), "PPL WinTcb");
+}
+
+#[test]
+fn access_filter_ace() {
+    assert_roundtrip(r#"S:(FL;TP;CC;;;WD;(@User.Title == "PM"))"#);
+    assert_roundtrip("S:(FL;CI;FA;;;WD;(Member_of {SID(BA)}))");
+
+    let sd = SecurityDescriptor::from_sddl(r#"S:(FL;TP;CC;;;WD;(@User.Title == "PM"))"#, None)
+        .unwrap();
+    let ace = &sd.sacl().as_ref().unwrap().ace_list()[0];
+    assert!(matches!(ace, Ace::SYSTEM_ACCESS_FILTER_ACE { .. }));
+    assert!(ace
+        .header()
+        .ace_flags()
+        .contains(AceHeaderFlags::TRUST_PROTECTED_FILTER));
+    assert!(ace.condition().unwrap().is_some());
+}
+
+#[test]
+fn trust_protected_flag_is_only_used_by_access_filters() {
+    // the same bit means "successful access" in audit ACEs
+    assert_roundtrip("S:(AU;SA;FA;;;WD)");
+
+    // object inherit has nothing to do with trust protection
+    assert_roundtrip("D:(A;OI;FA;;;WD)");
+}
+
+#[test]
+fn serde_output() {
+    let sd = SecurityDescriptor::from_sddl("S:(TL;;0x200;;;S-1-19-512-8192)", None).unwrap();
+    let json = serde_json::to_value(&sd).unwrap();
+    let ace = &json["sacl"]["ace_list"][0];
+    assert!(ace.get("SYSTEM_PROCESS_TRUST_LABEL_ACE").is_some(), "{json}");
+}
@@ -0,0 +1,183 @@
+use std::io::Cursor;
+
+use binrw::{BinReaderExt, BinWriterExt};
+use sddl::{
+    constants::FILE_ALL, Ace, AceHeaderFlags, Acl, AclRevision, AclType, CompoundAceType,
+    ControlFlags, Error, SecurityDescriptor, Sid,
+};
+
+fn assert_roundtrip(sddl: &str) {
+    let sd = SecurityDescriptor::from_sddl(sddl, None).unwrap();
+    assert_eq!(sd.to_string(), sddl);
+
+    let bytes = sd.to_bytes().unwrap();
+    let decoded = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_string(), sddl);
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+}
+
+/// encodes a SACL which contains only `ace` and decodes it again
+fn binary_roundtrip(ace: Ace) -> SecurityDescriptor {
+    let sacl = Acl::new(
+        AclRevision::ACL_REVISION,
+        AclType::SACL,
+        ControlFlags::empty(),
+        vec![ace],
+    );
+    let sd = SecurityDescriptor::new(None, None, None, Some(sacl));
+    let bytes = sd.to_bytes().unwrap();
+    let decoded = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        decoded.sacl().as_ref().unwrap().ace_list(),
+        sd.sacl().as_ref().unwrap().ace_list()
+    );
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+    decoded
+}
+
+#[test]
+fn alarm_aces() {
+    assert_roundtrip("S:(AL;SA;FA;;;WD)");
+    assert_roundtrip("S:(OL;FA;RP;bf967aba-0de6-11d0-a285-00aa003049e2;;WD)");
+}
+
+#[test]
+fn alarm_callback_aces_have_no_sddl_representation() {
+    // there are no ACE strings for the callback alarm ACEs
+    assert!(
+        SecurityDescriptor::from_sddl(r#"S:(XL;SA;FA;;;WD;(@User.Title == "PM"))"#, None).is_err()
+    );
+
+    let sd =
+        SecurityDescriptor::from_sddl(r#"D:(XA;;FA;;;WD;(@User.Title == "PM"))"#, None).unwrap();
+    let condition = sd.dacl().as_ref().unwrap().ace_list()[0]
+        .application_data()
+        .unwrap()
+        .to_vec();
+    let everyone = Sid::try_from("S-1-1-0").unwrap();
+    let flags = AceHeaderFlags::SUCCESSFUL_ACCESS_ACE_FLAG;
+    for ace in [
+        Ace::system_alarm_callback(flags, *FILE_ALL, everyone.clone(), condition.clone()).unwrap(),
+        Ace::system_alarm_callback_object(flags, *FILE_ALL, None, None, everyone, condition)
+            .unwrap(),
+    ] {
+        let sd = binary_roundtrip(ace);
+        assert!(matches!(sd.to_sddl(), Err(Error::NoSddlRepresentation(_))));
+        assert!(sd.try_sacl_as_sddl_string().is_err());
+        assert!(sd.sacl_as_sddl_string().unwrap().starts_with("S:(#"));
+        assert!(sd.to_string().starts_with("S:(#"), "{sd}");
+    }
+}
+
+#[test]
+fn alarm_object_ace_requires_ds_revision() {
+    let sd = SecurityDescriptor::from_sddl(
+        "S:(OL;FA;RP;bf967aba-0de6-11d0-a285-00aa003049e2;;WD)",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        *sd.sacl().as_ref().unwrap().acl_revision(),
+        AclRevision::ACL_REVISION_DS
+    );
+}
+
+fn compound_ace() -> Ace {
+    Ace::access_allowed_compound(
+        AceHeaderFlags::empty(),
+        *FILE_ALL,
+        Sid::try_from("S-1-5-18").unwrap(),
+        Sid::try_from("S-1-5-32-544").unwrap(),
+    )
+}
+
+#[test]
+fn compound_ace_has_no_sddl_representation() {
+    assert!(SecurityDescriptor::from_sddl("D:(CA;;FA;;;SY;BA)", None).is_err());
+
+    let dacl = Acl::new(
+        AclRevision::ACL_REVISION,
+        AclType::DACL,
+        ControlFlags::empty(),
+        vec![compound_ace()],
+    );
+    assert_eq!(*dacl.acl_revision(), AclRevision::ACL_REVISION3);
+    assert!(matches!(
+        dacl.try_sddl_string(),
+        Err(Error::NoSddlRepresentation(_))
+    ));
+    assert!(dacl.ace_list()[0].to_sddl().is_err());
+    assert_eq!(
+        dacl.ace_list()[0].to_string(),
+        "#04002800ff011f000100000001010000000000051200000001020000000000052000000020020000"
+    );
+    assert_eq!(dacl.to_string(), format!("({})", dacl.ace_list()[0]));
+
+    // the octet string is rejected by the parser, with the position of the ACE
+    let sd = SecurityDescriptor::new(None, None, Some(dacl.clone()), None);
+    let sddl = format!("{sd}(A;;FR;;;WD)");
+    assert!(sddl.starts_with("D:(#0400"), "{sddl}");
+    let Err(Error::ParseError(err)) = SecurityDescriptor::from_sddl(&sddl, None) else {
+        panic!("ACEs written as octet strings must not be parsed");
+    };
+    assert_eq!(err.span().start, 2);
+    assert!(err.reason().as_ref().unwrap().contains("octet string"), "{err}");
+
+    let Ace::ACCESS_ALLOWED_COMPOUND_ACE {
+        compound_ace_type,
+        server_sid,
+        client_sid,
+        ..
+    } = &dacl.ace_list()[0]
+    else {
+        panic!("expected a compound ACE");
+    };
+    assert_eq!(
+        *compound_ace_type,
+        CompoundAceType::COMPOUND_ACE_IMPERSONATION
+    );
+    assert_eq!(server_sid.to_string(), "S-1-5-18");
+    assert_eq!(client_sid.to_string(), "S-1-5-32-544");
+}
+
+#[test]
+fn compound_ace_layout() {
+    #[rustfmt::skip]
+    let bytes = [
+        0x04, 0x00, 0x28, 0x00,                         // type, flags, size
+        0xff, 0x01, 0x1f, 0x00,                         // mask
+        0x01, 0x00, 0x00, 0x00,                         // compound type, reserved
+        0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, // S-1-5-18
+        0x12, 0x00, 0x00, 0x00,
+        0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, // S-1-5-32-544
+        0x20, 0x00, 0x00, 0x00, 0x20, 0x02, 0x00, 0x00,
+    ];
+    let ace = compound_ace();
+
+    let mut cursor = Cursor::new(Vec::new());
+    cursor.write_le(&ace).unwrap();
+    assert_eq!(cursor.into_inner(), bytes);
+
+    let decoded: Ace = Cursor::new(&bytes).read_le().unwrap();
+    assert_eq!(decoded, ace);
+}
+
+#[test]
+fn serde_output() {
+    let sd = SecurityDescriptor::from_sddl("S:(AL;SA;FA;;;WD)", None).unwrap();
+    let json = serde_json::to_value(&sd).unwrap();
+    let ace = &json["sacl"]["ace_list"][0];
+    assert!(ace.get("SYSTEM_ALARM_ACE").is_some(), "{json}");
+
+    let dacl = Acl::new(
+        AclRevision::ACL_REVISION,
+        AclType::DACL,
+        ControlFlags::empty(),
+        vec![compound_ace()],
+    );
+    let sd = SecurityDescriptor::new(None, None, Some(dacl), None);
+    let json = serde_json::to_value(&sd).unwrap();
+    let ace = &json["dacl"]["ace_list"][0]["ACCESS_ALLOWED_COMPOUND_ACE"];
+    assert_eq!(ace["compound_ace_type"], "COMPOUND_ACE_IMPERSONATION");
+    assert!(ace.get("client_sid").is_some(), "{json}");
+}
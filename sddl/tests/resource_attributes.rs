@@ -0,0 +1,89 @@
+use sddl::{
+    AccessMask, Ace, AceHeaderFlags, ClaimAttributeFlags, ClaimValue, ClaimValueType, Error,
+    SecurityDescriptor, Sid,
+};
+
+fn assert_roundtrip(sddl: &str) {
+    let sd = SecurityDescriptor::from_sddl(sddl, None).unwrap();
+    assert_eq!(sd.to_string(), sddl);
+
+    let bytes = sd.to_bytes().unwrap();
+    let decoded = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_string(), sddl);
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn all_value_types() {
+    assert_roundtrip(r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#);
+    assert_roundtrip(r#"S:AI(RA;ID;;;;WD;("Project",TS,0x0,"Windows","SQL"))"#);
+    assert_roundtrip(r#"S:(RA;CI;;;;WD;("Level",TI,0x10000,-3,16))"#);
+    assert_roundtrip(r#"S:(RA;;;;;WD;("Owner",TD,0x0,SID(BA),SID(SY)))"#);
+    assert_roundtrip(r#"S:(RA;;;;;WD;("Reviewed",TB,0x0,1,0))"#);
+    assert_roundtrip(r#"S:(RA;;;;;WD;("Hash",TX,0x0,#00ff10))"#);
+    assert_roundtrip(r#"S:(RA;;;;;WD;("Empty",TS,0x0))"#);
+}
+
+#[test]
+fn flags_and_numbers_are_normalized() {
+    let sd = SecurityDescriptor::from_sddl(r#"S:(RA;;;;;WD;("Secrecy",TU,0,0x3))"#, None).unwrap();
+    assert_eq!(sd.to_string(), r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#);
+}
+
+#[test]
+fn typed_attribute() {
+    let sd = SecurityDescriptor::from_sddl(
+        r#"S:(RA;;;;;WD;("Project",TS,0x20002,"Windows","SQL"))"#,
+        None,
+    )
+    .unwrap();
+    let ace = &sd.sacl().as_ref().unwrap().ace_list()[0];
+    assert!(matches!(ace, Ace::SYSTEM_RESOURCE_ATTRIBUTE_ACE { .. }));
+    assert_eq!(ace.trustee(), Some(&Sid::try_from("S-1-1-0").unwrap()));
+
+    let attribute = ace.resource_attribute().unwrap().unwrap();
+    assert_eq!(attribute.name(), "Project");
+    assert_eq!(attribute.value_type(), ClaimValueType::String);
+    assert_eq!(
+        attribute.flags(),
+        ClaimAttributeFlags::POLICY_DERIVED | ClaimAttributeFlags::VALUE_CASE_SENSITIVE
+    );
+    assert_eq!(
+        attribute.values(),
+        &[ClaimValue::from("Windows"), ClaimValue::from("SQL")]
+    );
+}
+
+#[test]
+fn invalid_values() {
+    for sddl in [
+        r#"S:(RA;;;;;WD;("b",TB,0x0,2))"#,
+        r#"S:(RA;;;;;WD;("u",TU,0x0,-1))"#,
+        r#"S:(RA;;;;;WD;("i",TI,0x0,"a"))"#,
+        r#"S:(RA;;;;;WD;("f",TS,0x100000000))"#,
+        r#"S:(RA;;;;;WD)"#,
+    ] {
+        assert!(SecurityDescriptor::from_sddl(sddl, None).is_err(), "{sddl}");
+    }
+}
+
+#[test]
+fn undecodable_attribute_has_no_sddl_representation() {
+    let ace = Ace::system_resource_attribute(
+        AceHeaderFlags::empty(),
+        AccessMask::empty(),
+        Sid::try_from("S-1-1-0").unwrap(),
+        vec![0xff; 8],
+    )
+    .unwrap();
+    assert!(matches!(ace.to_sddl(), Err(Error::NoSddlRepresentation(_))));
+}
+
+#[test]
+fn serde_output() {
+    let sd = SecurityDescriptor::from_sddl(r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#, None).unwrap();
+    let json = serde_json::to_value(sd.resource_attributes()).unwrap();
+    assert_eq!(json[0]["name"], "Secrecy");
+    assert_eq!(json[0]["value_type"], "UInt64");
+    assert_eq!(json[0]["values"][0]["UInt64"], 3);
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use sddl::{ActiveDirectorySchema, Guid, GuidResolver, NameContext, SecurityDescriptor};
+
+#[test]
+fn object_ace_names_in_json() {
+    let sd = SecurityDescriptor::from_sddl(
+        "D:(OA;;CR;1131f6ad-9c07-11d1-f79f-00c04fc2dcd2;;S-1-5-21-1-2-3-1104)\
+         (OA;CIIO;RP;4c164200-20c0-11d0-a768-00aa006e0529;bf967aba-0de6-11d0-a285-00aa003049e2;RU)\
+         (OA;;CR;00000000-1111-2222-3333-444444444444;;AU)",
+        None,
+    )
+    .unwrap();
+    let context = Arc::new(NameContext::new());
+    let json = context.scope(|| serde_json::to_value(&sd).unwrap());
+    let aces = json["dacl"]["ace_list"].as_array().unwrap();
+    let object_type =
+        |idx: usize| &aces[idx].as_object().unwrap().values().next().unwrap()["object_type"];
+    let inherited_object_type = |idx: usize| {
+        &aces[idx].as_object().unwrap().values().next().unwrap()["inherited_object_type"]
+    };
+
+    assert_eq!(object_type(0)["name"], "DS-Replication-Get-Changes-All");
+    assert_eq!(object_type(0)["kind"], "extended-right");
+    assert_eq!(object_type(1)["name"], "User-Account-Restrictions");
+    assert_eq!(inherited_object_type(1)["name"], "user");
+    assert_eq!(inherited_object_type(1)["kind"], "class");
+
+    // unknown GUIDs are written as plain strings
+    assert_eq!(object_type(2), "00000000-1111-2222-3333-444444444444");
+}
+
+#[test]
+fn object_ace_guids_in_json_without_names() {
+    let sd = SecurityDescriptor::from_sddl(
+        "D:(OA;;CR;1131f6ad-9c07-11d1-f79f-00c04fc2dcd2;;S-1-5-21-1-2-3-1104)",
+        None,
+    )
+    .unwrap();
+    let json = serde_json::to_value(&sd).unwrap();
+    let ace = &json["dacl"]["ace_list"][0];
+    let object_type = &ace.as_object().unwrap().values().next().unwrap()["object_type"];
+    assert_eq!(object_type, "1131f6ad-9c07-11d1-f79f-00c04fc2dcd2");
+}
+
+#[test]
+fn guid_from_json() {
+    let guid = Guid::try_from("bf967a9c-0de6-11d0-a285-00aa003049e2").unwrap();
+    let json = serde_json::to_value(guid).unwrap();
+    assert_eq!(json, "bf967a9c-0de6-11d0-a285-00aa003049e2");
+    assert_eq!(serde_json::from_value::<Guid>(json).unwrap(), guid);
+
+    let context = Arc::new(NameContext::new());
+    let json = context.scope(|| serde_json::to_value(guid).unwrap());
+    assert_eq!(json["name"], "group");
+    assert_eq!(serde_json::from_value::<Guid>(json).unwrap(), guid);
+    assert_eq!(
+        serde_json::from_str::<Guid>(r#""bf967a9c-0de6-11d0-a285-00aa003049e2""#).unwrap(),
+        guid
+    );
+    assert_eq!(
+        ActiveDirectorySchema::builtin().resolve_guid(&guid),
+        guid.schema_entry()
+    );
+}
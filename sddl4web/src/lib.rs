@@ -1,11 +1,36 @@
 mod utils;
 mod error;
 
+use std::{str::FromStr, sync::Arc};
+
 use wasm_bindgen::prelude::*;
-use sddl::{SecurityDescriptor, Sid};
+use sddl::{NameContext, NameFormat, SecurityDescriptor, Sid};
 
 #[wasm_bindgen]
 pub fn convert(sddl: &str, domain_sid: &str) -> Result<String, error::Error> {
+    convert_in_context(sddl, domain_sid, NameContext::new())
+}
+
+/// converts `sddl` like [`convert`], but displays the names of SIDs and GUIDs
+/// which are found in `names`, which is an export of a directory in the format
+/// `format` (`ldif`, `csv` or `json`)
+#[wasm_bindgen]
+pub fn convert_with_names(
+    sddl: &str,
+    domain_sid: &str,
+    names: &str,
+    format: &str,
+) -> Result<String, error::Error> {
+    let format = NameFormat::from_str(format)
+        .map_err(|_| sddl::Error::IllegalNameMap(format!("unknown format: {format}")))?;
+    convert_in_context(sddl, domain_sid, NameContext::from_data(format, names)?)
+}
+
+fn convert_in_context(
+    sddl: &str,
+    domain_sid: &str,
+    context: NameContext,
+) -> Result<String, error::Error> {
 
     let sid = Sid::try_from(domain_sid)?;
     let sub_authorities = sid.sub_authority();
@@ -15,5 +40,5 @@ pub fn convert(sddl: &str, domain_sid: &str) -> Result<String, error::Error> {
         None
     };
     let sd = SecurityDescriptor::from_sddl(sddl, domain_rid)?;
-    Ok(serde_json::to_string_pretty(&sd)?)
-}
\ No newline at end of file
+    Ok(Arc::new(context).scope(|| serde_json::to_string_pretty(&sd))?)
+}